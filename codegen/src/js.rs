@@ -22,11 +22,18 @@ pub struct Ctx<'a> {
 	file: &'a DeclFile,
 	provider: &'a FSProvider,
 	path_map: &'a PathMap<'a>,
+	/// generic parameters of the current item
+	params: &'a [String],
+	/// whether to skip encoding fields equal to their default value
+	skip_defaults: bool,
+	/// whether to encode the fingerprint of the root type in the header
+	fingerprints: bool,
 }
 
 /// generate serialization code for rust lang
 pub fn to_js(
-	inputs: &Vec<Entry>, in_dir: &str, out_dir: &Path, provider: &FSProvider,
+	inputs: &Vec<Entry>, in_dir: &str, out_dir: &Path, provider: &FSProvider, skip_defaults: bool,
+	fingerprints: bool,
 ) -> Result<(), String> {
 	// prepare path map
 	let mut path_map = HashMap::new();
@@ -36,7 +43,8 @@ pub fn to_js(
 	// generate files
 	for Entry { resolved_path, rel_path, decl, .. } in inputs {
 		let mut source = String::new();
-		let ctx = Ctx { file: decl, provider, path_map: &path_map };
+		let path_map = &path_map;
+		let ctx = Ctx { file: decl, provider, path_map, params: &[], skip_defaults, fingerprints };
 
 		// write header and imports
 		write!(source, "// generated from file: {}\n\n", &decl.name).unwrap();
@@ -64,7 +72,7 @@ pub fn to_js(
 
 	// generate root mod
 	let root_path = absolute(out_dir.join("index.ts")).unwrap();
-	write(&root_path, gen_root(inputs, in_dir))
+	write(&root_path, gen_root(inputs, in_dir, provider))
 		.map_err(|_| format!("unable to write file \"{}\"", root_path.display()))?;
 
 	Ok(())
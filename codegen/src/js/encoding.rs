@@ -1,6 +1,9 @@
 use crate::{
-	js::Ctx,
-	utils::{encode_header, new_size_ind},
+	js::{
+		Ctx,
+		type_def::{generics, write_literal},
+	},
+	utils::{encode_header, flat_name, new_size_ind},
 };
 use std::fmt::Write;
 
@@ -11,27 +14,41 @@ pub fn gen_encoding(source: &mut String, rel_path: &str, ctx: &Ctx) {
 	let Ctx { file, .. } = ctx;
 
 	for (_, item) in &file.items {
-		write!(source, "export function encode_{0}(value: {0}) {{\n", item.name()).unwrap();
-		source.push_str("\tlet _buf = new Uint8Array(256);\n");
-		source.push_str("\tlet buf = { buf: _buf, pos: 0, view: new DataView(_buf.buffer) };\n");
-		source.push_str("\tenc.encode_u8_arr(buf, [");
-		encode_header(source, rel_path, item.typeid() as u64);
-		source.push_str("]);\n");
-		write!(source, "\tencode_int_{}(buf, value);\n", item.name()).unwrap();
-		source.push_str("\treturn buf.buf.slice(0, buf.pos);\n");
-		source.push_str("}\n\n");
+		let ctx = &Ctx { params: item.params(), ..*ctx };
+
+		// generic items have no standalone binary representation
+		if ctx.params.is_empty() {
+			write!(source, "export function encode_{0}(value: {0}) {{\n", flat_name(item.name()))
+				.unwrap();
+			source.push_str("\tlet _buf = new Uint8Array(256);\n");
+			source
+				.push_str("\tlet buf = { buf: _buf, pos: 0, view: new DataView(_buf.buffer) };\n");
+			source.push_str("\tenc.encode_u8_arr(buf, [");
+			let fingerprint = ctx.fingerprints.then(|| item.fingerprint(ctx.provider));
+			encode_header(source, rel_path, item.typeid() as u64, fingerprint);
+			source.push_str("]);\n");
+			write!(source, "\tencode_int_{}(buf, value);\n", flat_name(item.name())).unwrap();
+			source.push_str("\treturn buf.buf.slice(0, buf.pos);\n");
+			source.push_str("}\n\n");
+		}
 
 		match item {
 			DeclItem::Struct { name, def, .. } => {
-				#[rustfmt::skip]
-				write!(source, "export function encode_int_{name}(buf: Buffer, value: {name}) {{\n")
+				let name = flat_name(name);
+				let generics = generics(ctx.params);
+				write!(source, "export function encode_int_{name}{generics}(buf: Buffer, ")
 					.unwrap();
+				write!(source, "value: {name}{generics}").unwrap();
+				write_params_encoders(source, ctx);
+				source.push_str(") {\n");
 				encode_struct(source, def, ctx);
 				source.push_str("}\n");
 
-				write!(source, "export function decode_{name}(buf: Buffer, cur: Cursor)").unwrap();
-				write!(source, ": {name} {{\n").unwrap();
-				write!(source, "let value = {{}} as any as {name};\n").unwrap();
+				write!(source, "export function decode_{name}{generics}(buf: Buffer, cur: Cursor")
+					.unwrap();
+				write_params_decoders(source, ctx);
+				write!(source, "): {name}{generics} {{\n").unwrap();
+				write!(source, "let value = {{}} as any as {name}{generics};\n").unwrap();
 				decode_struct(source, def, ctx);
 				source.push_str("}\n\n");
 			}
@@ -40,6 +57,26 @@ pub fn gen_encoding(source: &mut String, rel_path: &str, ctx: &Ctx) {
 
 				decode_enum(source, item, ctx);
 			}
+			DeclItem::Flags { name, flags, .. } => {
+				let name = flat_name(name);
+				// flag names indexed by bit
+				let mut bits = String::new();
+				for flag in flags {
+					match flag {
+						Some(flag) => write!(bits, "'{flag}', ").unwrap(),
+						None => bits.push_str("undefined, "),
+					}
+				}
+
+				#[rustfmt::skip]
+				write!(source, "export function encode_int_{name}(buf: Buffer, value: {name}) {{\n")
+					.unwrap();
+				write!(source, "\tenc.encode_flags(buf, value, [{bits}]);\n}}\n").unwrap();
+
+				write!(source, "export function decode_{name}(buf: Buffer, cur: Cursor)").unwrap();
+				write!(source, ": {name} {{\n").unwrap();
+				write!(source, "\treturn enc.decode_flags(buf, cur, [{bits}]);\n}}\n\n").unwrap();
+			}
 		}
 	}
 }
@@ -47,9 +84,14 @@ pub fn gen_encoding(source: &mut String, rel_path: &str, ctx: &Ctx) {
 /// generate encoding function for enum
 fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let name = flat_name(name);
+	let generics = generics(ctx.params);
 
 	// fn decleration
-	write!(source, "export function encode_int_{name}(buf: Buffer, value: {name}) {{\n").unwrap();
+	write!(source, "export function encode_int_{name}{generics}(buf: Buffer, ").unwrap();
+	write!(source, "value: {name}{generics}").unwrap();
+	write_params_encoders(source, ctx);
+	source.push_str(") {\n");
 	// encode based on variant
 	source.push_str("\tswitch (value.type) {");
 	for variant in variants.iter().filter_map(|v| v.as_ref()) {
@@ -58,7 +100,9 @@ fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 		if variant.def.is_some() {
 			write!(source, "\n\t\tcase '{var_name}':  {{\n").unwrap();
 			write!(source, "\t\t\tenc.encode_vuint(buf, {tag});\n",).unwrap();
-			write!(source, "\t\t\treturn encode_{name}_{var_name}(buf, value);\n",).unwrap();
+			write!(source, "\t\t\treturn encode_{name}_{var_name}(buf, value").unwrap();
+			write_params_coders(source, "encode", ctx);
+			source.push_str(");\n");
 			source.push_str("\t\t}");
 		// case unit enum
 		} else {
@@ -71,8 +115,10 @@ fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	// generate encode functions for variants with fields
 	for variant in variants.iter().filter_map(|v| v.as_ref().filter(|v| v.def.is_some())) {
 		let EnumVariant { name: var_name, def: Some(def), .. } = variant else { unreachable!() };
-		write!(source, "export function encode_{name}_{var_name}(buf: Buffer, ").unwrap();
-		write!(source, "value: {name} & {{ type: '{var_name}' }}) {{\n").unwrap();
+		write!(source, "export function encode_{name}_{var_name}{generics}(buf: Buffer, ").unwrap();
+		write!(source, "value: {name}{generics} & {{ type: '{var_name}' }}").unwrap();
+		write_params_encoders(source, ctx);
+		source.push_str(") {\n");
 		encode_struct(source, def, ctx);
 		source.push_str("}\n");
 	}
@@ -80,10 +126,11 @@ fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 
 /// generate encoding function for struct
 fn encode_struct(source: &mut String, def: &StructDef, ctx: &Ctx) {
-	// split fields into optional and required
+	// split fields into optional and required, fields equal to their default can be skipped
 	#[rustfmt::skip]
 	let (opt_fields, req_fields): (Vec<_>, Vec<_>) = 
-		def.fields.iter().filter_map(|f| f.as_ref()).partition(|field| field.is_optional);
+		def.fields.iter().filter_map(|f| f.as_ref()).partition(|field| field.is_optional
+			|| is_skippable(field, ctx));
 
 	// encode fields count
 	// case only required, direct count
@@ -93,7 +140,9 @@ fn encode_struct(source: &mut String, def: &StructDef, ctx: &Ctx) {
 	} else {
 		write!(source, "\tenc.encode_vuint(buf, {}\n", req_fields.len()).unwrap();
 		for field in &opt_fields {
-			write!(source, "\t\t+ ('{}' in value ? 1 : 0)\n", field.name).unwrap();
+			source.push_str("\t\t+ (");
+			write_field_condition(source, field, ctx);
+			source.push_str(" ? 1 : 0)\n");
 		}
 		source.push_str("\t);\n");
 	}
@@ -104,11 +153,46 @@ fn encode_struct(source: &mut String, def: &StructDef, ctx: &Ctx) {
 	}
 	// encode optional fields if have value
 	for field in opt_fields {
-		write!(source, "\tif ('{}' in value) {{\n", field.name).unwrap();
+		source.push_str("\tif (");
+		write_field_condition(source, field, ctx);
+		source.push_str(") {\n");
 		encode_field(source, &field, ctx);
 		source.push_str("\t}\n");
 	}
 }
+/// whether the field is skipped when equal to its default value
+/// only primitives are skipped, since they are compared by value
+fn is_skippable(field: &Field, ctx: &Ctx) -> bool {
+	let Field { typeid, default, .. } = field;
+	ctx.skip_defaults
+		&& default.is_some()
+		&& typeid.ns == 0
+		&& matches!(
+			typeid.id,
+			BOOL_TYPEID
+				| STR_TYPEID | U8_TYPEID
+				| U16_TYPEID | U32_TYPEID
+				| U64_TYPEID | I8_TYPEID
+				| I16_TYPEID | I32_TYPEID
+				| I64_TYPEID | F32_TYPEID
+				| F64_TYPEID | VUINT_TYPEID
+				| VINT_TYPEID
+		)
+}
+/// write the condition of encoding an optional or skippable field
+fn write_field_condition(source: &mut String, field: &Field, ctx: &Ctx) {
+	let name = &field.name;
+	match (field.is_optional, is_skippable(field, ctx)) {
+		(true, false) => write!(source, "'{name}' in value").unwrap(),
+		(is_optional, _) => {
+			if is_optional {
+				write!(source, "'{name}' in value && ").unwrap();
+			}
+			write!(source, "value.{name} !== ").unwrap();
+			write_literal(source, field.default.as_ref().unwrap(), &field.typeid, ctx);
+		}
+	}
+}
 // encode code for common field value types
 // encode header then value
 fn encode_simple_value(source: &mut String, ty: &str, name: &str, tag: u32, size: u32) {
@@ -134,7 +218,7 @@ fn encode_sized_value(source: &mut String, tag: u32, encoder: impl Fn(&mut Strin
 	write!(source, "{size_ind_inst} - 2, size_ind_{size_ind_inst}, 2);\n").unwrap();
 }
 // generate fn that encode primitive types
-fn write_primitive_encoder(source: &mut String, typeid: u8) {
+fn write_primitive_encoder(source: &mut String, typeid: u16) {
 	match typeid {
 		ANY_TYPEID => source.push_str("enc.encode_any"),
 
@@ -161,56 +245,124 @@ fn write_primitive_encoder(source: &mut String, typeid: u8) {
 		INST_TYPEID => source.push_str("enc.encode_inst"),
 		INSTN_TYPEID => source.push_str("enc.encode_instN"),
 		DUR_TYPEID => source.push_str("enc.encode_dur"),
+		DEC_TYPEID => source.push_str("enc.encode_dec"),
+		INSTZ_TYPEID => source.push_str("enc.encode_instZ"),
+		DATE_TYPEID => source.push_str("enc.encode_date"),
+		TIME_TYPEID => source.push_str("enc.encode_time"),
 		UUID_TYPEID => source.push_str("enc.encode_uuid"),
 		_ => (),
 	}
 }
 /// write fn that encode specific type
 fn write_value_encoder(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
-	let Ctx { file, path_map, provider } = ctx;
+	let Ctx { file, path_map, provider, .. } = ctx;
 	// builtins
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ARR_TYPEID => {
 				source.push_str("(buf, value) => enc.encode_arr(buf, value, ");
 				// item encoder
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			SET_TYPEID => {
+				source.push_str("(buf, value) => enc.encode_set(buf, value, ");
+				// item encoder
+				write_primitive_encoder(source, typeid.item.as_ref().unwrap().id);
+				source.push_str(")");
+			}
+			FIXED_ARR_TYPEID => {
+				write!(
+					source,
+					"(buf, value) => enc.encode_fixed_arr(buf, value, {}, ",
+					typeid.variant
+				)
+				.unwrap();
+				// item encoder
+				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push_str(")");
+			}
+			TUPLE_TYPEID => {
+				source.push_str("(buf, value) => enc.encode_tuple(buf, value, ");
+				write_tuple_encoders(source, typeid, ctx);
+				source.push_str(")");
+			}
 			MAP_TYPEID => {
 				source.push_str("(buf, value) => enc.encode_map(buf, value, ");
 				// key encoder
-				write_primitive_encoder(source, typeid.variant as u8);
+				write_primitive_encoder(source, typeid.variant);
 				source.push_str(", ");
 				// value encoder
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			PARAM_TYPEID => {
+				write!(source, "encode_{}", ctx.params[typeid.variant as usize]).unwrap()
+			}
 			id => write_primitive_encoder(source, id),
 		}
 	// user defined
 	} else {
+		// generic types are encoded with their arguments encoders
+		let is_generic = !typeid.args.is_empty();
+		if is_generic {
+			source.push_str("(buf, value) => ");
+		}
 		// same file
 		if typeid.ns == file.id {
-			write!(source, "encode_int_{}", file.get_by_id(typeid.id).unwrap().name()).unwrap();
+			write!(source, "encode_int_{}", flat_name(file.get_by_id(typeid.id).unwrap().name()))
+				.unwrap();
 		// different file
 		} else {
 			// write ns.encode_type
-			let file = provider.get_by_id(typeid.ns);
+			let file = provider.get(typeid.ns);
 			source.push_str("ns_");
 			source.push_str(path_map.get(&typeid.ns).unwrap());
 			source.push_str(".encode_int_");
-			source.push_str(file.get_by_id(typeid.id).unwrap().name());
+			source.push_str(&flat_name(file.get_by_id(typeid.id).unwrap().name()));
+		}
+		if is_generic {
+			source.push_str("(buf, value");
+			write_args_encoders(source, typeid, ctx);
+			source.push(')');
 		}
 	}
 }
+/// write the type arguments encoders of a generic type
+fn write_args_encoders(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	for arg in &typeid.args {
+		source.push_str(", ");
+		write_value_encoder(source, arg, ctx);
+	}
+}
+/// write the parameters encoders of the current generic item
+fn write_params_encoders(source: &mut String, ctx: &Ctx) {
+	for param in ctx.params {
+		write!(source, ", encode_{param}: (buf: Buffer, value: {param}) => void").unwrap();
+	}
+}
+/// pass the parameters coders of the current generic item
+fn write_params_coders(source: &mut String, prefix: &str, ctx: &Ctx) {
+	for param in ctx.params {
+		write!(source, ", {prefix}_{param}").unwrap();
+	}
+}
+/// write list of the tuple items encoders
+fn write_tuple_encoders(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	source.push('[');
+	for (ind, item) in typeid.args.iter().enumerate() {
+		source.push_str(if ind == 0 { "" } else { ", " });
+		write_value_encoder(source, item, ctx);
+	}
+	source.push(']');
+}
 /// generate encode code for a field
 fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
-	let Ctx { file, path_map, provider } = ctx;
+	let Ctx { file, path_map, provider, .. } = ctx;
 	let Field { name, typeid, tag, .. } = field;
 	// builtins
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ANY_TYPEID => encode_sized_value(source, *tag, |source| {
 				write!(source, "enc.encode_any(buf, value.{name})").unwrap()
 			}),
@@ -241,10 +393,28 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(", true)");
 			}),
+			SET_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "enc.encode_set(buf, value.{name}, ").unwrap();
+				// item encoder
+				write_primitive_encoder(source, typeid.item.as_ref().unwrap().id);
+				source.push_str(", true)");
+			}),
+			FIXED_ARR_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "enc.encode_fixed_arr(buf, value.{name}, {}, ", typeid.variant)
+					.unwrap();
+				// item encoder
+				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push(')');
+			}),
+			TUPLE_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "enc.encode_tuple(buf, value.{name}, ").unwrap();
+				write_tuple_encoders(source, typeid, ctx);
+				source.push(')');
+			}),
 			MAP_TYPEID => encode_sized_value(source, *tag, |source| {
 				write!(source, "enc.encode_map(buf, value.{name}, ").unwrap();
 				// key encoder
-				write_primitive_encoder(source, typeid.variant as u8);
+				write_primitive_encoder(source, typeid.variant);
 				source.push_str(", ");
 				// value encoder
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
@@ -254,7 +424,18 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 			INST_TYPEID => encode_simple_value(source, "inst", name, *tag, 0b011),
 			INSTN_TYPEID => encode_compound_value(source, "instN", name, *tag, 12),
 			DUR_TYPEID => encode_simple_value(source, "dur", name, *tag, 0b011),
+			DEC_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "enc.encode_dec(buf, value.{name})").unwrap();
+			}),
 			UUID_TYPEID => encode_compound_value(source, "uuid", name, *tag, 16),
+			INSTZ_TYPEID => encode_compound_value(source, "instZ", name, *tag, 10),
+			DATE_TYPEID => encode_simple_value(source, "date", name, *tag, 0b010),
+			TIME_TYPEID => encode_simple_value(source, "time", name, *tag, 0b011),
+			// generic fields are always sized
+			PARAM_TYPEID => encode_sized_value(source, *tag, |source| {
+				let param = &ctx.params[typeid.variant as usize];
+				write!(source, "encode_{param}(buf, value.{name})").unwrap();
+			}),
 			_ => unreachable!(),
 		}
 	// user defined types
@@ -262,17 +443,19 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 		encode_sized_value(source, *tag, |source| {
 			// same file
 			if typeid.ns == file.id {
-				let item_name = file.get_by_id(typeid.id).unwrap().name();
-				write!(source, "encode_int_{item_name}(buf, value.{name})",).unwrap();
+				let item_name = flat_name(file.get_by_id(typeid.id).unwrap().name());
+				write!(source, "encode_int_{item_name}(buf, value.{name}",).unwrap();
 			// different file
 			} else {
 				// write ns.encode_value
-				let file = provider.get_by_id(typeid.ns);
-				let item_name = file.get_by_id(typeid.id).unwrap().name();
+				let file = provider.get(typeid.ns);
+				let item_name = flat_name(file.get_by_id(typeid.id).unwrap().name());
 				source.push_str("ns_");
 				source.push_str(path_map.get(&typeid.ns).unwrap());
-				write!(source, ".encode_int_{item_name}(buf, value.{name})").unwrap();
+				write!(source, ".encode_int_{item_name}(buf, value.{name}").unwrap();
 			}
+			write_args_encoders(source, typeid, ctx);
+			source.push(')');
 		});
 	};
 }
@@ -280,16 +463,22 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 /// generate decoding function for enum
 fn decode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let name = flat_name(name);
+	let generics = generics(ctx.params);
 
 	// main function
-	write!(source, "export function decode_{name}(buf: Buffer, cur: Cursor): {name} {{\n").unwrap();
+	write!(source, "export function decode_{name}{generics}(buf: Buffer, cur: Cursor").unwrap();
+	write_params_decoders(source, ctx);
+	write!(source, "): {name}{generics} {{\n").unwrap();
 
 	// switch on tag
 	write!(source, "\tswitch (enc.decode_vuint(buf, cur) as number) {{\n").unwrap();
 	for variant in variants.iter().filter_map(|v| v.as_ref()) {
 		let EnumVariant { name: var_name, tag, def, .. } = variant;
 		if def.is_some() {
-			write!(source, "\t\tcase {tag}: return decode_{name}_{var_name}(buf, cur);\n").unwrap();
+			write!(source, "\t\tcase {tag}: return decode_{name}_{var_name}(buf, cur").unwrap();
+			write_params_coders(source, "decode", ctx);
+			source.push_str(");\n");
 		} else {
 			write!(source, "\t\tcase {tag}: return {{ type: '{var_name}' }};\n").unwrap();
 		}
@@ -299,10 +488,15 @@ fn decode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	// generate decode functions for variants with fields
 	for variant in variants.iter().filter_map(|v| v.as_ref().filter(|v| v.def.is_some())) {
 		let EnumVariant { name: var_name, def: Some(def), .. } = variant else { unreachable!() };
-		write!(source, "export function decode_{name}_{var_name}(buf: Buffer, cur: Cursor) {{\n")
-			.unwrap();
+		write!(
+			source,
+			"export function decode_{name}_{var_name}{generics}(buf: Buffer, cur: Cursor"
+		)
+		.unwrap();
+		write_params_decoders(source, ctx);
+		source.push_str(") {\n");
 		write!(source, "let value = {{ type: '{var_name}' }} as any ").unwrap();
-		write!(source, "as {name} & {{ type: '{var_name}' }};\n").unwrap();
+		write!(source, "as {name}{generics} & {{ type: '{var_name}' }};\n").unwrap();
 		decode_struct(source, def, ctx);
 		source.push_str("}\n");
 	}
@@ -338,6 +532,13 @@ fn decode_struct(source: &mut String, def: &StructDef, ctx: &Ctx) {
 	source.push_str(" else { enc.skip_field(buf, cur, header) }\n");
 	source.push_str("\t}\n");
 
+	// absent fields take their default value
+	for field in fields.iter().filter(|f| f.default.is_some()) {
+		write!(source, "\tif (!('{0}' in value)) value.{0} = ", field.name).unwrap();
+		write_literal(source, field.default.as_ref().unwrap(), &field.typeid, ctx);
+		source.push_str(";\n");
+	}
+
 	source.push_str("\treturn value;\n");
 }
 
@@ -350,7 +551,7 @@ fn decode_compound_value(source: &mut String, name: &str, ty: &str) {
 	write!(source, "\t\t\tvalue.{name} = enc.decode_{ty}(buf, cur);\n").unwrap();
 }
 /// generate fn that decode primitive types
-fn write_primitive_decoder(source: &mut String, typeid: u8) {
+fn write_primitive_decoder(source: &mut String, typeid: u16) {
 	match typeid {
 		ANY_TYPEID => source.push_str("enc.decode_any"),
 
@@ -376,47 +577,103 @@ fn write_primitive_decoder(source: &mut String, typeid: u8) {
 		INST_TYPEID => source.push_str("enc.decode_inst"),
 		INSTN_TYPEID => source.push_str("enc.decode_instN"),
 		DUR_TYPEID => source.push_str("enc.decode_dur"),
+		DEC_TYPEID => source.push_str("enc.decode_dec"),
+		INSTZ_TYPEID => source.push_str("enc.decode_instZ"),
+		DATE_TYPEID => source.push_str("enc.decode_date"),
+		TIME_TYPEID => source.push_str("enc.decode_time"),
 		UUID_TYPEID => source.push_str("enc.decode_uuid"),
 		_ => (),
 	}
 }
 /// write a fn that decode a value
 fn write_value_decoder(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
-	let Ctx { file, path_map, provider } = ctx;
+	let Ctx { file, path_map, provider, .. } = ctx;
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ARR_TYPEID => {
 				source.push_str("(buf, cur) => enc.decode_arr(buf, cur, ");
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			SET_TYPEID => {
+				source.push_str("(buf, cur) => enc.decode_set(buf, cur, ");
+				write_primitive_decoder(source, typeid.item.as_ref().unwrap().id);
+				source.push_str(")");
+			}
+			FIXED_ARR_TYPEID => {
+				write!(source, "(buf, cur) => enc.decode_fixed_arr(buf, cur, {}, ", typeid.variant)
+					.unwrap();
+				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push_str(")");
+			}
+			TUPLE_TYPEID => {
+				source.push_str("(buf, cur) => enc.decode_tuple(buf, cur, ");
+				write_tuple_decoders(source, typeid, ctx);
+				source.push_str(")");
+			}
 			MAP_TYPEID => {
 				source.push_str("(buf, cur) => enc.decode_map(buf, cur, ");
-				write_primitive_decoder(source, typeid.variant as u8);
+				write_primitive_decoder(source, typeid.variant);
 				source.push_str(", ");
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			PARAM_TYPEID => {
+				write!(source, "decode_{}", ctx.params[typeid.variant as usize]).unwrap()
+			}
 			id => write_primitive_decoder(source, id),
 		}
 	} else {
+		// generic types are decoded with their arguments decoders
+		let is_generic = !typeid.args.is_empty();
+		if is_generic {
+			source.push_str("(buf, cur) => ");
+		}
 		if typeid.ns == file.id {
-			write!(source, "decode_{}", file.get_by_id(typeid.id).unwrap().name()).unwrap();
+			write!(source, "decode_{}", flat_name(file.get_by_id(typeid.id).unwrap().name()))
+				.unwrap();
 		} else {
-			let file = provider.get_by_id(typeid.ns);
+			let file = provider.get(typeid.ns);
 			source.push_str("ns_");
 			source.push_str(path_map.get(&typeid.ns).unwrap());
 			source.push_str(".decode_");
-			source.push_str(file.get_by_id(typeid.id).unwrap().name());
+			source.push_str(&flat_name(file.get_by_id(typeid.id).unwrap().name()));
+		}
+		if is_generic {
+			source.push_str("(buf, cur");
+			write_args_decoders(source, typeid, ctx);
+			source.push(')');
 		}
 	}
 }
+/// write the type arguments decoders of a generic type
+fn write_args_decoders(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	for arg in &typeid.args {
+		source.push_str(", ");
+		write_value_decoder(source, arg, ctx);
+	}
+}
+/// write the parameters decoders of the current generic item
+fn write_params_decoders(source: &mut String, ctx: &Ctx) {
+	for param in ctx.params {
+		write!(source, ", decode_{param}: (buf: Buffer, cur: Cursor) => {param}").unwrap();
+	}
+}
+/// write list of the tuple items decoders
+fn write_tuple_decoders(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	source.push('[');
+	for (ind, item) in typeid.args.iter().enumerate() {
+		source.push_str(if ind == 0 { "" } else { ", " });
+		write_value_decoder(source, item, ctx);
+	}
+	source.push(']');
+}
 /// decode code for one field
 fn decode_field(source: &mut String, name: &str, typeid: &TypeId, ctx: &Ctx) {
-	let Ctx { file, provider, path_map } = ctx;
+	let Ctx { file, provider, path_map, .. } = ctx;
 	// builtins
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ANY_TYPEID => decode_compound_value(source, name, "any"),
 			BOOL_TYPEID => decode_simple_value(source, name, "bool"),
 
@@ -441,20 +698,47 @@ fn decode_field(source: &mut String, name: &str, typeid: &TypeId, ctx: &Ctx) {
 			INST_TYPEID => decode_simple_value(source, name, "inst"),
 			INSTN_TYPEID => decode_compound_value(source, name, "instN"),
 			DUR_TYPEID => decode_simple_value(source, name, "dur"),
+			DEC_TYPEID => decode_compound_value(source, name, "dec"),
 			UUID_TYPEID => decode_compound_value(source, name, "uuid"),
+			INSTZ_TYPEID => decode_compound_value(source, name, "instZ"),
+			DATE_TYPEID => decode_simple_value(source, name, "date"),
+			TIME_TYPEID => decode_simple_value(source, name, "time"),
 
 			ARR_TYPEID => {
 				write!(source, "\t\t\tvalue.{name} = enc.decode_arr(buf, cur, ").unwrap();
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(", true);\n");
 			}
+			SET_TYPEID => {
+				write!(source, "\t\t\tvalue.{name} = enc.decode_set(buf, cur, ").unwrap();
+				write_primitive_decoder(source, typeid.item.as_ref().unwrap().id);
+				source.push_str(", true);\n");
+			}
+			FIXED_ARR_TYPEID => {
+				source.push_str("\t\t\tenc.decode_vuint(buf, cur);\n");
+				write!(source, "\t\t\tvalue.{name} = enc.decode_fixed_arr(buf, cur, ").unwrap();
+				write!(source, "{}, ", typeid.variant).unwrap();
+				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push_str(");\n");
+			}
+			TUPLE_TYPEID => {
+				source.push_str("\t\t\tenc.decode_vuint(buf, cur);\n");
+				write!(source, "\t\t\tvalue.{name} = enc.decode_tuple(buf, cur, ").unwrap();
+				write_tuple_decoders(source, typeid, ctx);
+				source.push_str(");\n");
+			}
 			MAP_TYPEID => {
 				write!(source, "\t\t\tvalue.{name} = enc.decode_map(buf, cur, ").unwrap();
-				write_primitive_decoder(source, typeid.variant as u8);
+				write_primitive_decoder(source, typeid.variant);
 				source.push_str(", ");
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(", true);\n");
 			}
+			PARAM_TYPEID => {
+				let param = &ctx.params[typeid.variant as usize];
+				source.push_str("\t\t\tenc.decode_vuint(buf, cur);\n");
+				write!(source, "\t\t\tvalue.{name} = decode_{param}(buf, cur);\n").unwrap();
+			}
 			_ => (),
 		}
 	// user defined
@@ -462,16 +746,18 @@ fn decode_field(source: &mut String, name: &str, typeid: &TypeId, ctx: &Ctx) {
 		// same file
 		if typeid.ns == file.id {
 			source.push_str("\t\t\tenc.decode_vuint(buf, cur);\n");
-			let type_name = file.get_by_id(typeid.id).unwrap().name();
-			write!(source, "\t\t\tvalue.{name} = decode_{type_name}(buf, cur);\n",).unwrap();
+			let type_name = flat_name(file.get_by_id(typeid.id).unwrap().name());
+			write!(source, "\t\t\tvalue.{name} = decode_{type_name}(buf, cur",).unwrap();
 		// different file
 		} else {
-			let file = provider.get_by_id(typeid.ns);
+			let file = provider.get(typeid.ns);
 			source.push_str("\t\t\tenc.decode_vuint(buf, cur);\n");
 			write!(source, "\t\t\tvalue.{name} = ns_").unwrap();
 			source.push_str(path_map.get(&typeid.ns).unwrap());
-			let type_name = file.get_by_id(typeid.id).unwrap().name();
-			write!(source, ".decode_{type_name}(buf, cur);\n",).unwrap();
+			let type_name = flat_name(file.get_by_id(typeid.id).unwrap().name());
+			write!(source, ".decode_{type_name}(buf, cur",).unwrap();
 		}
+		write_args_decoders(source, typeid, ctx);
+		source.push_str(");\n");
 	}
 }
@@ -1,8 +1,9 @@
-use crate::Entry;
-use std::fmt::Write;
+use crate::{Entry, utils::flat_name};
+use std::{collections::HashSet, fmt::Write};
+use structom::FSProvider;
 
 /// generate root mod
-pub fn gen_root(inputs: &Vec<Entry>, in_dir: &str) -> String {
+pub fn gen_root(inputs: &Vec<Entry>, in_dir: &str, provider: &FSProvider) -> String {
 	let mut source = String::new();
 	// header
 	write!(source, "// generated from {in_dir}\n").unwrap();
@@ -18,16 +19,35 @@ pub fn gen_root(inputs: &Vec<Entry>, in_dir: &str) -> String {
 	source.push_str("\tlet buf = { buf: new Uint8Array(data), pos: 0, view: new DataView(data) };");
 	source.push_str("\n\tlet cur = { pos: 0 };\n");
 
-	// match decl_path
-	source.push_str("\tswitch (enc.decode_str(buf, cur)) {\n");
+	source.push_str("\tlet { path, fingerprint } = enc.decode_header(buf, cur);\n");
+	source.push_str("\tlet typeid = enc.decode_vuint(buf, cur);\n");
+
+	// match fingerprint if present
+	source.push_str("\tif (fingerprint !== undefined) switch (fingerprint) {\n");
+	let mut fingerprints = HashSet::new();
+	for Entry { decl, resolved_path, .. } in inputs {
+		// generic items have no standalone binary representation
+		for (_, item) in decl.items.iter().filter(|(_, item)| item.params().is_empty()) {
+			let fingerprint = item.fingerprint(provider);
+			// the first of identical items is used
+			if !fingerprints.insert(fingerprint) {
+				continue;
+			}
+			write!(source, "\t\tcase 0x{fingerprint:016x}n: return ns_{resolved_path}").unwrap();
+			write!(source, ".decode_{}(buf, cur) as any;\n", flat_name(item.name())).unwrap();
+		}
+	}
+	source.push_str("\t}\n");
+
+	// else match decl_path
+	source.push_str("\telse switch (path) {\n");
 	for Entry { decl, rel_path, resolved_path } in inputs {
 		// match typeid
-		write!(source, "\t\tcase '{rel_path}': {{ switch (enc.decode_vuint(buf, cur)) {{\n")
-			.unwrap();
+		write!(source, "\t\tcase '{rel_path}': {{ switch (typeid) {{\n").unwrap();
 		// try decode
-		for (_, item) in &decl.items {
+		for (_, item) in decl.items.iter().filter(|(_, item)| item.params().is_empty()) {
 			write!(source, "\t\t\tcase {}: return ns_{resolved_path}", item.typeid()).unwrap();
-			write!(source, ".decode_{}(buf, cur) as any;\n", item.name()).unwrap();
+			write!(source, ".decode_{}(buf, cur) as any;\n", flat_name(item.name())).unwrap();
 		}
 		source.push_str("\t\t}};\n");
 	}
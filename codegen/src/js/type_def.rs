@@ -1,30 +1,60 @@
-use crate::js::Ctx;
-use std::{collections::HashSet, fmt::Write};
-use structom::{DeclProvider, internal::*};
+use crate::{
+	js::Ctx,
+	utils::{add_ident, flat_name},
+};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Write,
+};
+use structom::{
+	Decimal, DeclProvider, Key, Value,
+	encoding::{decode_u64, encode_time},
+	internal::*,
+};
 
 /// generate type definition for a decleration file
 pub fn gen_type_def(source: &mut String, used_files: &mut HashSet<u64>, ctx: &Ctx) {
 	let Ctx { file, .. } = ctx;
 
 	for (_, item) in &file.items {
+		let ctx = &Ctx { params: item.params(), ..*ctx };
+		write_annotations(source, item.annotations(), 0);
 		match item {
-			DeclItem::Struct { name, def, .. } => {
-				write!(source, "export interface {name} {{\n").unwrap();
+			DeclItem::Struct { name, def, params, .. } => {
+				let name = flat_name(name);
+				write!(source, "export interface {name}{} {{\n", generics(params)).unwrap();
 				write_struct(source, def, used_files, ctx);
 				source.push_str("}\n");
 			}
 			DeclItem::Enum { .. } => write_enum(source, item, used_files, ctx),
+			DeclItem::Flags { name, flags, .. } => {
+				let name = flat_name(name);
+				// flags are arrays of flag names
+				write!(source, "export type {name} = Array<").unwrap();
+				for (ind, flag) in flags.iter().filter_map(|f| f.as_ref()).enumerate() {
+					source.push_str(if ind == 0 { "" } else { " | " });
+					write!(source, "'{flag}'").unwrap();
+				}
+				source.push_str(">;\n");
+			}
 		}
 	}
+	// write type aliases
+	for (name, typeid) in &file.aliases {
+		write!(source, "export type {} = ", flat_name(name)).unwrap();
+		write_type(source, typeid, used_files, ctx);
+		source.push_str(";\n");
+	}
 
 	source.push('\n');
 }
 
 /// write enum definition
 fn write_enum(source: &mut String, item: &DeclItem, used_files: &mut HashSet<u64>, ctx: &Ctx) {
-	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let DeclItem::Enum { name, variants, params, .. } = item else { unreachable!() };
+	let name = flat_name(name);
 
-	write!(source, "export type {name} = ").unwrap();
+	write!(source, "export type {name}{} = ", generics(params)).unwrap();
 
 	let (unit_vars, fieldfull_vars): (Vec<_>, Vec<_>) =
 		variants.iter().filter_map(|v| v.as_ref()).partition(|v| v.def.is_none());
@@ -32,38 +62,82 @@ fn write_enum(source: &mut String, item: &DeclItem, used_files: &mut HashSet<u64
 	let mut is_first = true;
 	if !unit_vars.is_empty() {
 		source.push_str("{ type: ");
-		for EnumVariant { name, .. } in unit_vars {
+		for EnumVariant { name, annotations, .. } in unit_vars {
 			source.push_str(if is_first { "" } else { " | " });
 			is_first = false;
+			// inlined in one line
+			if !annotations.is_empty() {
+				write!(source, "/** {} */ ", jsdoc_lines(annotations).join(" ")).unwrap();
+			}
 			write!(source, "'{name}'").unwrap();
 		}
 		source.push_str(" }");
 	}
 
-	for EnumVariant { name, def, .. } in fieldfull_vars {
+	for EnumVariant { name, def, annotations, .. } in fieldfull_vars {
 		source.push_str(if is_first { "" } else { " | " });
 		is_first = false;
 
-		write!(source, "{{\n\ttype: '{name}',\n").unwrap();
+		source.push_str("{\n");
+		write_annotations(source, annotations, 1);
+		write!(source, "\ttype: '{name}',\n").unwrap();
 		write_struct(source, def.as_ref().unwrap(), used_files, ctx);
 		source.push('}');
 	}
 	source.push_str(";\n");
 }
 
+/// generic parameters list of an item, empty if not generic
+pub fn generics(params: &[String]) -> String {
+	if params.is_empty() {
+		return String::new();
+	}
+	format!("<{}>", params.join(", "))
+}
+
 /// write struct definition
 fn write_struct(source: &mut String, def: &StructDef, used_files: &mut HashSet<u64>, ctx: &Ctx) {
 	// write every fields
 	for field in def.fields.iter().flat_map(|f| f.as_ref()) {
+		write_annotations(source, &field.annotations, 1);
 		write!(source, "\t{}{}: ", field.name, if field.is_optional { "?" } else { "" }).unwrap();
 		write_type(source, &field.typeid, used_files, ctx).unwrap();
 		source.push_str(",\n");
 	}
 }
 
+/// write documentation, metadata and deprecation as jsdoc
+fn write_annotations(source: &mut String, annotations: &Annotations, ident: usize) {
+	if annotations.is_empty() {
+		return;
+	}
+	add_ident(source, ident);
+	source.push_str("/**\n");
+	for line in jsdoc_lines(annotations) {
+		add_ident(source, ident);
+		// a reason or doc holding `*/` would close the comment
+		source.push_str(format!(" * {}", line.replace("*/", "*\\/")).trim_end());
+		source.push('\n');
+	}
+	add_ident(source, ident);
+	source.push_str(" */\n");
+}
+/// jsdoc lines, metadata and deprecation are tags
+fn jsdoc_lines(annotations: &Annotations) -> Vec<String> {
+	let Annotations { doc, metadata, deprecated } = annotations;
+	let mut lines: Vec<_> = doc.iter().flat_map(|doc| doc.lines()).map(String::from).collect();
+	for (name, value) in metadata.as_deref().unwrap_or_default() {
+		lines.push(format!("@{name} {value}"));
+	}
+	if let Some(reason) = deprecated {
+		lines.push(format!("@deprecated {reason}"));
+	}
+	lines
+}
+
 /// convert built-in typeid to a js type
-fn resolve_built_in_type(typeid: u8) -> &'static str {
-	match typeid as u8 {
+fn resolve_built_in_type(typeid: u16) -> &'static str {
+	match typeid {
 		ANY_TYPEID => "Value",
 		BOOL_TYPEID => "boolean",
 		STR_TYPEID => "string",
@@ -75,6 +149,10 @@ fn resolve_built_in_type(typeid: u8) -> &'static str {
 
 		UUID_TYPEID => "UUID",
 		DUR_TYPEID => "Dur",
+		DEC_TYPEID => "Dec",
+		INSTZ_TYPEID => "InstZ",
+		DATE_TYPEID => "PlainDate",
+		TIME_TYPEID => "PlainTime",
 		INST_TYPEID | INSTN_TYPEID => "Date",
 
 		_ => unreachable!(),
@@ -86,39 +164,202 @@ fn write_type(
 ) -> Option<()> {
 	// built-ins
 	Some(if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ARR_TYPEID => {
 				source.push_str("Array<");
 				// item type
 				write_type(source, typeid.item.as_ref()?, used_files, ctx);
 				source.push('>');
 			}
+			SET_TYPEID => {
+				source.push_str("Set<");
+				// item type
+				write_type(source, typeid.item.as_ref()?, used_files, ctx);
+				source.push('>');
+			}
+			FIXED_ARR_TYPEID => {
+				source.push_str("Array<");
+				// item type
+				write_type(source, typeid.item.as_ref()?, used_files, ctx);
+				source.push('>');
+			}
+			TUPLE_TYPEID => {
+				source.push('[');
+				for (ind, item) in typeid.args.iter().enumerate() {
+					source.push_str(if ind == 0 { "" } else { ", " });
+					write_type(source, item, used_files, ctx);
+				}
+				source.push(']');
+			}
 			MAP_TYPEID => {
 				source.push_str("Map<");
 				// key type
-				source.push_str(resolve_built_in_type(typeid.variant as u8));
+				source.push_str(resolve_built_in_type(typeid.variant));
 				source.push_str(", ");
 				// value type
 				write_type(source, typeid.item.as_ref()?, used_files, ctx);
 				source.push('>');
 			}
+			PARAM_TYPEID => source.push_str(&ctx.params[typeid.variant as usize]),
 			id => source.push_str(resolve_built_in_type(id)),
 		}
 	// user-defined type
 	} else {
-		let Ctx { file, provider, path_map } = ctx;
+		let Ctx { file, provider, path_map, .. } = ctx;
 		// same file
 		if typeid.ns == file.id {
-			source.push_str(file.get_by_id(typeid.id)?.name());
+			source.push_str(&flat_name(file.get_by_id(typeid.id)?.name()));
 		// other file
 		} else {
 			// write ns.type_name
 			used_files.insert(typeid.ns);
-			let file = provider.get_by_id(typeid.ns);
+			let file = provider.get(typeid.ns);
 			source.push_str("ns_");
 			source.push_str(path_map.get(&file.id)?);
 			source.push('.');
-			source.push_str(file.get_by_id(typeid.id)?.name());
+			source.push_str(&flat_name(file.get_by_id(typeid.id)?.name()));
+		}
+
+		// generic arguments
+		if !typeid.args.is_empty() {
+			source.push('<');
+			for (ind, arg) in typeid.args.iter().enumerate() {
+				source.push_str(if ind == 0 { "" } else { ", " });
+				write_type(source, arg, used_files, ctx);
+			}
+			source.push('>');
 		}
 	})
 }
+
+/// write a js expression constructing the value of the given type
+pub fn write_literal(source: &mut String, value: &Value, typeid: &TypeId, ctx: &Ctx) {
+	let value = value.inner();
+	// built-ins
+	if typeid.ns == 0 {
+		match (typeid.id, value) {
+			(ARR_TYPEID | SET_TYPEID | FIXED_ARR_TYPEID | TUPLE_TYPEID, Value::Arr(items)) => {
+				let is_set = typeid.id == SET_TYPEID;
+				source.push_str(if is_set { "new Set([" } else { "[" });
+				for (ind, item) in items.iter().enumerate() {
+					let itemid = typeid.item.as_deref().unwrap_or_else(|| &typeid.args[ind]);
+					write_literal(source, item, itemid, ctx);
+					source.push_str(", ");
+				}
+				source.push_str(if is_set { "])" } else { "]" });
+			}
+			(MAP_TYPEID, Value::Map(map)) => {
+				let keyid = TypeId::new(0, typeid.variant, None);
+				source.push_str("new Map([");
+				for (key, item) in map.iter() {
+					source.push('[');
+					write_literal(source, &Value::from(key.clone()), &keyid, ctx);
+					source.push_str(", ");
+					write_literal(source, item, typeid.item.as_ref().unwrap(), ctx);
+					source.push_str("], ");
+				}
+				source.push_str("])");
+			}
+			(_, Value::Float(nb)) if nb.is_nan() => source.push_str("NaN"),
+			(_, Value::Float(nb)) if nb.is_infinite() => {
+				source.push_str(if *nb < 0. { "-Infinity" } else { "Infinity" })
+			}
+			(_, Value::Float(nb)) => write!(source, "{nb:?}").unwrap(),
+			(_, Value::Bool(value)) => write!(source, "{value}").unwrap(),
+			// 64 bits integers are bigints
+			(U64_TYPEID | I64_TYPEID | VINT_TYPEID | VUINT_TYPEID, Value::Int(nb)) => {
+				write!(source, "{nb}n").unwrap()
+			}
+			(U64_TYPEID | I64_TYPEID | VINT_TYPEID | VUINT_TYPEID, Value::Uint(nb)) => {
+				write!(source, "{nb}n").unwrap()
+			}
+			(_, Value::Int(nb)) => write!(source, "{nb}").unwrap(),
+			(_, Value::Uint(nb)) => write!(source, "{nb}").unwrap(),
+			(_, Value::BigInt(_)) => source.push_str("undefined"),
+			(_, Value::Str(str)) => write!(source, "{str:?}").unwrap(),
+			(_, Value::UUID(bytes)) => {
+				write!(source, "{{ type: 'uuid', value: new Uint8Array({bytes:?}) }}").unwrap()
+			}
+			(_, Value::Decimal(Decimal { value, scale })) => {
+				write!(source, "{{ type: 'dec', value: {value}n, scale: {scale} }}").unwrap()
+			}
+			(_, Value::Dur(dur)) => {
+				let nanos = dur.num_nanoseconds().unwrap_or(i64::MAX);
+				write!(source, "{{ type: 'dur', value: {nanos}n }}").unwrap()
+			}
+			(_, Value::Inst(inst)) => {
+				write!(source, "new Date({})", inst.timestamp_millis()).unwrap()
+			}
+			(_, Value::InstZ(inst)) => {
+				let (millis, offset) =
+					(inst.timestamp_millis(), inst.offset().local_minus_utc() / 60);
+				write!(source, "{{ type: 'instZ', value: new Date({millis}), offset: {offset} }}")
+					.unwrap()
+			}
+			(_, Value::Date(date)) => {
+				// dates are displayed as year-month-day
+				let date = date.to_string();
+				let mut parts = date.rsplitn(3, '-').map(|part| part.parse::<i32>().unwrap());
+				let (day, month, year) = (parts.next(), parts.next(), parts.next());
+				let (day, month, year) = (day.unwrap(), month.unwrap(), year.unwrap());
+				write!(source, "{{ type: 'date', year: {year}, month: {month}, day: {day} }}")
+					.unwrap()
+			}
+			(_, Value::Time(time)) => {
+				// nanoseconds since midnight, same as the binary representation
+				let mut data = Vec::new();
+				encode_time(&mut data, time);
+				let nanos = decode_u64(&data, &mut 0).unwrap();
+				write!(source, "{{ type: 'time', value: {nanos}n }}").unwrap()
+			}
+			_ => unreachable!(),
+		}
+		return;
+	}
+
+	// user defined
+	let Ctx { file, provider, .. } = ctx;
+	let file = if typeid.ns == file.id { *file } else { provider.get(typeid.ns) };
+	let item = file.get_by_id(typeid.id).unwrap();
+
+	match (item, value) {
+		// flags are arrays of flag names
+		(DeclItem::Flags { .. }, Value::Arr(flags)) => {
+			source.push('[');
+			for flag in flags {
+				let Value::UnitVar(flag) = flag else { unreachable!() };
+				write!(source, "'{flag}', ").unwrap();
+			}
+			source.push(']');
+		}
+		(DeclItem::Enum { .. }, Value::UnitVar(variant)) => {
+			write!(source, "{{ type: '{variant}' }}").unwrap()
+		}
+		(DeclItem::Enum { .. }, Value::Map(map)) => {
+			let variant = value.enum_variant().unwrap();
+			let def = item.get_variant_by_name(variant).unwrap().def.as_ref().unwrap();
+			write!(source, "{{ type: '{variant}',").unwrap();
+			write_fields_literal(source, map, def, &typeid.args, ctx);
+			source.push('}');
+		}
+		(DeclItem::Struct { def, .. }, Value::Map(map)) => {
+			source.push('{');
+			write_fields_literal(source, map, def, &typeid.args, ctx);
+			source.push('}');
+		}
+		_ => unreachable!(),
+	}
+}
+/// write the fields of an object literal, absent fields are omitted
+fn write_fields_literal(
+	source: &mut String, map: &HashMap<Key, Value>, def: &StructDef, args: &[TypeId], ctx: &Ctx,
+) {
+	for field in def.fields.iter().filter_map(|f| f.as_ref()) {
+		if let Some(value) = map.get(&Key::from(field.name.as_str())) {
+			write!(source, " {}: ", field.name).unwrap();
+			write_literal(source, value, &field.typeid.substitute(args), ctx);
+			source.push(',');
+		}
+	}
+	source.push(' ');
+}
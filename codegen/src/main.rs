@@ -2,12 +2,12 @@ mod js;
 mod rust;
 pub mod utils;
 
-use std::fs::{canonicalize, create_dir, create_dir_all, remove_dir_all};
+use std::fs::{canonicalize, create_dir, create_dir_all, remove_dir_all, write};
 use std::{fs::read_dir, path::Path};
 
-use clap::{Parser, ValueEnum};
-use structom::FSProvider;
-use structom::{DeclFile, LoadFileError};
+use clap::{Parser, Subcommand, ValueEnum};
+use structom::{DeclFile, ImportError};
+use structom::{FSProvider, bundle, compat};
 
 use crate::js::to_js;
 use crate::rust::to_rust;
@@ -19,25 +19,92 @@ enum Lang {
 	JS,
 }
 
+/// required compatibility direction
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Direction {
+	/// the new version reads old data
+	Backward,
+	/// old readers read new data
+	Forward,
+	/// both backward and forward
+	Full,
+}
+impl From<Direction> for compat::Direction {
+	fn from(direction: Direction) -> Self {
+		match direction {
+			Direction::Backward => Self::Backward,
+			Direction::Forward => Self::Forward,
+			Direction::Full => Self::Full,
+		}
+	}
+}
+
 /// generate serialization code for structom declerations
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+	#[command(subcommand)]
+	command: Option<Command>,
+
 	/// declerations directory path
-	#[arg(short, long)]
-	input: String,
+	#[arg(short, long, required = true)]
+	input: Option<String>,
 
 	/// generated code output path
-	#[arg(short, long)]
-	output: String,
+	#[arg(short, long, required = true)]
+	output: Option<String>,
 
 	/// language of the generated code
-	#[arg(short, long)]
-	lang: Lang,
+	#[arg(short, long, required = true)]
+	lang: Option<Lang>,
+
+	/// skip encoding fields equal to their default value
+	#[arg(long)]
+	skip_defaults: bool,
+
+	/// encode the fingerprint of the root type in the binary header
+	#[arg(long)]
+	fingerprints: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+	/// pack a declerations directory into a single binary bundle
+	Bundle {
+		/// declerations directory path
+		#[arg(short, long)]
+		input: String,
+
+		/// bundle file output path
+		#[arg(short, long)]
+		output: String,
+	},
+	/// check the compatibility between two versions of a declerations directory
+	CheckCompat {
+		/// old declerations directory path
+		old: String,
+
+		/// new declerations directory path
+		new: String,
+
+		/// compatibility direction the changes must keep
+		#[arg(short, long, value_enum, default_value_t = Direction::Backward)]
+		direction: Direction,
+	},
 }
 
 fn main() -> Result<(), String> {
-	let Args { input, output, lang } = Args::parse();
+	let Args { command, input, output, lang, skip_defaults, fingerprints } = Args::parse();
+	match command {
+		Some(Command::Bundle { input, output }) => return pack(input, output),
+		Some(Command::CheckCompat { old, new, direction }) => {
+			return check_compat(old, new, direction.into());
+		}
+		None => (),
+	}
+	// required without subcommand
+	let (Some(input), Some(output), Some(lang)) = (input, output, lang) else { unreachable!() };
 
 	// prepare output directory
 	create_dir_all(&output).map_err(errors::create_dir(&output))?;
@@ -53,13 +120,66 @@ fn main() -> Result<(), String> {
 
 	// generate code
 	match lang {
-		Lang::Rust => to_rust(&inputs, input.to_str().unwrap(), &out_path, &provider)?,
-		Lang::JS => to_js(&inputs, input.to_str().unwrap(), &out_path, &provider)?,
+		Lang::Rust => {
+			let in_dir = input.to_str().unwrap();
+			to_rust(&inputs, in_dir, &out_path, &provider, skip_defaults, fingerprints)?
+		}
+		Lang::JS => {
+			let in_dir = input.to_str().unwrap();
+			to_js(&inputs, in_dir, &out_path, &provider, skip_defaults, fingerprints)?
+		}
 	}
 
 	Ok(())
 }
 
+/// pack every decleration file in the input directory into a bundle file
+fn pack(input: String, output: String) -> Result<(), String> {
+	let input = canonicalize(&input).map_err(errors::read_dir(&input))?;
+	let provider = FSProvider::new(&input).unwrap();
+	let mut inputs = Vec::new();
+	walk_fs(&mut inputs, &input, "".to_string(), &provider)?;
+
+	let files: Vec<_> = inputs.iter().map(|entry| entry.decl).collect();
+	let data = bundle::write(&files, input.to_str().unwrap(), &provider);
+	write(&output, data).map_err(errors::write_file(&output))
+}
+
+/// print the changes of every decleration file between two versions, fails on changes breaking the direction
+fn check_compat(old: String, new: String, direction: compat::Direction) -> Result<(), String> {
+	let old = canonicalize(&old).map_err(errors::read_dir(&old))?;
+	let new = canonicalize(&new).map_err(errors::read_dir(&new))?;
+	let old_provider = FSProvider::new(&old).unwrap();
+	let new_provider = FSProvider::new(&new).unwrap();
+	let (mut old_inputs, mut new_inputs) = (Vec::new(), Vec::new());
+	walk_fs(&mut old_inputs, &old, "".to_string(), &old_provider)?;
+	walk_fs(&mut new_inputs, &new, "".to_string(), &new_provider)?;
+
+	// files are matched by relative path
+	let mut breaking = 0;
+	for Entry { rel_path, decl, .. } in &old_inputs {
+		let Some(new) = new_inputs.iter().find(|entry| entry.rel_path == *rel_path) else {
+			println!("{rel_path}: file removed, breaks backward");
+			breaking += (direction != compat::Direction::Forward) as usize;
+			continue;
+		};
+		for change in compat::check(decl, new.decl, &old_provider, &new_provider) {
+			breaking += change.breaks(direction) as usize;
+			println!("{rel_path}: {change}");
+		}
+	}
+	for Entry { rel_path, .. } in &new_inputs {
+		if !old_inputs.iter().any(|entry| entry.rel_path == *rel_path) {
+			println!("{rel_path}: file added, safe");
+		}
+	}
+
+	if breaking != 0 {
+		return Err(format!("found {breaking} breaking changes"));
+	}
+	Ok(())
+}
+
 /// file input
 #[derive(Debug)]
 pub struct Entry<'a> {
@@ -94,15 +214,10 @@ pub fn walk_fs<'a>(
 			resolved_path.truncate(rel_path.len() - 6);
 
 			// parse file and redirect errors
-			use LoadFileError::*;
-			use structom::ParseError::*;
 			match provider.load_file(&entry) {
 				Ok(decl) => inputs.push(Entry { resolved_path, rel_path, decl }),
-				Err(IO(_)) => return Err(errors::read_file(&entry.display())(())),
-				Err(Parse(TypeError(err))) => return Err(err),
-				Err(Parse(SyntaxError(err))) => {
-					return Err(format!("{err} at decleration file \"{}\"", entry.display()));
-				}
+				Err(ImportError::Parse(err)) => return Err(err.to_string()),
+				Err(_) => return Err(errors::read_file(&entry.display())(())),
 			};
 		}
 	}
@@ -10,12 +10,12 @@ use std::{
 	path::{Path, absolute},
 };
 
-use structom::{DeclFile, FSProvider};
+use structom::{DeclFile, DeclProvider, FSProvider, internal::*};
 
 use crate::{
 	Entry,
 	rust::{encoding::gen_encoding, root::gen_root, type_def::gen_type_def, value::gen_value_conv},
-	utils::errors,
+	utils::{errors, split_name},
 };
 
 /// DeclFile.id => mod path
@@ -26,11 +26,22 @@ pub struct Ctx<'a> {
 	file: &'a DeclFile,
 	provider: &'a FSProvider,
 	path_map: &'a PathMap,
+	/// generic parameters of the current item
+	params: &'a [String],
+	/// whether to skip encoding fields equal to their default value
+	skip_defaults: bool,
+	/// whether to encode the fingerprint of the root type in the header
+	fingerprints: bool,
+	/// qualified path of the current module, empty at file level
+	module: &'a str,
 }
 
+const IMPORTS: &str = "use std::collections::HashMap;\nuse structom::{Value, Key, encoding::*};\n";
+
 /// generate serialization code for rust lang
 pub fn to_rust(
-	inputs: &Vec<Entry>, in_dir: &str, out_dir: &Path, provider: &FSProvider,
+	inputs: &Vec<Entry>, in_dir: &str, out_dir: &Path, provider: &FSProvider, skip_defaults: bool,
+	fingerprints: bool,
 ) -> Result<(), String> {
 	// prepare path map
 	let mut path_map = HashMap::new();
@@ -41,16 +52,25 @@ pub fn to_rust(
 	// generate files
 	for Entry { resolved_path, rel_path, decl } in inputs {
 		let mut source = String::new();
-		let ctx = Ctx { file: decl, provider, path_map: &path_map };
+		let path_map = &path_map;
+		let ctx = Ctx {
+			file: decl,
+			provider,
+			path_map,
+			params: &[],
+			skip_defaults,
+			fingerprints,
+			module: "",
+		};
 
 		// write header and imports
 		write!(source, "// generated from file: {}\n\n", &decl.name).unwrap();
-		source.push_str("use std::collections::HashMap;\n");
-		source.push_str("use structom::{Value, Key, encoding::*};\n\n");
+		// deprecated items are still used by the generated code
+		source.push_str("#![allow(deprecated)]\n\n");
+		source.push_str(IMPORTS);
+		source.push('\n');
 
-		gen_type_def(&mut source, rel_path, &ctx);
-		gen_encoding(&mut source, &ctx);
-		gen_value_conv(&mut source, &ctx);
+		gen_module(&mut source, rel_path, &ctx);
 
 		let output = absolute(out_dir.join(resolved_path).with_extension("rs")).unwrap();
 		write(&output, source).map_err(errors::write_file(&output.display()))?;
@@ -63,3 +83,50 @@ pub fn to_rust(
 
 	Ok(())
 }
+
+/// generate the items of the current module, then its submodules as nested modules
+fn gen_module(source: &mut String, rel_path: &str, ctx: &Ctx) {
+	gen_type_def(source, rel_path, ctx);
+	gen_encoding(source, ctx);
+	gen_value_conv(source, ctx);
+
+	for module in ctx.file.modules.iter().filter(|m| split_name(m).0 == ctx.module) {
+		write!(source, "pub mod {} {{\n", split_name(module).1).unwrap();
+		source.push_str(IMPORTS);
+		source.push_str("use super::*;\n\n");
+		gen_module(source, rel_path, &Ctx { module, ..*ctx });
+		source.push_str("}\n\n");
+	}
+}
+
+/// items declared directly in the current module
+pub fn module_items<'a>(ctx: &Ctx<'a>) -> impl Iterator<Item = &'a DeclItem> {
+	let module = ctx.module;
+	ctx.file.items.values().filter(move |item| split_name(item.name()).0 == module)
+}
+
+/// path of a user type item relative to the current module, with `prefix` added to its name
+pub fn item_path(typeid: &TypeId, prefix: &str, ctx: &Ctx) -> String {
+	let Ctx { file, provider, path_map, module, .. } = ctx;
+	let is_same_file = typeid.ns == file.id;
+	let item_file = if is_same_file { *file } else { provider.get(typeid.ns) };
+	let (item_module, name) = split_name(item_file.get_by_id(typeid.id).unwrap().name());
+
+	let mut path = String::new();
+	if !is_same_file || item_module != *module {
+		// back to the file level, then down to the item module
+		module
+			.split('.')
+			.filter(|m| !m.is_empty())
+			.for_each(|_| path.push_str("super::"));
+		if !is_same_file {
+			path.push_str(path_map.get(&typeid.ns).unwrap());
+			path.push_str("::");
+		}
+		for segment in item_module.split('.').filter(|m| !m.is_empty()) {
+			write!(path, "{segment}::").unwrap();
+		}
+	}
+	write!(path, "{prefix}{name}").unwrap();
+	path
+}
@@ -1,22 +1,26 @@
-use crate::rust::Ctx;
+use crate::{
+	rust::{
+		Ctx, item_path, module_items,
+		type_def::{write_field_default, write_generics, write_literal, write_type},
+	},
+	utils::split_name,
+};
 use std::fmt::Write;
 
-use structom::{DeclProvider, internal::*};
+use structom::internal::*;
 
 /// generate encoding functions
 pub fn gen_encoding(source: &mut String, ctx: &Ctx) {
-	let Ctx { file, .. } = ctx;
-
-	for (_, item) in &file.items {
+	for item in module_items(ctx) {
+		let ctx = &Ctx { params: item.params(), ..*ctx };
+		let name = split_name(item.name()).1;
 		match item {
-			DeclItem::Struct { name, def, .. } => {
-				write!(source, "pub fn encode_{name}(data: &mut Vec<u8>, value: &{name}) {{\n",)
-					.unwrap();
+			DeclItem::Struct { def, .. } => {
+				write_encoder_header(source, &format!("encode_{name}"), name, ctx);
 				encode_struct(source, def, name, ctx);
 				source.push_str("}\n");
 
-				write!(source, "pub fn decode_{name}(data: &[u8], ind: &mut usize)").unwrap();
-				write!(source, " -> Option<{name}> {{\n").unwrap();
+				write_decoder_header(source, &format!("decode_{name}"), name, ctx);
 				decode_struct(source, def, name, ctx);
 				source.push_str("}\n\n");
 			}
@@ -25,16 +29,59 @@ pub fn gen_encoding(source: &mut String, ctx: &Ctx) {
 
 				decode_enum(source, item, ctx);
 			}
+			DeclItem::Flags { .. } => {
+				// flags are encoded as a bitmask
+				write_encoder_header(source, &format!("encode_{name}"), name, ctx);
+				source.push_str("\tencode_vuint(data, value.0);\n}\n");
+
+				write_decoder_header(source, &format!("decode_{name}"), name, ctx);
+				source.push_str("\tlet mask = decode_vuint(data, ind)?;\n");
+				// undeclared bits are invalid
+				write!(source, "\tif mask & !{} != 0 {{ return None; }}\n", item.flags_mask())
+					.unwrap();
+				write!(source, "\tSome({name}(mask))\n}}\n\n").unwrap();
+			}
 		}
 	}
 }
 
+/// write encoding fn signature, generic items take an encoder for every parameter
+fn write_encoder_header(source: &mut String, fn_name: &str, name: &str, ctx: &Ctx) {
+	write!(source, "pub fn {fn_name}").unwrap();
+	write_generics(source, ctx.params, "");
+	write!(source, "(data: &mut Vec<u8>, value: &{name}").unwrap();
+	write_generics(source, ctx.params, "");
+	for param in ctx.params {
+		write!(source, ", encode_{param}: &impl Fn(&mut Vec<u8>, &{param})").unwrap();
+	}
+	source.push_str(") {\n");
+}
+/// write decoding fn signature, generic items take a decoder for every parameter
+fn write_decoder_header(source: &mut String, fn_name: &str, name: &str, ctx: &Ctx) {
+	write!(source, "pub fn {fn_name}").unwrap();
+	write_generics(source, ctx.params, "");
+	source.push_str("(data: &[u8], ind: &mut usize");
+	for param in ctx.params {
+		write!(source, ", decode_{param}: &impl Fn(&[u8], &mut usize) -> Option<{param}>").unwrap();
+	}
+	write!(source, ") -> Option<{name}").unwrap();
+	write_generics(source, ctx.params, "");
+	source.push_str("> {\n");
+}
+/// write the parameters coders passed through to variants coders
+fn write_params_coders(source: &mut String, prefix: &str, ctx: &Ctx) {
+	for param in ctx.params {
+		write!(source, ", {prefix}_{param}").unwrap();
+	}
+}
+
 /// generate encoding function for enum
 fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
 
 	// fn decleration
-	write!(source, "pub fn encode_{name}(data: &mut Vec<u8>, value: &{name}) {{\n").unwrap();
+	write_encoder_header(source, &format!("encode_{name}"), name, ctx);
 	// encode based on variant
 	source.push_str("\tmatch value {");
 	for variant in variants.iter().filter_map(|v| v.as_ref()) {
@@ -43,7 +90,9 @@ fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 		if variant.def.is_some() {
 			write!(source, "\n\t\t{name}::{var_name} {{ .. }} => {{\n").unwrap();
 			write!(source, "\t\t\tencode_vuint(data, {tag});\n",).unwrap();
-			write!(source, "\t\t\tencode_{name}_{var_name}(data, value);\n",).unwrap();
+			write!(source, "\t\t\tencode_{name}_{var_name}(data, value").unwrap();
+			write_params_coders(source, "encode", ctx);
+			source.push_str(");\n");
 			source.push_str("\t\t},");
 		// case unit enum
 		} else {
@@ -55,8 +104,7 @@ fn encode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	// generate encode functions for variants with fields
 	for variant in variants.iter().filter_map(|v| v.as_ref().filter(|v| v.def.is_some())) {
 		let EnumVariant { name: var_name, def: Some(def), .. } = variant else { unreachable!() };
-		write!(source, "pub fn encode_{name}_{var_name}(data: &mut Vec<u8>, ").unwrap();
-		write!(source, "value: &{name}) {{\n").unwrap();
+		write_encoder_header(source, &format!("encode_{name}_{var_name}"), name, ctx);
 		encode_struct(source, def, &format!("{name}::{var_name}"), ctx);
 		source.push_str("}\n");
 	}
@@ -73,10 +121,11 @@ fn encode_struct(source: &mut String, def: &StructDef, type_name: &str, ctx: &Ct
 	}
 	source.push_str("\n\t} = value else { unreachable!() };\n");
 
-	// split fields into optional and required
+	// split fields into optional and required, fields equal to their default can be skipped
 	#[rustfmt::skip]
 	let (opt_fields, req_fields): (Vec<_>, Vec<_>) = 
-		def.fields.iter().filter_map(|f| f.as_ref()).partition(|field| field.is_optional);
+		def.fields.iter().filter_map(|f| f.as_ref()).partition(|field| field.is_optional
+			|| ctx.skip_defaults && field.default.is_some());
 
 	// encode fields count
 	// case only required, direct count
@@ -86,7 +135,9 @@ fn encode_struct(source: &mut String, def: &StructDef, type_name: &str, ctx: &Ct
 	} else {
 		write!(source, "\tencode_vuint(data, {}\n", req_fields.len()).unwrap();
 		for field in &opt_fields {
-			write!(source, "\t\t+ if f_{}.is_some() {{1}} else {{0}}\n", field.name).unwrap();
+			source.push_str("\t\t+ if ");
+			write_field_condition(source, field, ctx);
+			source.push_str(" {1} else {0}\n");
 		}
 		source.push_str("\t);\n");
 	}
@@ -97,11 +148,41 @@ fn encode_struct(source: &mut String, def: &StructDef, type_name: &str, ctx: &Ct
 	}
 	// encode optional fields if have value
 	for field in opt_fields {
-		write!(source, "\tif let Some(f_{0}) = f_{0} {{\n", field.name).unwrap();
+		let name = &field.name;
+		match (field.is_optional, ctx.skip_defaults && field.default.is_some()) {
+			(true, false) => write!(source, "\tif let Some(f_{name}) = f_{name} {{\n").unwrap(),
+			(true, true) => {
+				write!(source, "\tif let Some(f_{name}) = f_{name}.as_ref().filter(|v| **v != ")
+					.unwrap();
+				write_literal(source, field.default.as_ref().unwrap(), &field.typeid, ctx);
+				source.push_str(") {\n");
+			}
+			_ => {
+				source.push_str("\tif ");
+				write_field_condition(source, field, ctx);
+				source.push_str(" {\n");
+			}
+		}
 		encode_field(source, &field, ctx);
 		source.push_str("\t}\n");
 	}
 }
+/// write the condition of encoding an optional or skippable field
+fn write_field_condition(source: &mut String, field: &Field, ctx: &Ctx) {
+	let Field { name, is_optional, default, .. } = field;
+	match (*is_optional, ctx.skip_defaults && default.is_some()) {
+		(true, false) => write!(source, "f_{name}.is_some()").unwrap(),
+		(true, true) => {
+			write!(source, "f_{name}.as_ref().is_some_and(|v| *v != ").unwrap();
+			write_literal(source, default.as_ref().unwrap(), &field.typeid, ctx);
+			source.push(')');
+		}
+		_ => {
+			write!(source, "*f_{name} != ").unwrap();
+			write_field_default(source, field, ctx);
+		}
+	}
+}
 
 // encode code for common field value types
 // encode header then value
@@ -132,7 +213,7 @@ fn encode_sized_value(source: &mut String, tag: u32, encoder: impl Fn(&mut Strin
 	);
 }
 // generate fn that encode primitive types
-fn write_primitive_encoder(source: &mut String, typeid: u8, is_key: bool) {
+fn write_primitive_encoder(source: &mut String, typeid: u16, is_key: bool) {
 	match typeid {
 		ANY_TYPEID if is_key => source.push_str("encode_any_key"),
 		ANY_TYPEID if !is_key => source.push_str("encode_any"),
@@ -160,55 +241,95 @@ fn write_primitive_encoder(source: &mut String, typeid: u8, is_key: bool) {
 		INST_TYPEID => source.push_str("encode_inst"),
 		INSTN_TYPEID => source.push_str("encode_instN"),
 		DUR_TYPEID => source.push_str("encode_dur"),
+		DEC_TYPEID => source.push_str("encode_dec"),
+		INSTZ_TYPEID => source.push_str("encode_instZ"),
+		DATE_TYPEID => source.push_str("encode_date"),
+		TIME_TYPEID => source.push_str("encode_time"),
 		UUID_TYPEID => source.push_str("encode_uuid"),
 		_ => (),
 	}
 }
 /// write fn that encode specific type
 fn write_value_encoder(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
-	let Ctx { file, path_map, provider } = ctx;
+	let Ctx { params, .. } = ctx;
 	// builtins
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ARR_TYPEID => {
 				source.push_str("|data, value| encode_arr(data, value, false, ");
 				// item encoder
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			SET_TYPEID => {
+				source.push_str("|data, value| encode_set(data, value, false, ");
+				// item encoder
+				write_primitive_encoder(source, typeid.item.as_ref().unwrap().id, true);
+				source.push_str(")");
+			}
+			FIXED_ARR_TYPEID => {
+				source.push_str("|data, value| encode_fixed_arr(data, value, ");
+				// item encoder
+				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push_str(")");
+			}
+			TUPLE_TYPEID => {
+				source.push_str("|data, value| {");
+				write_tuple_encoder(source, typeid, "value", ctx);
+				source.push('}');
+			}
 			MAP_TYPEID => {
 				source.push_str("|data, value| encode_map(data, value, false, ");
 				// key encoder
-				write_primitive_encoder(source, typeid.variant as u8, true);
+				write_primitive_encoder(source, typeid.variant, true);
 				source.push_str(", ");
 				// value encoder
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			PARAM_TYPEID => {
+				write!(source, "encode_{}", params[typeid.variant as usize]).unwrap()
+			}
 			id => write_primitive_encoder(source, id, false),
 		}
 	// user defined
 	} else {
-		// same file
-		if typeid.ns == file.id {
-			write!(source, "encode_{}", file.get_by_id(typeid.id).unwrap().name()).unwrap();
-		// different file
-		} else {
-			// write mod_path::encode_type
-			let file = provider.get_by_id(typeid.ns);
-			source.push_str(path_map.get(&typeid.ns).unwrap());
-			source.push_str("::encode_");
-			source.push_str(file.get_by_id(typeid.id).unwrap().name());
+		// generic items take their arguments encoders
+		if !typeid.args.is_empty() {
+			source.push_str("|data, value| ");
+		}
+		// write mod_path::encode_type
+		source.push_str(&item_path(typeid, "encode_", ctx));
+		if !typeid.args.is_empty() {
+			source.push_str("(data, value");
+			write_args_encoders(source, typeid, ctx);
+			source.push(')');
 		}
 	}
 }
+/// write encoders of generic item arguments
+fn write_args_encoders(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	for arg in &typeid.args {
+		source.push_str(", &");
+		write_value_encoder(source, arg, ctx);
+	}
+}
+/// write encoding of every tuple item, in order
+fn write_tuple_encoder(source: &mut String, typeid: &TypeId, value: &str, ctx: &Ctx) {
+	for (ind, item) in typeid.args.iter().enumerate() {
+		write!(source, " encode_with(data, &{value}.{ind}, ").unwrap();
+		write_value_encoder(source, item, ctx);
+		source.push_str(");");
+	}
+	source.push(' ');
+}
 /// generate encode code for a field
 fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
-	let Ctx { file, path_map, provider } = ctx;
+	let Ctx { params, .. } = ctx;
 	let Field { name, typeid, tag, .. } = field;
 	// builtins
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ANY_TYPEID => encode_sized_value(source, *tag, |source| {
 				write!(source, "encode_any(data, f_{name})").unwrap()
 			}),
@@ -239,10 +360,27 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push(')');
 			}),
+			SET_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "encode_set(data, f_{name}, true, ").unwrap();
+				// item encoder
+				write_primitive_encoder(source, typeid.item.as_ref().unwrap().id, true);
+				source.push(')');
+			}),
+			FIXED_ARR_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "encode_fixed_arr(data, f_{name}, ").unwrap();
+				// item encoder
+				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push(')');
+			}),
+			TUPLE_TYPEID => encode_sized_value(source, *tag, |source| {
+				source.push('{');
+				write_tuple_encoder(source, typeid, &format!("f_{name}"), ctx);
+				source.push('}');
+			}),
 			MAP_TYPEID => encode_sized_value(source, *tag, |source| {
 				write!(source, "encode_map(data, f_{name}, true, ").unwrap();
 				// key encoder
-				write_primitive_encoder(source, typeid.variant as u8, true);
+				write_primitive_encoder(source, typeid.variant, true);
 				source.push_str(", ");
 				// value encoder
 				write_value_encoder(source, typeid.item.as_ref().unwrap(), ctx);
@@ -252,24 +390,28 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 			INST_TYPEID => encode_simple_value(source, "inst", name, *tag, 0b011),
 			INSTN_TYPEID => encode_compound_value(source, "instN", name, *tag, 12),
 			DUR_TYPEID => encode_simple_value(source, "dur", name, *tag, 0b011),
+			DEC_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "encode_dec(data, f_{name})").unwrap();
+			}),
 			UUID_TYPEID => encode_compound_value(source, "uuid", name, *tag, 16),
+			INSTZ_TYPEID => encode_compound_value(source, "instZ", name, *tag, 10),
+			DATE_TYPEID => encode_simple_value(source, "date", name, *tag, 0b010),
+			TIME_TYPEID => encode_simple_value(source, "time", name, *tag, 0b011),
+			// generic fields are always sized
+			PARAM_TYPEID => encode_sized_value(source, *tag, |source| {
+				write!(source, "encode_{}(data, f_{name})", params[typeid.variant as usize])
+					.unwrap()
+			}),
 			_ => unreachable!(),
 		}
 	// user defined types
 	} else {
 		encode_sized_value(source, *tag, |source| {
-			// same file
-			if typeid.ns == file.id {
-				let item_name = file.get_by_id(typeid.id).unwrap().name();
-				write!(source, "encode_{item_name}(data, f_{name})",).unwrap();
-			// different file
-			} else {
-				// write mod_path::encode_value
-				let file = provider.get_by_id(typeid.ns);
-				let item_name = file.get_by_id(typeid.id).unwrap().name();
-				source.push_str(path_map.get(&typeid.ns).unwrap());
-				write!(source, "::encode_{item_name}(data, f_{name})").unwrap();
-			}
+			// write mod_path::encode_value
+			let encoder = item_path(typeid, "encode_", ctx);
+			write!(source, "{encoder}(data, f_{name}").unwrap();
+			write_args_encoders(source, typeid, ctx);
+			source.push(')');
 		});
 	}
 }
@@ -277,15 +419,17 @@ fn encode_field(source: &mut String, field: &Field, ctx: &Ctx) {
 /// generate decoding function for enum
 fn decode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
 
-	write!(source, "pub fn decode_{name}(data: &[u8], ind: &mut usize) -> Option<{name}> {{\n")
-		.unwrap();
+	write_decoder_header(source, &format!("decode_{name}"), name, ctx);
 
 	write!(source, "\tmatch decode_vuint(data, ind)? {{\n").unwrap();
 	for variant in variants.iter().filter_map(|v| v.as_ref()) {
 		let EnumVariant { name: var_name, tag, def, .. } = variant;
 		if def.is_some() {
-			write!(source, "\t\t{tag} => decode_{name}_{var_name}(data, ind),\n").unwrap();
+			write!(source, "\t\t{tag} => decode_{name}_{var_name}(data, ind").unwrap();
+			write_params_coders(source, "decode", ctx);
+			source.push_str("),\n");
 		} else {
 			write!(source, "\t\t{tag} => Some({name}::{var_name}),\n").unwrap();
 		}
@@ -294,8 +438,7 @@ fn decode_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 
 	for variant in variants.iter().filter_map(|v| v.as_ref().filter(|v| v.def.is_some())) {
 		let EnumVariant { name: var_name, def: Some(def), .. } = variant else { unreachable!() };
-		write!(source, "pub fn decode_{name}_{var_name}(data: &[u8], ind: &mut usize)").unwrap();
-		write!(source, " -> Option<{name}> {{").unwrap();
+		write_decoder_header(source, &format!("decode_{name}_{var_name}"), name, ctx);
 		decode_struct(source, def, &format!("{name}::{var_name}"), ctx);
 		source.push_str("}\n");
 	}
@@ -334,7 +477,14 @@ fn decode_struct(source: &mut String, def: &StructDef, name: &str, ctx: &Ctx) {
 	source.push_str(" else { skip_field(data, ind, header)? }\n");
 	source.push_str("\t}\n");
 
-	for field in fields.iter().filter(|f| !f.is_optional) {
+	// absent fields take their default value
+	for field in fields.iter().filter(|f| f.default.is_some()) {
+		let method = if field.is_optional { "or_else" } else { "unwrap_or_else" };
+		write!(source, "\tlet f_{0} = f_{0}.{method}(|| ", field.name).unwrap();
+		write_field_default(source, field, ctx);
+		source.push_str(");\n");
+	}
+	for field in fields.iter().filter(|f| f.is_required()) {
 		let Field { name, .. } = field;
 		write!(source, "\tlet Some(f_{name}) = f_{name} else {{ return None; }};\n").unwrap();
 	}
@@ -356,7 +506,7 @@ fn decode_compound_value(source: &mut String, name: &str, ty: &str) {
 	source.push_str("\t\t\tdecode_vuint(data, ind)?;\n");
 	write!(source, "\t\t\tf_{name} = Some(decode_{ty}(data, ind)?);\n").unwrap();
 }
-fn write_primitive_decoder(source: &mut String, typeid: u8, is_key: bool) {
+fn write_primitive_decoder(source: &mut String, typeid: u16, is_key: bool) {
 	match typeid {
 		ANY_TYPEID if is_key => source.push_str("decode_any_key"),
 		ANY_TYPEID if !is_key => source.push_str("decode_any"),
@@ -384,43 +534,81 @@ fn write_primitive_decoder(source: &mut String, typeid: u8, is_key: bool) {
 		INST_TYPEID => source.push_str("decode_inst"),
 		INSTN_TYPEID => source.push_str("decode_instN"),
 		DUR_TYPEID => source.push_str("decode_dur"),
+		DEC_TYPEID => source.push_str("decode_dec"),
+		INSTZ_TYPEID => source.push_str("decode_instZ"),
+		DATE_TYPEID => source.push_str("decode_date"),
+		TIME_TYPEID => source.push_str("decode_time"),
 		UUID_TYPEID => source.push_str("decode_uuid"),
 		_ => (),
 	}
 }
 fn write_value_decoder(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
-	let Ctx { file, path_map, provider } = ctx;
+	let Ctx { params, .. } = ctx;
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ARR_TYPEID => {
 				source.push_str("|data, ind| decode_arr(data, ind, false, ");
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			SET_TYPEID => {
+				source.push_str("|data, ind| decode_set(data, ind, false, ");
+				write_primitive_decoder(source, typeid.item.as_ref().unwrap().id, true);
+				source.push_str(")");
+			}
+			FIXED_ARR_TYPEID => {
+				// decoded into a vec, then converted into an array
+				source.push_str("|data, ind| <");
+				write_type(source, typeid, ctx);
+				write!(source, ">::try_from(decode_fixed_arr(data, ind, {}, ", typeid.variant)
+					.unwrap();
+				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
+				source.push_str(")?).ok()");
+			}
+			TUPLE_TYPEID => {
+				source.push_str("|data, ind| Some((");
+				for item in &typeid.args {
+					source.push_str("decode_with(data, ind, ");
+					write_value_decoder(source, item, ctx);
+					source.push_str(")?, ");
+				}
+				source.push_str("))");
+			}
 			MAP_TYPEID => {
 				source.push_str("|data, ind| decode_map(data, ind, false, ");
-				write_primitive_decoder(source, typeid.variant as u8, true);
+				write_primitive_decoder(source, typeid.variant, true);
 				source.push_str(", ");
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")");
 			}
+			PARAM_TYPEID => {
+				write!(source, "decode_{}", params[typeid.variant as usize]).unwrap()
+			}
 			id => write_primitive_decoder(source, id, false),
 		}
 	} else {
-		if typeid.ns == file.id {
-			write!(source, "decode_{}", file.get_by_id(typeid.id).unwrap().name()).unwrap();
-		} else {
-			let file = provider.get_by_id(typeid.ns);
-			source.push_str(path_map.get(&typeid.ns).unwrap());
-			source.push_str("::decode_");
-			source.push_str(file.get_by_id(typeid.id).unwrap().name());
+		if !typeid.args.is_empty() {
+			source.push_str("|data, ind| ");
+		}
+		source.push_str(&item_path(typeid, "decode_", ctx));
+		if !typeid.args.is_empty() {
+			source.push_str("(data, ind");
+			write_args_decoders(source, typeid, ctx);
+			source.push(')');
 		}
 	}
 }
+/// write decoders of generic item arguments
+fn write_args_decoders(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	for arg in &typeid.args {
+		source.push_str(", &");
+		write_value_decoder(source, arg, ctx);
+	}
+}
 fn decode_field(source: &mut String, name: &str, typeid: &TypeId, ctx: &Ctx) {
-	let Ctx { file, provider, path_map } = ctx;
+	let Ctx { params, .. } = ctx;
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ANY_TYPEID => decode_compound_value(source, name, "any"),
 			BOOL_TYPEID => decode_simple_value(source, name, "bool"),
 
@@ -447,9 +635,20 @@ fn decode_field(source: &mut String, name: &str, typeid: &TypeId, ctx: &Ctx) {
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")?);\n");
 			}
+			SET_TYPEID => {
+				write!(source, "\t\t\tf_{name} = Some(decode_set(data, ind, true, ").unwrap();
+				write_primitive_decoder(source, typeid.item.as_ref().unwrap().id, true);
+				source.push_str(")?);\n");
+			}
+			FIXED_ARR_TYPEID | TUPLE_TYPEID => {
+				source.push_str("\t\t\tdecode_vuint(data, ind)?;\n");
+				write!(source, "\t\t\tf_{name} = Some(decode_with(data, ind, ").unwrap();
+				write_value_decoder(source, typeid, ctx);
+				source.push_str(")?);\n");
+			}
 			MAP_TYPEID => {
 				write!(source, "\t\t\tf_{name} = Some(decode_map(data, ind, true, ").unwrap();
-				write_primitive_decoder(source, typeid.variant as u8, true);
+				write_primitive_decoder(source, typeid.variant, true);
 				source.push_str(", ");
 				write_value_decoder(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push_str(")?);\n");
@@ -458,21 +657,19 @@ fn decode_field(source: &mut String, name: &str, typeid: &TypeId, ctx: &Ctx) {
 			INST_TYPEID => decode_simple_value(source, name, "inst"),
 			INSTN_TYPEID => decode_compound_value(source, name, "instN"),
 			DUR_TYPEID => decode_simple_value(source, name, "dur"),
+			DEC_TYPEID => decode_compound_value(source, name, "dec"),
 			UUID_TYPEID => decode_compound_value(source, name, "uuid"),
+			INSTZ_TYPEID => decode_compound_value(source, name, "instZ"),
+			DATE_TYPEID => decode_simple_value(source, name, "date"),
+			TIME_TYPEID => decode_simple_value(source, name, "time"),
+			PARAM_TYPEID => decode_compound_value(source, name, &params[typeid.variant as usize]),
 			_ => (),
 		}
 	} else {
-		if typeid.ns == file.id {
-			source.push_str("\t\t\tdecode_vuint(data, ind)?;\n");
-			let type_name = file.get_by_id(typeid.id).unwrap().name();
-			write!(source, "\t\t\tf_{name} = Some(decode_{type_name}(data, ind)?);\n",).unwrap();
-		} else {
-			let file = provider.get_by_id(typeid.ns);
-			source.push_str("\t\t\tdecode_vuint(data, ind)?;\n");
-			write!(source, "\t\t\tf_{name} = Some(").unwrap();
-			source.push_str(path_map.get(&typeid.ns).unwrap());
-			let type_name = file.get_by_id(typeid.id).unwrap().name();
-			write!(source, "::decode_{type_name}(data, ind)?);\n",).unwrap();
-		}
+		source.push_str("\t\t\tdecode_vuint(data, ind)?;\n");
+		let decoder = item_path(typeid, "decode_", ctx);
+		write!(source, "\t\t\tf_{name} = Some({decoder}(data, ind").unwrap();
+		write_args_decoders(source, typeid, ctx);
+		source.push_str(")?);\n");
 	}
 }
@@ -1,4 +1,4 @@
-use crate::Entry;
+use crate::{Entry, utils::split_name};
 use std::fmt::Write;
 
 /// generate root mod
@@ -22,10 +22,15 @@ pub fn gen_root(inputs: &Vec<Entry>, in_dir: &str) -> String {
 	for Entry { decl, rel_path, resolved_path } in inputs {
 		// match typeid
 		write!(source, "\t\t{rel_path:?} => match decode_vuint(data, &mut ind)? {{\n").unwrap();
-		// try decode
-		for (_, item) in &decl.items {
+		// try decode, generic items have no standalone binary representation
+		for (_, item) in decl.items.iter().filter(|(_, item)| item.params().is_empty()) {
 			write!(source, "\t\t\t{} => Box::new({resolved_path}", item.typeid()).unwrap();
-			write!(source, "::decode_{}(data, &mut ind)?),\n", item.name()).unwrap();
+			// items of nested modules
+			let (module, name) = split_name(item.name());
+			for segment in module.split('.').filter(|m| !m.is_empty()) {
+				write!(source, "::{segment}").unwrap();
+			}
+			write!(source, "::decode_{name}(data, &mut ind)?),\n").unwrap();
 		}
 		source.push_str("\t\t\t_ => return None,\n");
 		source.push_str("\t\t},\n");
@@ -1,39 +1,72 @@
-use std::fmt::Write;
+use std::{collections::HashMap, fmt::Write};
 
-use structom::{DeclProvider, internal::*};
+use structom::{Decimal, DeclProvider, Key, Value, internal::*};
 
 use crate::{
-	rust::Ctx,
-	utils::{add_ident, encode_header},
+	rust::{Ctx, item_path, module_items},
+	utils::{add_ident, encode_header, split_name},
 };
 
 /// generate type definition for a decleration file
 pub fn gen_type_def(source: &mut String, rel_path: &str, ctx: &Ctx) {
-	let Ctx { file, .. } = ctx;
+	let Ctx { file, module, .. } = ctx;
 
-	for (_, item) in &file.items {
+	for item in module_items(ctx) {
+		let ctx = &Ctx { params: item.params(), ..*ctx };
 		match item {
-			DeclItem::Struct { name, def, .. } => {
-				// write derived traits
-				source.push_str("#[derive(Default, Clone, PartialEq, Debug)]\n");
+			DeclItem::Struct { name, def, params, .. } => {
+				let name = split_name(name).1;
+				// write derived traits, fields with default value need a manual Default impl
+				let has_defaults =
+					def.fields.iter().filter_map(|f| f.as_ref()).any(|f| f.default.is_some());
+				source.push_str("#[derive(");
+				source.push_str(if has_defaults { "" } else { "Default, " });
+				source.push_str("Clone, PartialEq, Debug)]\n");
+				write_annotations(source, item.annotations(), 0);
 
-				write!(source, "pub struct {name} ").unwrap();
+				write!(source, "pub struct {name}").unwrap();
+				write_generics(source, params, "");
+				source.push(' ');
 				write_struct(source, def, 1, false, ctx);
 				source.push('\n');
+
+				if has_defaults {
+					write_default_header(source, name, params);
+					source.push_str("Self {");
+					write_default_fields(source, def, ctx);
+					source.push_str("\n\t\t}\n\t}\n}\n");
+				}
+				// conversions into spread structs
+				for base in &def.bases {
+					write_base_conv(source, name, params, base, ctx);
+				}
 			}
 			DeclItem::Enum { .. } => write_enum(source, item, ctx),
+			DeclItem::Flags { .. } => write_flags(source, item),
+		}
+	}
+	// write type aliases
+	for (name, typeid) in &file.aliases {
+		let (alias_module, name) = split_name(name);
+		if alias_module != *module {
+			continue;
 		}
+		write!(source, "pub type {name} = ").unwrap();
+		write_type(source, typeid, ctx);
+		source.push_str(";\n");
 	}
 	// write serialized traits
-	for (_, item) in &file.items {
-		write_serialized_trait(source, item, rel_path);
+	// generic items have no standalone binary representation
+	for item in module_items(ctx).filter(|item| item.params().is_empty()) {
+		write_serialized_trait(source, item, rel_path, ctx);
 	}
 
 	source.push('\n');
 }
 
 fn write_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
-	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let DeclItem::Enum { name, variants, params, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
 	// write derived traits
 	source.push_str("#[derive(Clone, PartialEq, Debug)]\n");
 
@@ -43,12 +76,16 @@ fn write_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 		256..65535 => source.push_str("repr(u16)]\n"),
 		_ => source.push_str("repr(u32)]\n"),
 	}
+	write_annotations(source, item.annotations(), 0);
 
-	write!(source, "pub enum {name} {{\n").unwrap();
+	write!(source, "pub enum {name}").unwrap();
+	write_generics(source, params, "");
+	source.push_str(" {\n");
 
 	// write variants
 	let mut last_tag = 0;
 	for variant in variants.iter().flat_map(|v| v.as_ref()) {
+		write_annotations(source, &variant.annotations, 1);
 		write!(source, "\t{}", variant.name).unwrap();
 		// write field definition if exists
 		if let Some(ref def) = variant.def {
@@ -66,8 +103,7 @@ fn write_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	source.push_str("}\n");
 
 	// write default trait impl
-	write!(source, "impl Default for {} {{\n", item.name()).unwrap();
-	source.push_str("\tfn default () -> Self {\n\t\t");
+	write_default_header(source, name, params);
 	// get first variant
 	let variant = variants.iter().find_map(|v| v.as_ref()).unwrap();
 
@@ -75,20 +111,107 @@ fn write_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	// case has fields
 	if let Some(def) = &variant.def {
 		source.push_str(" {");
-		for Field { name, .. } in def.fields.iter().flat_map(|f| f.as_ref()) {
-			write!(source, "\n\t\t\t{name}: Default::default(),").unwrap();
-		}
+		write_default_fields(source, def, ctx);
 		source.push_str("\n\t\t}");
 	}
 	source.push_str("\n\t}\n}\n");
 }
 
+/// write Default impl header, up to the returned value
+fn write_default_header(source: &mut String, name: &str, params: &[String]) {
+	source.push_str("impl");
+	write_generics(source, params, "Default");
+	write!(source, " Default for {name}").unwrap();
+	write_generics(source, params, "");
+	source.push_str(" {\n");
+	source.push_str("\tfn default () -> Self {\n\t\t");
+}
+/// write fields initialized to their default value
+fn write_default_fields(source: &mut String, def: &StructDef, ctx: &Ctx) {
+	for field in def.fields.iter().flat_map(|f| f.as_ref()) {
+		write!(source, "\n\t\t\t{}: ", field.name).unwrap();
+		write_field_default(source, field, ctx);
+		source.push(',');
+	}
+}
+
+fn write_flags(source: &mut String, item: &DeclItem) {
+	let DeclItem::Flags { name, flags, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
+	// flags are a bitmask
+	source.push_str("#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]\n");
+	write_annotations(source, item.annotations(), 0);
+	write!(source, "pub struct {name}(pub u64);\n").unwrap();
+
+	// write flag constants
+	source.push_str("#[allow(non_upper_case_globals)]\n");
+	write!(source, "impl {name} {{\n").unwrap();
+	for (bit, flag) in flags.iter().enumerate().filter_map(|(bit, f)| Some((bit, f.as_ref()?))) {
+		write!(source, "\tpub const {flag}: Self = Self(1 << {bit});\n").unwrap();
+	}
+
+	// write set operations
+	source.push_str("\tpub fn contains(&self, other: Self) -> bool {\n");
+	source.push_str("\t\tself.0 & other.0 == other.0\n\t}\n");
+	source.push_str("\tpub fn insert(&mut self, other: Self) {\n\t\tself.0 |= other.0;\n\t}\n");
+	source.push_str("\tpub fn remove(&mut self, other: Self) {\n\t\tself.0 &= !other.0;\n\t}\n");
+	source.push_str("}\n");
+
+	// write bitor trait impl
+	write!(source, "impl std::ops::BitOr for {name} {{\n").unwrap();
+	source.push_str("\ttype Output = Self;\n");
+	source.push_str("\tfn bitor(self, other: Self) -> Self {\n\t\tSelf(self.0 | other.0)\n\t}\n");
+	source.push_str("}\n");
+}
+
+/// write From impl converting a struct into a struct it spreads
+fn write_base_conv(source: &mut String, name: &str, params: &[String], base: &TypeId, ctx: &Ctx) {
+	let Ctx { file, provider, .. } = ctx;
+	let base_file = if base.ns == file.id { *file } else { provider.get(base.ns) };
+	let DeclItem::Struct { def, .. } = base_file.get_by_id(base.id).unwrap() else {
+		unreachable!()
+	};
+
+	source.push_str("impl");
+	write_generics(source, params, "");
+	write!(source, " From<{name}").unwrap();
+	write_generics(source, params, "");
+	source.push_str("> for ");
+	write_type(source, base, ctx);
+	source.push_str(" {\n");
+
+	write!(source, "\tfn from(value: {name}").unwrap();
+	write_generics(source, params, "");
+	source.push_str(") -> Self {\n\t\tSelf {");
+	for Field { name, .. } in def.fields.iter().flat_map(|f| f.as_ref()) {
+		write!(source, "\n\t\t\t{name}: value.{name},").unwrap();
+	}
+	source.push_str("\n\t\t}\n\t}\n}\n");
+}
+
+/// write generic parameters of an item, each bounded by `bound` if not empty
+pub fn write_generics(source: &mut String, params: &[String], bound: &str) {
+	if params.is_empty() {
+		return;
+	}
+	source.push('<');
+	for (ind, param) in params.iter().enumerate() {
+		source.push_str(if ind == 0 { "" } else { ", " });
+		source.push_str(param);
+		if !bound.is_empty() {
+			write!(source, ": {bound}").unwrap();
+		}
+	}
+	source.push('>');
+}
+
 /// write struct definition
 fn write_struct(source: &mut String, def: &StructDef, ident: usize, is_enum: bool, ctx: &Ctx) {
 	source.push_str("{\n");
 
 	// write every fields
 	for field in def.fields.iter().flat_map(|f| f.as_ref()) {
+		write_annotations(source, &field.annotations, ident);
 		add_ident(source, ident);
 		write!(source, "{}{}: ", if is_enum { "" } else { "pub " }, field.name).unwrap();
 		if field.is_optional {
@@ -105,9 +228,35 @@ fn write_struct(source: &mut String, def: &StructDef, ident: usize, is_enum: boo
 	source.push('}')
 }
 
+/// write documentation with metadata, and deprecated attribute
+fn write_annotations(source: &mut String, annotations: &Annotations, ident: usize) {
+	let Annotations { doc, metadata, deprecated } = annotations;
+	let doc = doc.as_deref().unwrap_or_default();
+	let metadata = metadata.as_deref().unwrap_or_default();
+
+	let mut write_line = |line: &str| {
+		add_ident(source, ident);
+		source.push_str(format!("/// {line}").trim_end());
+		source.push('\n');
+	};
+	doc.lines().for_each(&mut write_line);
+	// metadata in their own paragraph
+	if !doc.is_empty() && !metadata.is_empty() {
+		write_line("");
+	}
+	for (name, value) in metadata {
+		write_line(&format!("@{name} {value}"));
+	}
+
+	if let Some(reason) = deprecated {
+		add_ident(source, ident);
+		write!(source, "#[deprecated(note = {reason:?})]\n").unwrap();
+	}
+}
+
 /// convert built-in typeid to a rust type
-fn resolve_built_in_type(typeid: u8, is_key: bool) -> &'static str {
-	match typeid as u8 {
+fn resolve_built_in_type(typeid: u16, is_key: bool) -> &'static str {
+	match typeid {
 		ANY_TYPEID if !is_key => "Value",
 		ANY_TYPEID if is_key => "Key",
 		BOOL_TYPEID => "bool",
@@ -132,26 +281,53 @@ fn resolve_built_in_type(typeid: u8, is_key: bool) -> &'static str {
 
 		UUID_TYPEID => "[u8; 16]",
 		DUR_TYPEID => "chrono::TimeDelta",
+		DEC_TYPEID => "structom::Decimal",
+		INSTZ_TYPEID => "chrono::DateTime<chrono::FixedOffset>",
+		DATE_TYPEID => "chrono::NaiveDate",
+		TIME_TYPEID => "chrono::NaiveTime",
 		INST_TYPEID | INSTN_TYPEID => "chrono::DateTime<chrono::Utc>",
 
 		_ => unreachable!(),
 	}
 }
 /// convert a typeid to a rust type
-fn write_type(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+pub fn write_type(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
 	// built-ins
 	if typeid.ns == 0 {
-		match typeid.id as u8 {
+		match typeid.id {
 			ARR_TYPEID => {
 				source.push_str("Vec<");
 				// item type
 				write_type(source, typeid.item.as_ref().unwrap(), ctx);
 				source.push('>');
 			}
+			SET_TYPEID => {
+				source.push_str("std::collections::HashSet<");
+				// item type, sets only hold primitives
+				let itemid = typeid.item.as_ref().unwrap().id;
+				source.push_str(resolve_built_in_type(itemid, true));
+				source.push('>');
+			}
+			FIXED_ARR_TYPEID => {
+				source.push('[');
+				// item type
+				write_type(source, typeid.item.as_ref().unwrap(), ctx);
+				write!(source, "; {}]", typeid.variant).unwrap();
+			}
+			TUPLE_TYPEID => {
+				source.push('(');
+				for (ind, item) in typeid.args.iter().enumerate() {
+					source.push_str(if ind == 0 { "" } else { ", " });
+					write_type(source, item, ctx);
+				}
+				// single item tuples need a trailing comma
+				source.push_str(if typeid.args.len() == 1 { ",)" } else { ")" });
+			}
+			PARAM_TYPEID => source.push_str(&ctx.params[typeid.variant as usize]),
 			MAP_TYPEID => {
 				source.push_str("HashMap<");
 				// key type
-				source.push_str(resolve_built_in_type(typeid.variant as u8, true));
+				source.push_str(resolve_built_in_type(typeid.variant, true));
 				source.push_str(", ");
 				// value type
 				write_type(source, typeid.item.as_ref().unwrap(), ctx);
@@ -161,24 +337,25 @@ fn write_type(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
 		}
 	// user-defined type
 	} else {
-		let Ctx { file, provider, path_map } = ctx;
-		// same file
-		if typeid.ns == file.id {
-			source.push_str(file.get_by_id(typeid.id).unwrap().name());
-		// other file
-		} else {
-			// write mod_path::type_name
-			let file = provider.get_by_id(typeid.ns);
-			source.push_str(path_map.get(&file.id).unwrap());
-			source.push_str("::");
-			source.push_str(file.get_by_id(typeid.id).unwrap().name());
+		// write mod_path::type_name
+		source.push_str(&item_path(typeid, "", ctx));
+
+		// generic arguments
+		if !typeid.args.is_empty() {
+			source.push('<');
+			for (ind, arg) in typeid.args.iter().enumerate() {
+				source.push_str(if ind == 0 { "" } else { ", " });
+				write_type(source, arg, ctx);
+			}
+			source.push('>');
 		}
 	}
 }
 
 /// generate Serialized trait impl
-fn write_serialized_trait(source: &mut String, item: &DeclItem, file: &str) {
-	let name = item.name();
+fn write_serialized_trait(source: &mut String, item: &DeclItem, file: &str, ctx: &Ctx) {
+	let name = split_name(item.name()).1;
+	let fingerprint = item.fingerprint(ctx.provider);
 	write!(source, "impl Serialized for {name} {{\n").unwrap();
 
 	// encode
@@ -186,7 +363,7 @@ fn write_serialized_trait(source: &mut String, item: &DeclItem, file: &str) {
 	source.push_str("\t\tlet mut data = Vec::new();\n");
 	// header
 	source.push_str("\t\tdata.extend_from_slice(&[\n\t\t\t");
-	encode_header(source, file, item.typeid() as u64);
+	encode_header(source, file, item.typeid() as u64, ctx.fingerprints.then_some(fingerprint));
 	source.push_str("\n\t\t]);\n");
 	// encode item
 	write!(source, "\t\tencode_{name}(&mut data, self);\n").unwrap();
@@ -201,12 +378,15 @@ fn write_serialized_trait(source: &mut String, item: &DeclItem, file: &str) {
 	// decode
 	write!(source, "\tfn decode(data: &[u8]) -> Option<{name}> {{\n").unwrap();
 	source.push_str("\t\tlet mut ind = 0;\n");
-	// check decl_path
-	write!(source, "\t\tif decode_str(data, &mut ind)? != {file:?} {{\n").unwrap();
-	source.push_str("\t\t\treturn None;\n\t\t}\n");
-	// check typeid
-	write!(source, "\t\tif decode_vuint(data, &mut ind)? != {} {{\n", item.typeid()).unwrap();
-	source.push_str("\t\t\treturn None;\n\t\t}\n");
+	source.push_str("\t\tlet (decl_path, fingerprint) = decode_header(data, &mut ind)?;\n");
+	source.push_str("\t\tlet typeid = decode_vuint(data, &mut ind)?;\n");
+	// check fingerprint if present, else decl_path and typeid
+	source.push_str("\t\tlet is_compatible = match fingerprint {\n");
+	write!(source, "\t\t\tSome(fingerprint) => fingerprint == 0x{fingerprint:016x},\n").unwrap();
+	write!(source, "\t\t\tNone => decl_path == {file:?} && typeid == {},\n", item.typeid())
+		.unwrap();
+	source.push_str("\t\t};\n");
+	source.push_str("\t\tif !is_compatible {\n\t\t\treturn None;\n\t\t}\n");
 	// decode item
 	write!(source, "\t\tlet value = decode_{name}(data, &mut ind)?;\n").unwrap();
 	// check no remaining data
@@ -229,3 +409,142 @@ fn write_serialized_trait(source: &mut String, item: &DeclItem, file: &str) {
 
 	source.push_str("}\n");
 }
+
+/// write the default value expression of a field
+pub fn write_field_default(source: &mut String, field: &Field, ctx: &Ctx) {
+	match &field.default {
+		Some(default) if field.is_optional => {
+			source.push_str("Some(");
+			write_literal(source, default, &field.typeid, ctx);
+			source.push(')');
+		}
+		Some(default) => write_literal(source, default, &field.typeid, ctx),
+		None => source.push_str("Default::default()"),
+	}
+}
+
+/// write a rust expression constructing the value of the given type
+pub fn write_literal(source: &mut String, value: &Value, typeid: &TypeId, ctx: &Ctx) {
+	let value = value.inner();
+	// built-ins
+	if typeid.ns == 0 {
+		let ty = resolve_built_in_type(typeid.id, false);
+		match (typeid.id, value) {
+			(ARR_TYPEID | SET_TYPEID | FIXED_ARR_TYPEID | TUPLE_TYPEID, Value::Arr(items)) => {
+				let (open, close) = match typeid.id {
+					ARR_TYPEID => ("vec![", "]"),
+					SET_TYPEID => ("std::collections::HashSet::from([", "])"),
+					FIXED_ARR_TYPEID => ("[", "]"),
+					_ => ("(", ")"),
+				};
+				source.push_str(open);
+				for (ind, item) in items.iter().enumerate() {
+					let itemid = typeid.item.as_deref().unwrap_or_else(|| &typeid.args[ind]);
+					write_literal(source, item, itemid, ctx);
+					source.push_str(", ");
+				}
+				source.push_str(close);
+			}
+			(MAP_TYPEID, Value::Map(map)) => {
+				let keyid = TypeId::new(0, typeid.variant, None);
+				source.push_str("HashMap::from([");
+				for (key, item) in map.iter() {
+					source.push('(');
+					write_literal(source, &Value::from(key.clone()), &keyid, ctx);
+					source.push_str(", ");
+					write_literal(source, item, typeid.item.as_ref().unwrap(), ctx);
+					source.push_str("), ");
+				}
+				source.push_str("])");
+			}
+			(_, Value::Float(nb)) if nb.is_nan() => write!(source, "{ty}::NAN").unwrap(),
+			(_, Value::Float(nb)) if nb.is_infinite() => {
+				write!(source, "{}{ty}::INFINITY", if *nb < 0. { "-" } else { "" }).unwrap()
+			}
+			(_, Value::Float(nb)) => write!(source, "{nb:?}").unwrap(),
+			(_, Value::Bool(value)) => write!(source, "{value}").unwrap(),
+			(_, Value::Int(nb)) => write!(source, "{nb}").unwrap(),
+			(_, Value::Uint(nb)) => write!(source, "{nb}").unwrap(),
+			(_, Value::BigInt(bytes)) => write!(source, "vec!{bytes:?}").unwrap(),
+			(_, Value::Str(str)) => write!(source, "{str:?}.to_string()").unwrap(),
+			(_, Value::UUID(bytes)) => write!(source, "{bytes:?}").unwrap(),
+			(_, Value::Decimal(Decimal { value, scale })) => {
+				write!(source, "structom::Decimal::new({value}, {scale})").unwrap()
+			}
+			(_, Value::Dur(dur)) => {
+				let nanos = dur.num_nanoseconds().unwrap_or(i64::MAX);
+				write!(source, "chrono::TimeDelta::nanoseconds({nanos})").unwrap()
+			}
+			// rich types are parsed from their string representation
+			(_, Value::Inst(inst)) => {
+				write!(source, "{:?}.parse::<{ty}>().unwrap()", inst.to_rfc3339()).unwrap()
+			}
+			(_, Value::InstZ(inst)) => {
+				write!(source, "{:?}.parse::<{ty}>().unwrap()", inst.to_rfc3339()).unwrap()
+			}
+			(_, Value::Date(date)) => {
+				write!(source, "\"{date}\".parse::<{ty}>().unwrap()").unwrap()
+			}
+			(_, Value::Time(time)) => {
+				write!(source, "\"{time}\".parse::<{ty}>().unwrap()").unwrap()
+			}
+			_ => unreachable!(),
+		}
+		return;
+	}
+
+	// user defined
+	let Ctx { file, provider, .. } = ctx;
+	let file = if typeid.ns == file.id { *file } else { provider.get(typeid.ns) };
+	let item = file.get_by_id(typeid.id).unwrap();
+
+	// generic arguments in expressions require a turbofish
+	let mut path = String::new();
+	write_type(&mut path, typeid, ctx);
+	let path = path.replacen('<', "::<", 1);
+
+	match (item, value) {
+		(DeclItem::Flags { .. }, Value::Arr(flags)) => {
+			let mask = flags.iter().fold(0u64, |mask, flag| {
+				let Value::UnitVar(flag) = flag else { unreachable!() };
+				mask | 1 << item.get_flag_by_name(flag).unwrap()
+			});
+			write!(source, "{path}({mask})").unwrap();
+		}
+		(DeclItem::Enum { .. }, Value::UnitVar(variant)) => {
+			write!(source, "{path}::{variant}").unwrap()
+		}
+		(DeclItem::Enum { .. }, Value::Map(map)) => {
+			let variant = value.enum_variant().unwrap();
+			let def = item.get_variant_by_name(variant).unwrap().def.as_ref().unwrap();
+			write!(source, "{path}::{variant} {{").unwrap();
+			write_fields_literal(source, map, def, &typeid.args, ctx);
+			source.push('}');
+		}
+		(DeclItem::Struct { def, .. }, Value::Map(map)) => {
+			write!(source, "{path} {{").unwrap();
+			write_fields_literal(source, map, def, &typeid.args, ctx);
+			source.push('}');
+		}
+		_ => unreachable!(),
+	}
+}
+/// write the fields of a struct literal
+fn write_fields_literal(
+	source: &mut String, map: &HashMap<Key, Value>, def: &StructDef, args: &[TypeId], ctx: &Ctx,
+) {
+	for field in def.fields.iter().flat_map(|f| f.as_ref()) {
+		write!(source, " {}: ", field.name).unwrap();
+		match map.get(&Key::from(field.name.as_str())) {
+			Some(value) if field.is_optional => {
+				source.push_str("Some(");
+				write_literal(source, value, &field.typeid.substitute(args), ctx);
+				source.push(')');
+			}
+			Some(value) => write_literal(source, value, &field.typeid.substitute(args), ctx),
+			None => source.push_str("None"),
+		}
+		source.push(',');
+	}
+	source.push(' ');
+}
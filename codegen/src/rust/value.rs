@@ -1,36 +1,73 @@
-use crate::rust::Ctx;
+use crate::{
+	rust::{
+		Ctx, module_items,
+		type_def::{write_generics, write_type},
+	},
+	utils::split_name,
+};
 use std::fmt::Write;
 
-use structom::internal::{DeclItem, EnumVariant, Field};
+use structom::internal::{
+	ARR_TYPEID, DeclItem, EnumVariant, FIXED_ARR_TYPEID, Field, MAP_TYPEID, TUPLE_TYPEID, TypeId,
+};
 
 /// generate value conversion functions
 pub fn gen_value_conv(source: &mut String, ctx: &Ctx) {
-	let Ctx { file, .. } = ctx;
-
-	for (_, item) in &file.items {
+	for item in module_items(ctx) {
+		let ctx = &Ctx { params: item.params(), ..*ctx };
 		match item {
 			DeclItem::Struct { .. } => {
 				from_struct(source, item);
 
-				to_struct(source, item);
+				to_struct(source, item, ctx);
 			}
 			DeclItem::Enum { .. } => {
 				from_enum(source, item);
 
-				to_enum(source, item);
+				to_enum(source, item, ctx);
+			}
+			DeclItem::Flags { .. } => {
+				from_flags(source, item);
+
+				to_flags(source, item);
 			}
 		}
 	}
 }
 
+// bound of generic parameters in conversions into values
+const INTO_BOUND: &str = "Into<Value>";
+
+/// write conversion trait impl header, generic parameters must be convertible too
+fn write_conv_header(source: &mut String, item: &DeclItem, conv_trait: &str) {
+	let is_from = conv_trait == "TryFrom<Value>";
+	source.push_str("impl");
+	write_generics(source, item.params(), if is_from { "" } else { INTO_BOUND });
+	write!(source, " {conv_trait} for {}", split_name(item.name()).1).unwrap();
+	write_generics(source, item.params(), "");
+	if is_from {
+		write_from_bounds(source, item.params());
+	}
+	source.push_str(" {\n");
+}
+/// write where clause bounding generic parameters to be convertible from values,
+/// bounded on `Value` since builtins only implement `TryInto` from it
+fn write_from_bounds(source: &mut String, params: &[String]) {
+	for (ind, param) in params.iter().enumerate() {
+		source.push_str(if ind == 0 { " where " } else { ", " });
+		write!(source, "Value: TryInto<{param}, Error = ()>").unwrap();
+	}
+}
+
 /// generate code for converting value to struct
-fn to_struct(source: &mut String, item: &DeclItem) {
+fn to_struct(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	let DeclItem::Struct { name, def, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
 
 	// header
-	write!(source, "impl TryFrom<Value> for {name} {{\n").unwrap();
+	write_conv_header(source, item, "TryFrom<Value>");
 	source.push_str("\ttype Error = ();\n");
-	write!(source, "\tfn try_from(value: Value) -> Result<{name}, ()> {{\n").unwrap();
+	source.push_str("\tfn try_from(value: Value) -> Result<Self, ()> {\n");
 
 	// extract inner map
 	source.push_str("\t\tlet Value::Map(mut map) = value else { return Err(()); };\n");
@@ -45,14 +82,28 @@ fn to_struct(source: &mut String, item: &DeclItem) {
 				write!(source, "\t\tlet f_{name} = map.remove(&\"{name}\".into()).ok_or(())?;\n")
 					.unwrap();
 			}
+		} else if has_fixed_arr(typeid) {
+			write!(source, "\t\tlet f_{name} = map.remove(&\"{name}\".into())").unwrap();
+			if *is_optional {
+				source.push_str(".map(|v| ");
+				write_from_value(source, typeid, "v");
+				source.push_str(").transpose()?;\n");
+			} else {
+				source.push_str(".ok_or(())?;\n");
+				write!(source, "\t\tlet f_{name} = ").unwrap();
+				write_from_value(source, typeid, &format!("f_{name}"));
+				source.push_str("?;\n");
+			}
 		} else if *is_optional {
 			// extract field if found, fail if couldnt convert
-			write!(source, "\t\tlet f_{name} = map.remove(&\"{name}\".into())").unwrap();
-			source.push_str(".map(|v| v.try_into()).transpose()?;\n");
+			write!(source, "\t\tlet f_{name} = map.remove(&\"{name}\".into()).map(").unwrap();
+			write_try_into(source, typeid, ctx);
+			source.push_str(").transpose()?;\n");
 		} else {
 			// extract field, fail if not found or couldnt convert
-			write!(source, "\t\tlet f_{name} = map.remove(&\"{name}\".into())").unwrap();
-			source.push_str(".ok_or(())?.try_into()?;\n");
+			write!(source, "\t\tlet f_{name} = ").unwrap();
+			write_try_into(source, typeid, ctx);
+			write!(source, "(map.remove(&\"{name}\".into()).ok_or(())?)?;\n").unwrap();
 		}
 	}
 
@@ -74,22 +125,25 @@ fn to_struct(source: &mut String, item: &DeclItem) {
 
 /// generate code for converting struct to value
 fn from_struct(source: &mut String, item: &DeclItem) {
-	let DeclItem::Struct { name, def, .. } = item else { unreachable!() };
+	let DeclItem::Struct { def, .. } = item else { unreachable!() };
 
 	// header
-	write!(source, "impl Into<Value> for {name} {{\n").unwrap();
+	write_conv_header(source, item, "Into<Value>");
 	source.push_str("\tfn into(self) -> Value {\n");
 	source.push_str("\t\tlet mut map = HashMap::new();\n");
 
 	// insert fields
 	for field in def.fields.iter().filter_map(|f| f.as_ref()) {
-		let Field { name, is_optional, .. } = field;
+		let Field { name, is_optional, typeid, .. } = field;
 		if *is_optional {
 			write!(source, "\t\tif let Some(value) = self.{name} {{\n").unwrap();
-			write!(source, "\t\t\tmap.insert(Key::from(\"{name}\"), value.into());\n\t\t}}\n")
-				.unwrap();
+			write!(source, "\t\t\tmap.insert(Key::from(\"{name}\"), ").unwrap();
+			write_into_value(source, typeid, "value");
+			source.push_str(");\n\t\t}\n");
 		} else {
-			write!(source, "\t\tmap.insert(Key::from(\"{name}\"), self.{name}.into());\n").unwrap();
+			write!(source, "\t\tmap.insert(Key::from(\"{name}\"), ").unwrap();
+			write_into_value(source, typeid, &format!("self.{name}"));
+			source.push_str(");\n");
 		}
 	}
 
@@ -97,13 +151,14 @@ fn from_struct(source: &mut String, item: &DeclItem) {
 }
 
 /// generate code for converting value to enum
-fn to_enum(source: &mut String, item: &DeclItem) {
+fn to_enum(source: &mut String, item: &DeclItem, ctx: &Ctx) {
 	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
 
 	// header
-	write!(source, "impl TryFrom<Value> for {name} {{\n").unwrap();
+	write_conv_header(source, item, "TryFrom<Value>");
 	source.push_str("\ttype Error = ();\n");
-	write!(source, "\tfn try_from(value: Value) -> Result<{name}, ()> {{\n").unwrap();
+	source.push_str("\tfn try_from(value: Value) -> Result<Self, ()> {\n");
 
 	// split variants
 	let (fieldless_vars, fieldfull_vars): (Vec<_>, Vec<_>) =
@@ -141,8 +196,13 @@ fn to_enum(source: &mut String, item: &DeclItem) {
 	for variant in fieldfull_vars {
 		let EnumVariant { name: var_name, def: Some(def), .. } = variant else { unreachable!() };
 		// header
-		write!(source, "fn {name}_{var_name}_from_value(value: Value) -> Result<{name}, ()> {{\n")
-			.unwrap();
+		write!(source, "fn {name}_{var_name}_from_value").unwrap();
+		write_generics(source, item.params(), "");
+		write!(source, "(value: Value) -> Result<{name}").unwrap();
+		write_generics(source, item.params(), "");
+		source.push_str(", ()>");
+		write_from_bounds(source, item.params());
+		source.push_str(" {\n");
 		// extract inner map
 		source.push_str("\tlet Value::Map(mut map) = value else { return Err(()); };\n");
 		source.push_str("\tmap.remove(&\"$enum_variant\".into()).ok_or(())?;\n");
@@ -157,14 +217,28 @@ fn to_enum(source: &mut String, item: &DeclItem) {
 					write!(source, "\tlet f_{name} = map.remove(&\"{name}\".into()).ok_or(())?;\n")
 						.unwrap();
 				}
+			} else if has_fixed_arr(typeid) {
+				write!(source, "\tlet f_{name} = map.remove(&\"{name}\".into())").unwrap();
+				if *is_optional {
+					source.push_str(".map(|v| ");
+					write_from_value(source, typeid, "v");
+					source.push_str(").transpose()?;\n");
+				} else {
+					source.push_str(".ok_or(())?;\n");
+					write!(source, "\tlet f_{name} = ").unwrap();
+					write_from_value(source, typeid, &format!("f_{name}"));
+					source.push_str("?;\n");
+				}
 			} else if *is_optional {
 				// extract field if found, fail if couldnt convert
-				write!(source, "\tlet f_{name} = map.remove(&\"{name}\".into())").unwrap();
-				source.push_str(".map(|v| v.try_into()).transpose()?;\n");
+				write!(source, "\tlet f_{name} = map.remove(&\"{name}\".into()).map(").unwrap();
+				write_try_into(source, typeid, ctx);
+				source.push_str(").transpose()?;\n");
 			} else {
 				// extract field, fail if not found or couldnt convert
-				write!(source, "\tlet f_{name} = map.remove(&\"{name}\".into())").unwrap();
-				source.push_str(".ok_or(())?.try_into()?;\n");
+				write!(source, "\tlet f_{name} = ").unwrap();
+				write_try_into(source, typeid, ctx);
+				write!(source, "(map.remove(&\"{name}\".into()).ok_or(())?)?;\n").unwrap();
 			}
 		}
 
@@ -188,9 +262,10 @@ fn to_enum(source: &mut String, item: &DeclItem) {
 /// generate code for converting value to enum
 fn from_enum(source: &mut String, item: &DeclItem) {
 	let DeclItem::Enum { name, variants, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
 
 	// header
-	write!(source, "impl Into<Value> for {name} {{\n").unwrap();
+	write_conv_header(source, item, "Into<Value>");
 	source.push_str("\tfn into(self) -> Value {\n");
 
 	// match based on variant
@@ -217,7 +292,11 @@ fn from_enum(source: &mut String, item: &DeclItem) {
 	for variant in variants.iter().filter_map(|v| v.as_ref().filter(|v| v.def.is_some())) {
 		let EnumVariant { name: var_name, def: Some(def), .. } = variant else { unreachable!() };
 		// header
-		write!(source, "fn {name}_{var_name}_to_value(value: {name}) -> Value {{\n").unwrap();
+		write!(source, "fn {name}_{var_name}_to_value").unwrap();
+		write_generics(source, item.params(), INTO_BOUND);
+		write!(source, "(value: {name}").unwrap();
+		write_generics(source, item.params(), "");
+		source.push_str(") -> Value {\n");
 
 		// extract variant fields
 		write!(source, "\tlet {name}::{var_name} {{\n").unwrap();
@@ -235,16 +314,147 @@ fn from_enum(source: &mut String, item: &DeclItem) {
 		write!(source, "\tlet mut map = HashMap::new();\n").unwrap();
 		write!(source, "\tmap.insert(\"$enum_variant\".into(), \"{var_name}\".into());\n").unwrap();
 
-		for Field { name, is_optional, .. } in fields {
+		for Field { name, is_optional, typeid, .. } in fields {
 			if *is_optional {
 				write!(source, "\tif let Some(value) = f_{name} {{\n").unwrap();
-				write!(source, "\t\tmap.insert(Key::from(\"{name}\"), value.into());\n\t}}\n")
-					.unwrap();
+				write!(source, "\t\tmap.insert(Key::from(\"{name}\"), ").unwrap();
+				write_into_value(source, typeid, "value");
+				source.push_str(");\n\t}\n");
 			} else {
-				write!(source, "\tmap.insert(Key::from(\"{name}\"), f_{name}.into());\n").unwrap();
+				write!(source, "\tmap.insert(Key::from(\"{name}\"), ").unwrap();
+				write_into_value(source, typeid, &format!("f_{name}"));
+				source.push_str(");\n");
 			}
 		}
 
 		source.push_str("\tValue::Map(Box::new(map))\n}\n");
 	}
 }
+
+/// generate code for converting value to flags
+fn to_flags(source: &mut String, item: &DeclItem) {
+	let DeclItem::Flags { name, flags, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
+
+	// header
+	write!(source, "impl TryFrom<Value> for {name} {{\n").unwrap();
+	source.push_str("\ttype Error = ();\n");
+	write!(source, "\tfn try_from(value: Value) -> Result<{name}, ()> {{\n").unwrap();
+
+	// flags are written as an array of flag names
+	source.push_str("\t\tlet Value::Arr(arr) = value else { return Err(()); };\n");
+	write!(source, "\t\tlet mut flags = {name}(0);\n").unwrap();
+	source.push_str("\t\tfor flag in arr {\n");
+	source.push_str("\t\t\tlet Value::UnitVar(flag) = flag else { return Err(()); };\n");
+	source.push_str("\t\t\tflags.insert(match flag.as_str() {\n");
+	for flag in flags.iter().filter_map(|f| f.as_ref()) {
+		write!(source, "\t\t\t\t\"{flag}\" => {name}::{flag},\n").unwrap();
+	}
+	source.push_str("\t\t\t\t_ => return Err(()),\n");
+	source.push_str("\t\t\t});\n\t\t}\n");
+
+	source.push_str("\t\tOk(flags)\n\t}\n}\n");
+}
+
+/// generate code for converting flags to value
+fn from_flags(source: &mut String, item: &DeclItem) {
+	let DeclItem::Flags { name, flags, .. } = item else { unreachable!() };
+	let name = split_name(name).1;
+
+	// header
+	write!(source, "impl Into<Value> for {name} {{\n").unwrap();
+	source.push_str("\tfn into(self) -> Value {\n");
+	source.push_str("\t\tlet mut flags = Vec::new();\n");
+
+	// push set flags by name
+	for flag in flags.iter().filter_map(|f| f.as_ref()) {
+		write!(source, "\t\tif self.contains({name}::{flag}) {{\n").unwrap();
+		write!(source, "\t\t\tflags.push(Value::UnitVar(\"{flag}\".to_string()));\n").unwrap();
+		source.push_str("\t\t}\n");
+	}
+
+	source.push_str("\t\tValue::Arr(flags)\n\t}\n}\n");
+}
+
+/// write the conversion function from value into the type,
+/// qualified since the bounds of generic parameters take over the inference
+fn write_try_into(source: &mut String, typeid: &TypeId, ctx: &Ctx) {
+	source.push_str("TryInto::<");
+	write_type(source, typeid, ctx);
+	source.push_str(">::try_into");
+}
+
+/// fixed length arrays cant implement value conversions, since they collide with uuids
+fn has_fixed_arr(typeid: &TypeId) -> bool {
+	typeid.ns == 0
+		&& (typeid.id == FIXED_ARR_TYPEID
+			|| typeid.item.as_ref().is_some_and(|item| has_fixed_arr(item))
+			|| typeid.args.iter().any(has_fixed_arr))
+}
+
+/// write expression converting `expr` into a value
+fn write_into_value(source: &mut String, typeid: &TypeId, expr: &str) {
+	if !has_fixed_arr(typeid) {
+		write!(source, "{expr}.into()").unwrap();
+		return;
+	}
+	match typeid.id {
+		ARR_TYPEID | FIXED_ARR_TYPEID => {
+			write!(source, "Value::Arr({expr}.into_iter().map(|v| ").unwrap();
+			write_into_value(source, typeid.item.as_ref().unwrap(), "v");
+			source.push_str(").collect())");
+		}
+		MAP_TYPEID => {
+			write!(source, "Value::Map(Box::new({expr}.into_iter().map(|(k, v)| (k.into(), ")
+				.unwrap();
+			write_into_value(source, typeid.item.as_ref().unwrap(), "v");
+			source.push_str(")).collect()))");
+		}
+		TUPLE_TYPEID => {
+			write!(source, "{{ let t = {expr}; Value::Arr(vec![").unwrap();
+			for (ind, item) in typeid.args.iter().enumerate() {
+				write_into_value(source, item, &format!("t.{ind}"));
+				source.push_str(", ");
+			}
+			source.push_str("]) }");
+		}
+		_ => unreachable!(),
+	}
+}
+
+/// write expression converting the value `expr` into a `Result` of the type
+fn write_from_value(source: &mut String, typeid: &TypeId, expr: &str) {
+	if !has_fixed_arr(typeid) {
+		write!(source, "TryInto::try_into({expr}).map_err(|_| ())").unwrap();
+		return;
+	}
+	match typeid.id {
+		ARR_TYPEID | FIXED_ARR_TYPEID => {
+			write!(source, "match {expr} {{ Value::Arr(v) => v.into_iter().map(|v| ").unwrap();
+			write_from_value(source, typeid.item.as_ref().unwrap(), "v");
+			source.push_str(").collect::<Result<Vec<_>, ()>>()");
+			// fixed length arrays are collected into vec then converted
+			if typeid.id == FIXED_ARR_TYPEID {
+				source.push_str(".and_then(|v| v.try_into().map_err(|_| ()))");
+			}
+			source.push_str(", _ => Err(()) }");
+		}
+		MAP_TYPEID => {
+			write!(source, "match {expr} {{ Value::Map(m) => m.into_iter().map(|(k, v)| ").unwrap();
+			source.push_str("Ok((TryInto::try_into(k).map_err(|_| ())?, ");
+			write_from_value(source, typeid.item.as_ref().unwrap(), "v");
+			source.push_str("?))).collect(), _ => Err(()) }");
+		}
+		TUPLE_TYPEID => {
+			let len = typeid.args.len();
+			write!(source, "match {expr} {{ Value::Arr(v) if v.len() == {len} => {{ ").unwrap();
+			source.push_str("let mut v = v.into_iter(); Ok((");
+			for item in &typeid.args {
+				write_from_value(source, item, "v.next().unwrap()");
+				source.push_str("?, ");
+			}
+			source.push_str(")) }, _ => Err(()) }");
+		}
+		_ => unreachable!(),
+	}
+}
@@ -1,18 +1,22 @@
 use std::fmt::Write;
 use structom::encoding::encode_vuint;
 
+/// split a qualified item name into its module path and local name
+pub fn split_name(name: &str) -> (&str, &str) {
+	name.rsplit_once('.').unwrap_or(("", name))
+}
+
+/// flatten a qualified item name into a single identifier, `billing.Invoice` -> `billing_Invoice`
+pub fn flat_name(name: &str) -> String {
+	name.replace('.', "_")
+}
+
 /// add identation of certain depth
 pub fn add_ident(str: &mut String, depth: usize) {
 	for _ in 0..depth {
 		str.push('\t');
 	}
 }
-pub fn vuint_to_bytes(nb: u64) -> Vec<u8> {
-	let mut data = Vec::new();
-	encode_vuint(&mut data, nb);
-	data
-}
-
 pub mod errors {
 	use std::fmt::Display;
 
@@ -41,14 +45,12 @@ pub fn new_size_ind() -> u64 {
 	}
 }
 
-pub fn encode_header(source: &mut String, file: &str, typeid: u64) {
-	for byte in vuint_to_bytes(file.len() as u64) {
-		write!(source, "0x{byte:02x}, ").unwrap();
-	}
-	for byte in file.as_bytes() {
-		write!(source, "0x{byte:02x}, ").unwrap();
-	}
-	for byte in vuint_to_bytes(typeid) {
+/// write the bytes of a binary header, with the fingerprint of the root item if given
+pub fn encode_header(source: &mut String, file: &str, typeid: u64, fingerprint: Option<u64>) {
+	let mut data = Vec::new();
+	structom::encoding::encode_header(&mut data, file, fingerprint);
+	encode_vuint(&mut data, typeid);
+	for byte in data {
 		write!(source, "0x{byte:02x}, ").unwrap();
 	}
 }
@@ -0,0 +1,61 @@
+use std::{
+	fs::{create_dir_all, read_to_string, write},
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// declerations with generic items, used by each generated language
+const DECLS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/generic");
+
+/// run codegen over the declerations into a fresh directory
+fn generate(lang: &str) -> PathBuf {
+	let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated").join(lang);
+	let status = Command::new(env!("CARGO_BIN_EXE_structom-codegen"))
+		.args(["-i", DECLS, "-o", out.to_str().unwrap(), "-l", lang])
+		.status()
+		.unwrap();
+	assert!(status.success());
+	out
+}
+
+#[test]
+fn generic_rust_compiles() {
+	let out = generate("rust");
+
+	// crate wrapping the generated code
+	let krate = out.parent().unwrap().join("rust_crate");
+	create_dir_all(krate.join("src")).unwrap();
+	let structom = Path::new(env!("CARGO_MANIFEST_DIR")).join("../rust");
+	let manifest = format!(
+		"[package]\nname = \"generated\"\nversion = \"0.0.0\"\nedition = \"2024\"\n\n\
+		[dependencies]\nstructom = {{ path = {:?} }}\n\n[workspace]\n",
+		structom.display()
+	);
+	write(krate.join("Cargo.toml"), manifest).unwrap();
+	let lib = format!("#![allow(warnings)]\n#[path = {:?}]\nmod out;\n", out.join("mod.rs"));
+	write(krate.join("src/lib.rs"), lib).unwrap();
+
+	let output = Command::new(env!("CARGO"))
+		.args(["check", "--offline", "--quiet"])
+		.current_dir(&krate)
+		.env("CARGO_TARGET_DIR", krate.join("target"))
+		.output()
+		.unwrap();
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn generic_js_signatures() {
+	let out = generate("js");
+	let source = read_to_string(out.join("main.ts")).unwrap();
+	let decoder = "decode_T: (buf: Buffer, cur: Cursor) => T";
+	assert!(source.contains(&format!("decode_Page<T>(buf: Buffer, cur: Cursor, {decoder}): ")));
+	assert!(source.contains(&format!("decode_GenE<T>(buf: Buffer, cur: Cursor, {decoder}): ")));
+
+	// generic items are only decoded through their users
+	let index = read_to_string(out.join("index.ts")).unwrap();
+	assert!(!index.contains("decode_Page(") && !index.contains("decode_GenE("));
+
+	// comment terminators in deprecation reasons are escaped
+	assert!(source.contains("@deprecated use e *\\/ instead"));
+}
@@ -1,8 +1,8 @@
 import { decode_i16, decode_i32, decode_i64, decode_i8, decode_u16, decode_u32, decode_u64, decode_u8, encode_u8, type Buffer, type Cursor } from "./buf.ts";
-import { decode_bool, decode_str, decode_arr, decode_map, encode_bool, encode_str, encode_arr, encode_map } from "./general.ts";
+import { decode_bool, decode_str, decode_arr, decode_fixed_arr, decode_map, decode_set, encode_bool, encode_str, encode_arr, encode_map } from "./general.ts";
 import type { Value } from "./index.ts";
 import { decode_f32, decode_f64, decode_vint, decode_vuint, encode_f64, encode_vint, encode_vuint } from "./number.ts";
-import { decode_dur, decode_inst, decode_instN, decode_uuid, encode_dur, encode_inst, encode_uuid } from "./rich.ts";
+import { decode_date, decode_dec, decode_dur, decode_inst, decode_instN, decode_instZ, decode_time, decode_uuid, encode_date, encode_dec, encode_dur, encode_inst, encode_instZ, encode_time, encode_uuid } from "./rich.ts";
 
 const any_typeid = 0x01;
 const bool_typeid = 0x08;
@@ -16,15 +16,22 @@ const i32_typeid = 0x16;
 const i64_typeid = 0x17;
 const f32_typeid = 0x18;
 const f64_typeid = 0x19;
+const dec_typeid = 0x1b;
 const vuint_typeid = 0x1c;
 const vint_typeid = 0x1d;
 const str_typeid = 0x20;
 const arr_typeid = 0x22;
 const map_typeid = 0x23;
+const tuple_typeid = 0x24;
+const fixed_arr_typeid = 0x25;
+const set_typeid = 0x26;
 const inst_typeid = 0x30;
 const instn_typeid = 0x31;
 const dur_typeid = 0x32;
 const uuid_typeid = 0x33;
+const instz_typeid = 0x34;
+const date_typeid = 0x35;
+const time_typeid = 0x36;
 
 function decode_value(buf: Buffer, typeid: number, cur: Cursor): Value {
 	switch (typeid) {
@@ -43,6 +50,7 @@ function decode_value(buf: Buffer, typeid: number, cur: Cursor): Value {
 
 		case f32_typeid: return decode_f32(buf, cur);
 		case f64_typeid: return decode_f64(buf, cur);
+		case dec_typeid: return decode_dec(buf, cur);
 
 		case vuint_typeid: return decode_vuint(buf, cur);
 		case vint_typeid: return decode_vint(buf, cur);
@@ -52,11 +60,29 @@ function decode_value(buf: Buffer, typeid: number, cur: Cursor): Value {
 		case instn_typeid: return decode_instN(buf, cur);
 		case dur_typeid: return decode_dur(buf, cur);
 		case uuid_typeid: return decode_uuid(buf, cur);
+		case instz_typeid: return decode_instZ(buf, cur);
+		case date_typeid: return decode_date(buf, cur);
+		case time_typeid: return decode_time(buf, cur);
 
 		case arr_typeid: {
 			let item_id = decode_u8(buf, cur);	
 			return decode_arr(buf, cur, (buf, cur) => decode_value(buf, item_id, cur))
 		}
+		case set_typeid: {
+			let item_id = decode_u8(buf, cur);
+			return [...decode_set(buf, cur, (buf, cur) => decode_value(buf, item_id, cur))]
+		}
+		case fixed_arr_typeid: {
+			let item_id = decode_u8(buf, cur);
+			let len = decode_vuint(buf, cur) as number;
+			return decode_fixed_arr(buf, cur, len, (buf, cur) => decode_value(buf, item_id, cur))
+		}
+		case tuple_typeid: {
+			let len = decode_vuint(buf, cur) as number;
+			let item_ids: number[] = [];
+			for (let i = 0; i < len; i++) item_ids.push(decode_u8(buf, cur));
+			return item_ids.map(item_id => decode_value(buf, item_id, cur))
+		}
 		case map_typeid: {
 			let key_id = decode_u8(buf, cur);
 			let value_id = decode_u8(buf, cur);
@@ -85,6 +111,10 @@ function resolve_typeid(value: Value): number {
 	if ('type' in value) switch (value.type) {
 		case 'dur': return dur_typeid;
 		case 'uuid': return uuid_typeid;
+		case 'dec': return dec_typeid;
+		case 'instZ': return instz_typeid;
+		case 'date': return date_typeid;
+		case 'time': return time_typeid;
 	}
 
 	if (Array.isArray(value)) return arr_typeid;
@@ -100,6 +130,10 @@ function encode_value(buf: Buffer, value: Value, typeid: number): void {
 		case inst_typeid: return encode_inst(buf, value as any);
 		case dur_typeid: return encode_dur(buf, value as any);
 		case uuid_typeid: return encode_uuid(buf, value as any);
+		case dec_typeid: return encode_dec(buf, value as any);
+		case instz_typeid: return encode_instZ(buf, value as any);
+		case date_typeid: return encode_date(buf, value as any);
+		case time_typeid: return encode_time(buf, value as any);
 	}
 }
 export function encode_any (buf: Buffer, value: Value): void {
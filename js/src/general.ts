@@ -1,4 +1,4 @@
-import { decode_u8, decode_u8_arr, encode_u8, encode_u8_arr, reserve, type Buffer, type Cursor } from "./buf.ts";
+import { decode_u64, decode_u8, decode_u8_arr, encode_u8, encode_u8_arr, reserve, type Buffer, type Cursor } from "./buf.ts";
 import { decode_vuint, encode_vuint } from "./number.ts";
 
 export function encode_bool(buf: Buffer, value: boolean) {
@@ -21,6 +21,22 @@ export function decode_str (buf: Buffer, cur: Cursor) {
 	return TextDec.decode(decode_u8_arr(buf, size, cur));
 }
 
+/** byte following an empty `decl_path`, marking a header with a fingerprint */
+export const FINGERPRINT_MAGIC = 0xff;
+/** version of the header with a fingerprint */
+export const HEADER_VERSION = 1;
+
+/** decode the header up to the typeid, with the fingerprint if present */
+export function decode_header (buf: Buffer, cur: Cursor) {
+	let fingerprint: bigint | undefined;
+	if (buf.buf[cur.pos] === 0 && buf.buf[cur.pos + 1] === FINGERPRINT_MAGIC) {
+		if (buf.buf[cur.pos + 2] !== HEADER_VERSION) throw new Error("unsupported header version");
+		cur.pos += 3;
+		fingerprint = decode_u64(buf, cur);
+	}
+	return { path: decode_str(buf, cur), fingerprint };
+}
+
 type Encoder <T> = (buf: Buffer, value: T) => void;
 type Decoder <T> = (buf: Buffer, cur: Cursor) => T;
 
@@ -46,6 +62,82 @@ export function decode_arr<T> (buf: Buffer, cur: Cursor, item_fn: Decoder<T>, in
 	}
 }
 
+// sets are encoded as arrays, duplicated items are invalid
+export function encode_set<T> (buf: Buffer, value: Set<T>, item_fn: Encoder<T>, in_field = false) {
+	if (!in_field) 
+		encode_vuint(buf, value.size);
+	for (let val of value) 
+		item_fn(buf, val);
+}
+// rich values are objects, they are compared through a primitive key
+function set_key (value: any): unknown {
+	if (value instanceof Date) return value.getTime();
+	if (value instanceof Uint8Array) return value.join(',');
+	switch (value?.type) {
+		case 'uuid': return value.value.join(',');
+		// instZ items are compared by instant, ignoring their offset
+		case 'instZ': return value.value.getTime();
+		case 'date': return `${value.year}-${value.month}-${value.day}`;
+		case 'time': case 'dur': return value.value;
+	}
+	return value
+}
+export function decode_set<T> (buf: Buffer, cur: Cursor, item_fn: Decoder<T>, in_field = false) {
+	let arr = decode_arr(buf, cur, item_fn, in_field);
+	if (new Set(arr.map(set_key)).size != arr.length) 
+		throw new Error("duplicated set item");
+	return new Set(arr)
+}
+
+// flags are encoded as a bitmask, flags holds the name of each bit
+export function encode_flags<T extends string> (buf: Buffer, value: T[], flags: (T | undefined)[]) {
+	let mask = 0n;
+	for (let val of value) {
+		let bit = flags.indexOf(val);
+		if (bit === -1) 
+			throw new Error(`undeclared flag "${val}"`);
+		mask |= 1n << BigInt(bit);
+	}
+	encode_vuint(buf, mask);
+}
+export function decode_flags<T extends string> (buf: Buffer, cur: Cursor, flags: (T | undefined)[]) {
+	let mask = BigInt(decode_vuint(buf, cur));
+	let value: T[] = [];
+	for (let bit = 0; mask >> BigInt(bit) != 0n; bit++) {
+		if ((mask >> BigInt(bit) & 1n) == 0n) 
+			continue;
+		let flag = flags[bit];
+		if (flag === undefined) 
+			throw new Error("undeclared flag bit");
+		value.push(flag);
+	}
+	return value
+}
+
+// fixed length arrays and tuples have no length prefix
+export function encode_fixed_arr<T> (buf: Buffer, value: T[], len: number, item_fn: Encoder<T>) {
+	if (value.length !== len) throw new Error(`expected ${len} items, found ${value.length}`);
+	for (let val of value) 
+		item_fn(buf, val);
+}
+export function decode_fixed_arr<T> (buf: Buffer, cur: Cursor, len: number, item_fn: Decoder<T>) {
+	let arr: T[] = [];
+	for (let i = 0; i < len; i++) 
+		arr.push(item_fn(buf, cur));
+	return arr
+}
+
+export function encode_tuple (buf: Buffer, value: any[], item_fns: Encoder<any>[]) {
+	for (let i = 0; i < item_fns.length; i++) 
+		item_fns[i](buf, value[i]);
+}
+export function decode_tuple<T extends any[]> (buf: Buffer, cur: Cursor, item_fns: Decoder<any>[]) {
+	let tuple: any[] = [];
+	for (let item_fn of item_fns) 
+		tuple.push(item_fn(buf, cur));
+	return tuple as T
+}
+
 export function encode_map<K, V> (
 	buf: Buffer, value: Map<K, V>, key_fn: Encoder<K>, val_fn: Encoder<V>, 
 	in_field = false
@@ -9,8 +9,32 @@ export interface Dur {
 	type: 'dur',
 	value: bigint
 }
+export interface Dec {
+	type: 'dec',
+	value: bigint,
+	scale: number
+}
+/** instant with its offset in minutes */
+export interface InstZ {
+	type: 'instZ',
+	value: Date,
+	offset: number
+}
+/** calendar date without timezone */
+export interface PlainDate {
+	type: 'date',
+	year: number,
+	month: number,
+	day: number
+}
+/** time of day in nanoseconds since midnight */
+export interface PlainTime {
+	type: 'time',
+	value: bigint
+}
 export type Value = 
-	boolean | number | string | bigint | Date | UUID | Dur | Array<Value> | Map<Value, Value>;
+	boolean | number | string | bigint | Date | UUID | Dur | Dec | InstZ | PlainDate | PlainTime | 
+	Array<Value> | Map<Value, Value>;
 
 export function encode(value: Value) {
 	let _buf = new Uint8Array(256);
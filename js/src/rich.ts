@@ -1,5 +1,6 @@
-import { decode_i64, decode_u32, decode_u8_arr, encode_i64, encode_u32, encode_u8_arr, type Buffer, type Cursor } from "./buf.ts";
-import type { Dur, UUID } from "./index.ts";
+import { decode_i16, decode_i32, decode_i64, decode_u32, decode_u64, decode_u8, decode_u8_arr, encode_i16, encode_i32, encode_i64, encode_u32, encode_u64, encode_u8, encode_u8_arr, type Buffer, type Cursor } from "./buf.ts";
+import type { Dec, Dur, InstZ, PlainDate, PlainTime, UUID } from "./index.ts";
+import { decode_vint, encode_vint } from "./number.ts";
 
 export function encode_uuid (buf: Buffer, value: UUID) {
 	encode_u8_arr(buf, value.value);
@@ -24,9 +25,44 @@ export function decode_instN(buf: Buffer, cur: Cursor) {
 	return new Date(Number(res))
 }
 
+export function encode_instZ(buf: Buffer, value: InstZ) {
+	encode_i64(buf, BigInt(Number(value.value)))
+	encode_i16(buf, value.offset)
+}
+export function decode_instZ(buf: Buffer, cur: Cursor) {
+	let value = new Date(Number(decode_i64(buf, cur)));
+	return { type: 'instZ', value, offset: decode_i16(buf, cur) } satisfies InstZ
+}
+
+const day_as_ms = 24 * 60 * 60 * 1000;
+export function encode_date(buf: Buffer, value: PlainDate) {
+	encode_i32(buf, Date.UTC(value.year, value.month - 1, value.day) / day_as_ms)
+}
+export function decode_date(buf: Buffer, cur: Cursor) {
+	let date = new Date(decode_i32(buf, cur) * day_as_ms);
+	return { 
+		type: 'date', year: date.getUTCFullYear(), month: date.getUTCMonth() + 1, day: date.getUTCDate() 
+	} satisfies PlainDate
+}
+
+export function encode_time(buf: Buffer, value: PlainTime) {
+	encode_u64(buf, value.value)
+}
+export function decode_time(buf: Buffer, cur: Cursor) {
+	return { type: 'time', value: decode_u64(buf, cur) } satisfies PlainTime
+}
+
 export function encode_dur(buf: Buffer, value: Dur) {
 	encode_i64(buf, value.value)
 }
 export function decode_dur(buf: Buffer, cur: Cursor) {
 	return { type: 'dur', value: decode_i64(buf, cur) } satisfies Dur
+}
+export function encode_dec(buf: Buffer, value: Dec) {
+	encode_vint(buf, value.value);
+	encode_u8(buf, value.scale);
+}
+export function decode_dec(buf: Buffer, cur: Cursor) {
+	let value = BigInt(decode_vint(buf, cur));
+	return { type: 'dec', value, scale: decode_u8(buf, cur) } satisfies Dec
 }
\ No newline at end of file
@@ -0,0 +1,307 @@
+use crate::{
+	DeclFile, DeclProvider, ParseError, ParseOptions, VoidProvider,
+	declaration::{DeclItem, EnumVariant, Field, StructDef, TypeId},
+	errors::{ImportError, err},
+	parser::{DeclContext, parse_type_expr},
+};
+
+static VOID_PROVIDER: VoidProvider = VoidProvider {};
+
+/// type of a field, either a [`TypeId`] or a type expression like `"map<str, arr<u32>>"`.
+#[derive(Debug, Clone)]
+pub enum TypeExpr {
+	Id(TypeId),
+	Expr(String),
+}
+
+impl From<TypeId> for TypeExpr {
+	fn from(value: TypeId) -> Self {
+		TypeExpr::Id(value)
+	}
+}
+impl From<&str> for TypeExpr {
+	fn from(value: &str) -> Self {
+		TypeExpr::Expr(value.to_string())
+	}
+}
+impl From<String> for TypeExpr {
+	fn from(value: String) -> Self {
+		TypeExpr::Expr(value)
+	}
+}
+
+/// builder creating a [`DeclFile`] without going through source text.
+///
+/// items take their typeids in declaration order, fields, variants and flags take their tags in order, names are checked for duplicates.
+///
+/// type expressions are parsed like in declaration files, they can reference the items declared before and the imported files.
+///
+/// the first error is kept and returned by [`build`](DeclFileBuilder::build), the later calls are ignored.
+///
+/// ## example
+/// ```
+/// let file = DeclFileBuilder::new("user.stomd")
+/// 	.struct_("User", |s| s.field("id", TypeId::UUID).optional("name", TypeId::STR))
+/// 	.enum_("Role", |e| e.variant("Admin").variant_struct("Member", |s| s.field("team", TypeId::STR)))
+/// 	.struct_("Group", |s| s.field("users", "map<str, arr<User>>").field("roles", "arr<Role>"))
+/// 	.build()
+/// 	.unwrap();
+/// ```
+pub struct DeclFileBuilder<'a> {
+	file: DeclFile,
+	provider: &'a dyn DeclProvider,
+	no_ns_imports: Vec<&'a DeclFile>,
+	ns_imports: Vec<(String, &'a DeclFile)>,
+	/// next item id
+	cur_id: u16,
+	error: Option<ParseError>,
+}
+
+/// fields of a struct, see [`DeclFileBuilder::struct_`].
+#[derive(Debug, Clone, Default)]
+pub struct StructBuilder {
+	fields: Vec<(String, TypeExpr, bool)>,
+}
+
+/// variants of an enum, see [`DeclFileBuilder::enum_`].
+#[derive(Debug, Clone, Default)]
+pub struct EnumBuilder {
+	variants: Vec<(String, Option<StructBuilder>)>,
+}
+
+impl DeclFileBuilder<'static> {
+	/// creates a `DeclFileBuilder` for a file with a given name, with no imports.
+	pub fn new(name: impl Into<String>) -> Self {
+		Self::with_provider(name, &VOID_PROVIDER)
+	}
+}
+
+impl<'a> DeclFileBuilder<'a> {
+	/// creates a `DeclFileBuilder` for a file with a given name, importing through a given provider.
+	pub fn with_provider(name: impl Into<String>, provider: &'a dyn DeclProvider) -> Self {
+		let (no_ns_imports, ns_imports) = (Vec::new(), Vec::new());
+		let file = DeclFile::new(name.into());
+		Self { file, provider, no_ns_imports, ns_imports, cur_id: 0, error: None }
+	}
+
+	/// import a declaration file through the provider, under a namespace if given.
+	pub fn import(self, path: &str, ns: Option<&str>) -> Self {
+		self.apply(|builder| {
+			let file = &builder.file.name;
+			let imported = match builder.provider.load(path) {
+				Ok(imported) => imported,
+				Err(ImportError::NotFound) => {
+					return err!(format!("declaration file \"{path}\" not found"), file);
+				}
+				Err(ImportError::Parse(error)) => return Err(error),
+				Err(ImportError::Other(error)) => {
+					return err!(format!("while importing \"{path}\" encountered: {error}"), file);
+				}
+			};
+
+			if builder.file.imports.contains(&imported.id) {
+				return err!(format!("importing declaration file \"{path}\" twice"), file);
+			}
+			match ns {
+				Some(ns) if builder.ns_imports.iter().any(|(used, _)| used == ns) => {
+					return err!(
+						format!("importing \"{path}\" into used namespace \"{ns}\""),
+						file
+					);
+				}
+				Some(ns) if builder.file.has_name(ns) => {
+					let msg = format!(
+						"importing \"{path}\" into namespace named like existing item \"{ns}\""
+					);
+					return err!(msg, file);
+				}
+				Some(ns) => builder.ns_imports.push((ns.to_string(), imported)),
+				None => builder.no_ns_imports.push(imported),
+			}
+			builder.file.imports.push(imported.id);
+			Ok(())
+		})
+	}
+
+	/// declare a struct, with fields added by `build`.
+	pub fn struct_(self, name: &str, build: impl FnOnce(StructBuilder) -> StructBuilder) -> Self {
+		self.apply(|builder| {
+			let id = builder.item_id(name)?;
+			let def = builder.build_def(name, build(StructBuilder::default()))?;
+			let (name, params, annotations) = (name.to_string(), Vec::new(), Default::default());
+			builder
+				.file
+				.add_item(DeclItem::Struct { name, typeid: id, params, def, annotations });
+			Ok(())
+		})
+	}
+
+	/// declare an enum, with variants added by `build`.
+	pub fn enum_(self, name: &str, build: impl FnOnce(EnumBuilder) -> EnumBuilder) -> Self {
+		self.apply(|builder| {
+			let id = builder.item_id(name)?;
+			let variants = build(EnumBuilder::default()).variants;
+			if variants.is_empty() {
+				let msg = format!("enum \"{name}\" must have at least one variant");
+				return err!(msg, &builder.file.name);
+			}
+
+			let mut decl = DeclItem::new_enum(name.to_string(), id, Vec::new());
+			for (tag, (variant, fields)) in variants.into_iter().enumerate() {
+				if decl.get_variant_by_name(&variant).is_some() {
+					let msg = format!(
+						"declaring variant \"{variant}\" multiple times at enum \"{name}\""
+					);
+					return err!(msg, &builder.file.name);
+				}
+				let def = match fields {
+					Some(fields) => Some(builder.build_def(&format!("{name}.{variant}"), fields)?),
+					None => None,
+				};
+				let (tag, annotations) = (tag as u32, Default::default());
+				_ = decl.add_variant(EnumVariant { name: variant, tag, def, annotations });
+			}
+
+			builder.file.add_item(decl);
+			Ok(())
+		})
+	}
+
+	/// declare flags, taking bits in order.
+	pub fn flags(self, name: &str, flags: &[&str]) -> Self {
+		self.apply(|builder| {
+			let id = builder.item_id(name)?;
+			let file = &builder.file.name;
+			if flags.is_empty() {
+				return err!(format!("flags \"{name}\" must have at least one flag"), file);
+			}
+			if flags.len() > 64 {
+				return err!(
+					format!("maximum number of flag bits (63) reached at flags \"{name}\""),
+					file
+				);
+			}
+
+			let mut decl = DeclItem::new_flags(name.to_string(), id);
+			for (bit, flag) in flags.iter().enumerate() {
+				if decl.get_flag_by_name(flag).is_some() {
+					let msg =
+						format!("declaring flag \"{flag}\" multiple times at flags \"{name}\"");
+					return err!(msg, file);
+				}
+				_ = decl.add_flag(flag.to_string(), bit as u32);
+			}
+
+			builder.file.add_item(decl);
+			Ok(())
+		})
+	}
+
+	/// the built file, or the first error encountered.
+	pub fn build(self) -> Result<DeclFile, ParseError> {
+		if let Some(error) = self.error {
+			return Err(error);
+		}
+		if self.file.items.is_empty() {
+			return err!(format!("no declaration in file \"{}\"", self.file.name), &self.file.name);
+		}
+		Ok(self.file)
+	}
+
+	/// run `f` unless an error was encountered, keeping its error
+	fn apply(mut self, f: impl FnOnce(&mut Self) -> Result<(), ParseError>) -> Self {
+		if self.error.is_none()
+			&& let Err(error) = f(&mut self)
+		{
+			self.error = Some(error);
+		}
+		self
+	}
+
+	/// check the name of a new item and take its id
+	fn item_id(&mut self, name: &str) -> Result<u16, ParseError> {
+		let file = &self.file.name;
+		if self.file.has_name(name) {
+			return err!(format!("declaring item \"{name}\" multiple times"), file);
+		}
+		if self.ns_imports.iter().any(|(ns, _)| ns == name) {
+			let msg = format!("declaring item \"{name}\" with name similar to existing namespace");
+			return err!(msg, file);
+		}
+		if self.cur_id == u16::MAX {
+			let msg =
+				format!("maximum number of item ids ({}) reached at item \"{name}\"", u16::MAX);
+			return err!(msg, file);
+		}
+
+		self.cur_id += 1;
+		Ok(self.cur_id - 1)
+	}
+
+	fn build_def(&mut self, item: &str, fields: StructBuilder) -> Result<StructDef, ParseError> {
+		if fields.fields.is_empty() {
+			return err!(
+				format!("struct \"{item}\" must have at least one field"),
+				&self.file.name
+			);
+		}
+
+		let mut def = StructDef::default();
+		for (tag, (name, typeid, is_optional)) in fields.fields.into_iter().enumerate() {
+			if def.get_field_by_name(&name).is_some() {
+				let msg =
+					format!("declaring a field \"{name}\" multiple times at struct \"{item}\"");
+				return err!(msg, &self.file.name);
+			}
+			let typeid = self.resolve_type(typeid)?;
+			_ = def.add_field(Field::new(name, tag as u32, typeid, is_optional));
+		}
+		Ok(def)
+	}
+
+	/// parse a type expression in the scope of the file, anonymous items take the next ids
+	fn resolve_type(&mut self, typeid: TypeExpr) -> Result<TypeId, ParseError> {
+		let source = match typeid {
+			TypeExpr::Id(typeid) => return Ok(typeid),
+			TypeExpr::Expr(source) => source,
+		};
+
+		let mut ctx = DeclContext::new(&mut self.file, self.provider);
+		ctx.no_ns_imports = self.no_ns_imports.clone();
+		ctx.ns_imports = self.ns_imports.iter().map(|(ns, file)| (ns.as_str(), *file)).collect();
+		ctx.cur_id = self.cur_id;
+
+		let options = ParseOptions { metadata: true, ..Default::default() };
+		let typeid = parse_type_expr(&source, &mut ctx, &options)?;
+		self.cur_id = ctx.cur_id;
+		Ok(typeid)
+	}
+}
+
+impl StructBuilder {
+	/// add a required field.
+	pub fn field(mut self, name: &str, typeid: impl Into<TypeExpr>) -> Self {
+		self.fields.push((name.to_string(), typeid.into(), false));
+		self
+	}
+	/// add an optional field.
+	pub fn optional(mut self, name: &str, typeid: impl Into<TypeExpr>) -> Self {
+		self.fields.push((name.to_string(), typeid.into(), true));
+		self
+	}
+}
+
+impl EnumBuilder {
+	/// add a unit variant.
+	pub fn variant(mut self, name: &str) -> Self {
+		self.variants.push((name.to_string(), None));
+		self
+	}
+	/// add a variant with fields added by `build`.
+	pub fn variant_struct(
+		mut self, name: &str, build: impl FnOnce(StructBuilder) -> StructBuilder,
+	) -> Self {
+		self.variants.push((name.to_string(), Some(build(StructBuilder::default()))));
+		self
+	}
+}
@@ -29,6 +29,7 @@ define_builtins![
 	("i64", I64_TYPEID, 0x17),
 	("f32", F32_TYPEID, 0x18),
 	("f64", F64_TYPEID, 0x19),
+	("dec", DEC_TYPEID, 0x1b),
 	("vuint", VUINT_TYPEID, 0x1c),
 	("vint", VINT_TYPEID, 0x1d),
 	("buint", BUINT_TYPEID, 0x1e),
@@ -36,8 +37,18 @@ define_builtins![
 	("str", STR_TYPEID, 0x20),
 	("arr", ARR_TYPEID, 0x22),
 	("map", MAP_TYPEID, 0x23),
+	("set", SET_TYPEID, 0x26),
 	("inst", INST_TYPEID, 0x30),
 	("instN", INSTN_TYPEID, 0x31),
 	("dur", DUR_TYPEID, 0x32),
 	("uuid", UUID_TYPEID, 0x33),
+	("instZ", INSTZ_TYPEID, 0x34),
+	("date", DATE_TYPEID, 0x35),
+	("time", TIME_TYPEID, 0x36),
 ];
+
+// structural types written through their own syntax, they have no name
+pub const TUPLE_TYPEID: u16 = 0x24;
+pub const FIXED_ARR_TYPEID: u16 = 0x25;
+// generic parameters placeholder, only used inside declarations, variant is the parameter index
+pub const PARAM_TYPEID: u16 = 0x02;
@@ -0,0 +1,402 @@
+//! packing declaration files into a single binary bundle.
+//!
+//! a bundle holds a tree of declaration files, with their names, typeids, tags and metadata, encoded in the structom binary format.
+//!
+//! bundles are loaded through [`BundleProvider`] without parsing the declarations again.
+//!
+//! ## example
+//! ```
+//! let provider = FSProvider::new("/path/to/decls").unwrap();
+//! let file = provider.load_file("user.stomd").unwrap();
+//! let data = bundle::write(&[file], "/path/to/decls", &provider);
+//!
+//! let provider = BundleProvider::new(&data).unwrap();
+//! provider.load("user.stomd").unwrap();
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+	DeclFile, DeclProvider, FixedSetProvider, Key, Value, VoidProvider,
+	declaration::{Annotations, DeclItem, EnumVariant, Field, Reserved, StructDef, TypeId},
+	decode, encode,
+	errors::ImportError,
+	source_provider::normalize_name,
+};
+
+/// version of the bundle layout
+const VERSION: u64 = 1;
+
+/// key of the maps standing for unit variants in defaults, as the binary `any` has no enums
+const UNIT_VAR_KEY: &str = "$unit_variant";
+
+/// array value of the given items
+macro_rules! arr {
+	($($value:expr),*) => { Value::Arr(vec![$(Value::from($value)),*]) };
+}
+
+/// pack declaration files and the files they import into a bundle.
+///
+/// file names are stored relative to `root`, a prefix removed from them, pass `""` to store them as is.
+pub fn write<'a>(files: &[&'a DeclFile], root: &str, provider: &'a dyn DeclProvider) -> Vec<u8> {
+	let mut all = Vec::new();
+	for file in files {
+		collect_file(file, &mut all, provider);
+	}
+
+	// files are referenced by their index, 0 is for builtins
+	let ns = all.iter().enumerate().map(|(ind, file)| (file.id, ind as u64 + 1)).collect();
+	let files = all.iter().map(|file| write_file(file, root, &ns)).collect();
+	encode(&arr![VERSION, Value::Arr(files)])
+}
+fn collect_file<'a>(
+	file: &'a DeclFile, all: &mut Vec<&'a DeclFile>, provider: &'a dyn DeclProvider,
+) {
+	if all.iter().any(|added| added.id == file.id) {
+		return;
+	}
+	all.push(file);
+	for id in &file.imports {
+		collect_file(provider.get(*id), all, provider);
+	}
+}
+
+fn write_file(file: &DeclFile, root: &str, ns: &HashMap<u64, u64>) -> Value {
+	let name = match file.name.strip_prefix(root) {
+		Some(name) if !root.is_empty() => name.trim_start_matches('/'),
+		_ => file.name.as_str(),
+	};
+
+	// maps are written sorted, so bundles of the same files are identical,
+	// items are written in definition order, kept when read
+	let items: Vec<_> = file.order.iter().map(|id| &file.items[id]).collect();
+	let mut aliases: Vec<_> = file.aliases.iter().collect();
+	aliases.sort_by(|a, b| a.0.cmp(b.0));
+	let mut namespaces: Vec<_> = file.exports.namespaces.iter().collect();
+	namespaces.sort_by(|a, b| a.0.cmp(b.0));
+	let mut exported: Vec<_> = file.exports.items.iter().collect();
+	exported.sort_by(|a, b| a.0.cmp(b.0));
+
+	let index = |id: &u64| Value::Uint(ns[id]);
+	let exports = arr![
+		Value::Arr(file.exports.files.iter().map(index).collect()),
+		Value::Arr(
+			namespaces
+				.into_iter()
+				.map(|(name, id)| arr![name.as_str(), index(id)])
+				.collect()
+		),
+		Value::Arr(
+			exported
+				.into_iter()
+				.map(|(name, (id, path))| arr![name.as_str(), index(id), path.as_str()])
+				.collect()
+		)
+	];
+	arr![
+		name,
+		Value::Arr(file.imports.iter().map(index).collect()),
+		Value::Arr(items.into_iter().map(|item| write_item(item, ns)).collect()),
+		Value::Arr(
+			aliases
+				.into_iter()
+				.map(|(name, typeid)| arr![name.as_str(), write_typeid(typeid, ns)])
+				.collect()
+		),
+		write_strs(&file.modules),
+		exports,
+		write_opt(&file.root, |root| write_typeid(root, ns))
+	]
+}
+
+fn write_item(item: &DeclItem, ns: &HashMap<u64, u64>) -> Value {
+	match item {
+		DeclItem::Struct { name, typeid, params, def, annotations } => arr![
+			"struct",
+			name.as_str(),
+			*typeid,
+			write_strs(params),
+			write_annotations(annotations),
+			write_def(def, ns)
+		],
+		DeclItem::Enum { name, typeid, params, variants, reserved, annotations, .. } => {
+			let variants = variants.iter().flatten().map(|variant| {
+				let def = write_opt(&variant.def, |def| write_def(def, ns));
+				let annotations = write_annotations(&variant.annotations);
+				arr![variant.name.as_str(), variant.tag, annotations, def]
+			});
+			arr![
+				"enum",
+				name.as_str(),
+				*typeid,
+				write_strs(params),
+				write_annotations(annotations),
+				write_reserved(reserved),
+				Value::Arr(variants.collect())
+			]
+		}
+		DeclItem::Flags { name, typeid, flags, annotations, .. } => {
+			let flags = flags.iter().enumerate();
+			let flags = flags.filter_map(|(bit, flag)| Some(arr![flag.as_deref()?, bit]));
+			let annotations = write_annotations(annotations);
+			arr!["flags", name.as_str(), *typeid, annotations, Value::Arr(flags.collect())]
+		}
+	}
+}
+fn write_def(def: &StructDef, ns: &HashMap<u64, u64>) -> Value {
+	let fields = def.fields.iter().flatten().map(|field| {
+		let default = write_opt(&field.default, write_default);
+		let (typeid, annotations) =
+			(write_typeid(&field.typeid, ns), write_annotations(&field.annotations));
+		arr![field.name.as_str(), field.tag, typeid, field.is_optional, default, annotations]
+	});
+	let bases = def.bases.iter().map(|base| write_typeid(base, ns));
+	arr![Value::Arr(fields.collect()), Value::Arr(bases.collect()), write_reserved(&def.reserved)]
+}
+/// defaults are written untyped, with unit variants, and so flags, written as tagged maps
+fn write_default(value: &Value) -> Value {
+	match value {
+		Value::UnitVar(name) => {
+			Value::Map(Box::new(HashMap::from([(Key::from(UNIT_VAR_KEY), name.as_str().into())])))
+		}
+		Value::Arr(arr) => Value::Arr(arr.iter().map(write_default).collect()),
+		Value::Map(map) => {
+			let map = map.iter().map(|(key, value)| (key.clone(), write_default(value)));
+			Value::Map(Box::new(map.collect()))
+		}
+		value => value.clone(),
+	}
+}
+fn write_typeid(typeid: &TypeId, ns: &HashMap<u64, u64>) -> Value {
+	let index = if typeid.ns == 0 { 0 } else { ns[&typeid.ns] };
+	let item = write_opt(&typeid.item, |item| write_typeid(item, ns));
+	let args = typeid.args.iter().map(|arg| write_typeid(arg, ns));
+	let metadata = write_opt(&typeid.metadata, |metadata| write_pairs(metadata));
+	arr![index, typeid.id, typeid.variant, item, Value::Arr(args.collect()), metadata]
+}
+fn write_annotations(annotations: &Annotations) -> Value {
+	arr![
+		write_opt(&annotations.doc, |doc| doc.as_str().into()),
+		write_opt(&annotations.metadata, |metadata| write_pairs(metadata)),
+		write_opt(&annotations.deprecated, |reason| reason.as_str().into())
+	]
+}
+fn write_reserved(reserved: &Reserved) -> Value {
+	let tags = reserved.tags.iter().map(|(start, end)| arr![*start, *end]);
+	arr![Value::Arr(tags.collect()), write_strs(&reserved.names)]
+}
+fn write_pairs(pairs: &[(String, String)]) -> Value {
+	Value::Arr(pairs.iter().map(|(name, value)| arr![name.as_str(), value.as_str()]).collect())
+}
+fn write_strs(strs: &[String]) -> Value {
+	Value::Arr(strs.iter().map(|str| Value::from(str.as_str())).collect())
+}
+/// optional values are written as arrays of zero or one item
+fn write_opt<T>(value: &Option<T>, write: impl FnMut(&T) -> Value) -> Value {
+	Value::Arr(value.iter().map(write).collect())
+}
+
+/// decleration provider loading the files of a bundle.
+///
+/// the files are decoded once when created, and are looked up by their stored names.
+///
+/// ## example
+/// ```
+/// let provider = BundleProvider::new(include_bytes!("decls.bundle")).unwrap();
+/// parse("import \"user.stomd\" ... ", &ParseOptions::default(), &provider).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BundleProvider {
+	files: FixedSetProvider,
+}
+
+impl BundleProvider {
+	/// load the declaration files of a bundle.
+	///
+	/// returns `None` if the data is not a valid bundle.
+	pub fn new(data: &[u8]) -> Option<Self> {
+		let bundle = decode(data, &VoidProvider {})?;
+		let [version, files] = bundle.as_slice()? else { return None };
+		if version.as_uint()? != VERSION {
+			return None;
+		}
+		let files = files.as_slice()?;
+
+		// files are created first, so typeids can refer to any of them
+		let decls = files.iter().map(|file| Some(DeclFile::new(read_str(file.get_by_index(0)?)?)));
+		let mut decls: Vec<_> = decls.collect::<Option<_>>()?;
+		let ns: Vec<_> = decls.iter().map(|decl| decl.id).collect();
+		for (decl, file) in decls.iter_mut().zip(files) {
+			read_file(decl, file, &ns)?;
+		}
+		Some(Self { files: FixedSetProvider::new(decls) })
+	}
+}
+impl DeclProvider for BundleProvider {
+	fn get(&self, id: u64) -> &DeclFile {
+		self.files.get(id)
+	}
+	fn load<'a>(&'a self, name: &str) -> Result<&'a DeclFile, ImportError> {
+		self.files.load(&normalize_name(name))
+	}
+	fn files(&self) -> Vec<&DeclFile> {
+		self.files.files()
+	}
+}
+
+fn read_file(decl: &mut DeclFile, value: &Value, ns: &[u64]) -> Option<()> {
+	let [_, imports, items, aliases, modules, exports, root] = value.as_slice()? else {
+		return None;
+	};
+	decl.imports = read_list(imports, |index| read_ns(index, ns))?;
+	for item in items.as_slice()? {
+		decl.add_item(read_item(item, ns)?);
+	}
+	for alias in aliases.as_slice()? {
+		let [name, typeid] = alias.as_slice()? else { return None };
+		decl.add_alias(read_str(name)?, read_typeid(typeid, ns)?);
+	}
+	for module in modules.as_slice()? {
+		decl.add_module(read_str(module)?);
+	}
+
+	let [files, namespaces, items] = exports.as_slice()? else { return None };
+	decl.exports.files = read_list(files, |index| read_ns(index, ns))?;
+	for namespace in namespaces.as_slice()? {
+		let [name, index] = namespace.as_slice()? else { return None };
+		decl.exports.namespaces.insert(read_str(name)?, read_ns(index, ns)?);
+	}
+	for item in items.as_slice()? {
+		let [name, index, path] = item.as_slice()? else { return None };
+		decl.exports
+			.items
+			.insert(read_str(name)?, (read_ns(index, ns)?, read_str(path)?));
+	}
+
+	decl.root = read_opt(root, |root| read_typeid(root, ns))?;
+	Some(())
+}
+
+fn read_item(value: &Value, ns: &[u64]) -> Option<DeclItem> {
+	let values = value.as_slice()?;
+	match (values.first()?.as_str()?, &values[1..]) {
+		("struct", [name, typeid, params, annotations, def]) => Some(DeclItem::Struct {
+			name: read_str(name)?,
+			typeid: typeid.as_uint()? as u16,
+			params: read_list(params, read_str)?,
+			def: read_def(def, ns)?,
+			annotations: read_annotations(annotations)?,
+		}),
+		("enum", [name, typeid, params, annotations, reserved, variants]) => {
+			let typeid = typeid.as_uint()? as u16;
+			let mut item =
+				DeclItem::new_enum(read_str(name)?, typeid, read_list(params, read_str)?);
+			for variant in variants.as_slice()? {
+				let [name, tag, annotations, def] = variant.as_slice()? else { return None };
+				let (name, tag) = (read_str(name)?, tag.as_uint()? as u32);
+				let def = read_opt(def, |def| read_def(def, ns))?;
+				let annotations = read_annotations(annotations)?;
+				item.add_variant(EnumVariant { name, tag, def, annotations }).ok()?;
+			}
+			if let DeclItem::Enum { reserved: own, .. } = &mut item {
+				*own = read_reserved(reserved)?;
+			}
+			item.set_annotations(read_annotations(annotations)?);
+			Some(item)
+		}
+		("flags", [name, typeid, annotations, flags]) => {
+			let mut item = DeclItem::new_flags(read_str(name)?, typeid.as_uint()? as u16);
+			for flag in flags.as_slice()? {
+				let [name, bit] = flag.as_slice()? else { return None };
+				item.add_flag(read_str(name)?, bit.as_uint()? as u32).ok()?;
+			}
+			item.set_annotations(read_annotations(annotations)?);
+			Some(item)
+		}
+		_ => None,
+	}
+}
+fn read_def(value: &Value, ns: &[u64]) -> Option<StructDef> {
+	let [fields, bases, reserved] = value.as_slice()? else { return None };
+	let mut def = StructDef::default();
+	for field in fields.as_slice()? {
+		let [name, tag, typeid, is_optional, default, annotations] = field.as_slice()? else {
+			return None;
+		};
+		let (name, tag) = (read_str(name)?, tag.as_uint()? as u32);
+		let mut field = Field::new(name, tag, read_typeid(typeid, ns)?, is_optional.as_bool()?);
+		// set before adding, since it decides whether the field is required
+		field.default = read_opt(default, read_default)?;
+		field.annotations = read_annotations(annotations)?;
+		def.add_field(field).ok()?;
+	}
+	def.bases = read_list(bases, |base| read_typeid(base, ns))?;
+	def.reserved = read_reserved(reserved)?;
+	Some(def)
+}
+fn read_default(value: &Value) -> Option<Value> {
+	Some(match value {
+		Value::Map(map) if map.len() == 1 && map.contains_key(&Key::from(UNIT_VAR_KEY)) => {
+			Value::UnitVar(read_str(&map[&Key::from(UNIT_VAR_KEY)])?)
+		}
+		Value::Arr(arr) => Value::Arr(arr.iter().map(read_default).collect::<Option<_>>()?),
+		Value::Map(map) => {
+			let map = map.iter().map(|(key, value)| Some((key.clone(), read_default(value)?)));
+			Value::Map(Box::new(map.collect::<Option<_>>()?))
+		}
+		value => value.clone(),
+	})
+}
+fn read_typeid(value: &Value, ns: &[u64]) -> Option<TypeId> {
+	let [index, id, variant, item, args, metadata] = value.as_slice()? else { return None };
+	Some(TypeId {
+		ns: read_ns(index, ns)?,
+		id: id.as_uint()? as u16,
+		variant: variant.as_uint()? as u16,
+		item: read_opt(item, |item| read_typeid(item, ns).map(Box::new))?,
+		args: read_list(args, |arg| read_typeid(arg, ns))?,
+		metadata: read_opt(metadata, read_pairs)?,
+	})
+}
+fn read_annotations(value: &Value) -> Option<Annotations> {
+	let [doc, metadata, deprecated] = value.as_slice()? else { return None };
+	Some(Annotations {
+		doc: read_opt(doc, read_str)?,
+		metadata: read_opt(metadata, read_pairs)?,
+		deprecated: read_opt(deprecated, read_str)?,
+	})
+}
+fn read_reserved(value: &Value) -> Option<Reserved> {
+	let [tags, names] = value.as_slice()? else { return None };
+	let tags = read_list(tags, |range| {
+		let [start, end] = range.as_slice()? else { return None };
+		Some((start.as_uint()? as u32, end.as_uint()? as u32))
+	})?;
+	Some(Reserved { tags, names: read_list(names, read_str)? })
+}
+fn read_pairs(value: &Value) -> Option<Vec<(String, String)>> {
+	read_list(value, |pair| {
+		let [name, value] = pair.as_slice()? else { return None };
+		Some((read_str(name)?, read_str(value)?))
+	})
+}
+/// file id of a file index
+fn read_ns(value: &Value, ns: &[u64]) -> Option<u64> {
+	match value.as_uint()? {
+		0 => Some(0),
+		index => ns.get(index as usize - 1).copied(),
+	}
+}
+fn read_str(value: &Value) -> Option<String> {
+	value.as_str().map(str::to_string)
+}
+fn read_list<T>(value: &Value, read: impl FnMut(&Value) -> Option<T>) -> Option<Vec<T>> {
+	value.as_slice()?.iter().map(read).collect()
+}
+fn read_opt<T>(value: &Value, read: impl FnOnce(&Value) -> Option<T>) -> Option<Option<T>> {
+	match value.as_slice()? {
+		[] => Some(None),
+		[value] => read(value).map(Some),
+		_ => None,
+	}
+}
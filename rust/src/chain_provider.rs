@@ -0,0 +1,106 @@
+use std::{collections::HashMap, io, path::PathBuf, sync::RwLock};
+
+use crate::{DeclFile, DeclProvider, FSProvider, errors::ImportError};
+
+/// provider that searches declerations through several providers in order.
+///
+/// each provider is a layer labeled by a name, a file is loaded from the first layer that has it, the imports of the file are loaded through the whole chain.
+///
+/// package imports `"pkg:path/to/file.stomd"` are searched through the layers with the `pkg:` prefix removed, making them independent from the location of the importing file.
+///
+/// the layer a file came from is recorded, it is reported in errors and by [`origin`](ChainProvider::origin).
+///
+/// the layers should only be used through the chain, since their files can reference files from other layers.
+///
+/// it is thread safe as its layers must be, see [`DeclProvider`].
+///
+/// ## example
+/// ```
+/// let provider = ChainProvider::from_roots(["/path/to/service", "/path/to/vendor"]).unwrap();
+///
+/// // loads money.stomd from vendor, unless service has it
+/// parse("import \"pkg:common/money.stomd\" ... ", &ParseOptions::default(), &provider).unwrap();
+/// ```
+pub struct ChainProvider {
+	layers: Vec<(String, Box<dyn DeclProvider + Send + Sync>)>,
+	origins: RwLock<HashMap<u64, usize>>,
+}
+
+impl ChainProvider {
+	/// creates an empty `ChainProvider`.
+	pub fn new() -> Self {
+		Self { layers: Vec::new(), origins: Default::default() }
+	}
+	/// creates a `ChainProvider` searching in given root directories in order with default options.
+	///
+	/// each layer is labeled by its root directory.
+	pub fn from_roots(roots: impl IntoIterator<Item = impl Into<PathBuf>>) -> io::Result<Self> {
+		let mut chain = ChainProvider::new();
+		for root in roots {
+			let root = root.into();
+			chain.push(root.display().to_string(), FSProvider::new(root)?);
+		}
+		Ok(chain)
+	}
+
+	/// add a provider at the end of the chain, labeled by a given name.
+	pub fn push(
+		&mut self, label: impl Into<String>, provider: impl DeclProvider + Send + Sync + 'static,
+	) {
+		self.layers.push((label.into(), Box::new(provider)));
+	}
+
+	/// the label of the layer a loaded file came from.
+	pub fn origin(&self, id: u64) -> Option<&str> {
+		self.find(id)?;
+		let ind = *self.origins.read().unwrap().get(&id)?;
+		Some(&self.layers[ind].0)
+	}
+}
+impl Default for ChainProvider {
+	fn default() -> Self {
+		ChainProvider::new()
+	}
+}
+impl DeclProvider for ChainProvider {
+	fn get(&self, id: u64) -> &DeclFile {
+		self.find(id).unwrap()
+	}
+	fn find(&self, id: u64) -> Option<&DeclFile> {
+		let ind = self.origins.read().unwrap().get(&id).copied();
+		if let Some(ind) = ind {
+			return Some(self.layers[ind].1.get(id));
+		}
+
+		// files reloaded by a layer, like the importers of a changed file, are not loaded through the chain
+		let mut layers = self.layers.iter().enumerate();
+		let (ind, file) = layers.find_map(|(ind, (_, layer))| Some((ind, layer.find(id)?)))?;
+		self.origins.write().unwrap().insert(id, ind);
+		Some(file)
+	}
+	/// gets a decleration file with a given name.
+	///
+	/// tries the layers in order, returns `ImportError` from the first layer that has the file, or `NotFound` if none has it.
+	fn load<'a>(&'a self, name: &str) -> Result<&'a DeclFile, ImportError> {
+		let name = name.strip_prefix("pkg:").unwrap_or(name);
+		for (ind, (label, layer)) in self.layers.iter().enumerate() {
+			match layer.load_with(name, self) {
+				Ok(file) => {
+					self.origins.write().unwrap().insert(file.id, ind);
+					return Ok(file);
+				}
+				Err(ImportError::NotFound) => continue,
+				Err(ImportError::Parse(error)) => {
+					return Err(ImportError::Parse(error.with_origin(label)));
+				}
+				Err(ImportError::Other(error)) => {
+					return Err(ImportError::Other(format!("{error}, from \"{label}\"")));
+				}
+			}
+		}
+		Err(ImportError::NotFound)
+	}
+	fn files(&self) -> Vec<&DeclFile> {
+		self.layers.iter().flat_map(|(_, layer)| layer.files()).collect()
+	}
+}
@@ -0,0 +1,324 @@
+//! checking the compatibility between two versions of a declaration file.
+//!
+//! every change is classified by whether the new version can read data written by the old one (backward), and whether the old version can read data written by the new one (forward).
+//!
+//! a change is breaking when it breaks the required [`Direction`], backward by default.
+//!
+//! the classification follows the binary format, where fields, variants and flags are identified by their tags, object notation data identifies them by name and breaks on renames.
+//!
+//! ## example
+//! ```
+//! let old = old_provider.load_file("user.stomd").unwrap();
+//! let new = new_provider.load_file("user.stomd").unwrap();
+//! for change in compat::check(old, new, &old_provider, &new_provider) {
+//! 	println!("{change}"); // => User.email: field added at tag 3, safe
+//! }
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+	DeclFile, DeclProvider,
+	declaration::{DeclItem, StructDef, TypeId},
+};
+
+/// a change between two versions of a declaration file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+	/// qualified name of the changed item, followed by the field, variant or flag name if any
+	pub path: String,
+	pub kind: ChangeKind,
+	/// whether the new version can read data written by the old one
+	pub backward: bool,
+	/// whether the old version can read data written by the new one
+	pub forward: bool,
+}
+
+/// direction of compatibility required between two versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+	/// the new version can read data written by the old one
+	#[default]
+	Backward,
+	/// the old version can read data written by the new one
+	Forward,
+	/// both backward and forward
+	Full,
+}
+
+/// kind of a [`Change`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+	ItemAdded,
+	ItemRemoved,
+	/// item changed between struct, enum and flags
+	ItemKindChanged,
+	TypeIdChanged {
+		old: u16,
+		new: u16,
+	},
+	ParamsChanged,
+	FieldAdded {
+		tag: u32,
+	},
+	FieldRemoved {
+		tag: u32,
+	},
+	/// field, variant or flag renamed keeping its tag
+	Renamed {
+		tag: u32,
+		old: String,
+	},
+	/// tag used by a member of another name and type, or a reserved tag
+	TagReused {
+		tag: u32,
+	},
+	TypeChanged {
+		old: String,
+		new: String,
+	},
+	OptionalToRequired,
+	RequiredToOptional,
+	VariantAdded {
+		tag: u32,
+	},
+	VariantRemoved {
+		tag: u32,
+	},
+	/// variant changed between unit and struct variant
+	VariantKindChanged,
+	FlagAdded {
+		bit: u32,
+	},
+	FlagRemoved {
+		bit: u32,
+	},
+}
+
+impl Change {
+	/// whether the change breaks backward compatibility, the new version can't read old data
+	pub fn is_breaking(&self) -> bool {
+		self.breaks(Direction::Backward)
+	}
+	/// whether the change breaks the required direction
+	pub fn breaks(&self, direction: Direction) -> bool {
+		match direction {
+			Direction::Backward => !self.backward,
+			Direction::Forward => !self.forward,
+			Direction::Full => !self.backward || !self.forward,
+		}
+	}
+}
+
+impl Display for Change {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let compat = match (self.backward, self.forward) {
+			(true, true) => "safe",
+			(false, true) => "breaks backward",
+			(true, false) => "breaks forward",
+			(false, false) => "breaks backward and forward",
+		};
+		write!(f, "{}: {}, {compat}", self.path, self.kind)
+	}
+}
+impl Display for ChangeKind {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::ItemAdded => write!(f, "item added"),
+			Self::ItemRemoved => write!(f, "item removed"),
+			Self::ItemKindChanged => write!(f, "item kind changed"),
+			Self::TypeIdChanged { old, new } => write!(f, "typeid changed from {old} to {new}"),
+			Self::ParamsChanged => write!(f, "generic parameters changed"),
+			Self::FieldAdded { tag } => write!(f, "field added at tag {tag}"),
+			Self::FieldRemoved { tag } => write!(f, "field removed from tag {tag}"),
+			Self::Renamed { tag, old } => write!(f, "renamed from \"{old}\" at tag {tag}"),
+			Self::TagReused { tag } => write!(f, "tag {tag} reused"),
+			Self::TypeChanged { old, new } => write!(f, "type changed from {old} to {new}"),
+			Self::OptionalToRequired => write!(f, "optional field made required"),
+			Self::RequiredToOptional => write!(f, "required field made optional"),
+			Self::VariantAdded { tag } => write!(f, "variant added at tag {tag}"),
+			Self::VariantRemoved { tag } => write!(f, "variant removed from tag {tag}"),
+			Self::VariantKindChanged => write!(f, "variant changed between unit and struct"),
+			Self::FlagAdded { bit } => write!(f, "flag added at bit {bit}"),
+			Self::FlagRemoved { bit } => write!(f, "flag removed from bit {bit}"),
+		}
+	}
+}
+
+/// the two versions being compared
+struct Ctx<'a> {
+	old: &'a dyn DeclProvider,
+	new: &'a dyn DeclProvider,
+	changes: Vec<Change>,
+}
+impl Ctx<'_> {
+	fn add(&mut self, path: impl Into<String>, kind: ChangeKind, backward: bool, forward: bool) {
+		self.changes.push(Change { path: path.into(), kind, backward, forward });
+	}
+}
+
+/// list the changes between two versions of a declaration file.
+///
+/// items are matched by name, and their members by tag, the providers resolve the types of each version.
+pub fn check(
+	old: &DeclFile, new: &DeclFile, old_provider: &dyn DeclProvider,
+	new_provider: &dyn DeclProvider,
+) -> Vec<Change> {
+	let mut ctx = Ctx { old: old_provider, new: new_provider, changes: Vec::new() };
+
+	let mut names: Vec<_> = old.items_by_name.keys().chain(new.items_by_name.keys()).collect();
+	names.sort();
+	names.dedup();
+	for name in names {
+		match (old.get_by_name(name), new.get_by_name(name)) {
+			// new data is only written by new readers
+			(None, Some(_)) => ctx.add(name, ChangeKind::ItemAdded, true, true),
+			(Some(_), None) => ctx.add(name, ChangeKind::ItemRemoved, false, true),
+			(Some(old), Some(new)) => check_item(old, new, &mut ctx),
+			(None, None) => unreachable!(),
+		}
+	}
+	ctx.changes
+}
+
+fn check_item(old: &DeclItem, new: &DeclItem, ctx: &mut Ctx) {
+	let name = new.name();
+	if old.typeid() != new.typeid() {
+		let kind = ChangeKind::TypeIdChanged { old: old.typeid(), new: new.typeid() };
+		ctx.add(name, kind, false, false);
+	}
+	if old.params().len() != new.params().len() {
+		ctx.add(name, ChangeKind::ParamsChanged, false, false);
+	}
+
+	match (old, new) {
+		(DeclItem::Struct { def: old, .. }, DeclItem::Struct { def: new, .. }) => {
+			check_def(name, old, new, ctx);
+		}
+		(DeclItem::Enum { variants: old, reserved, .. }, DeclItem::Enum { variants: new, .. }) => {
+			for tag in 0..old.len().max(new.len()) as u32 {
+				let old = old.get(tag as usize).and_then(|variant| variant.as_ref());
+				let new = new.get(tag as usize).and_then(|variant| variant.as_ref());
+				match (old, new) {
+					(Some(old), Some(new)) => {
+						let path = format!("{name}.{}", new.name);
+						if old.name != new.name {
+							let kind = ChangeKind::Renamed { tag, old: old.name.clone() };
+							ctx.add(&path, kind, true, true);
+						}
+						match (&old.def, &new.def) {
+							(Some(old), Some(new)) => check_def(&path, old, new, ctx),
+							(None, None) => (),
+							_ => ctx.add(path, ChangeKind::VariantKindChanged, false, false),
+						}
+					}
+					// old data can hold the removed variant, old readers fail on the added one
+					(Some(old), None) => {
+						let path = format!("{name}.{}", old.name);
+						ctx.add(path, ChangeKind::VariantRemoved { tag }, false, true);
+					}
+					(None, Some(new)) => {
+						let path = format!("{name}.{}", new.name);
+						match reserved.has_tag(tag) {
+							true => ctx.add(path, ChangeKind::TagReused { tag }, false, false),
+							false => ctx.add(path, ChangeKind::VariantAdded { tag }, true, false),
+						}
+					}
+					(None, None) => (),
+				}
+			}
+		}
+		(DeclItem::Flags { flags: old, .. }, DeclItem::Flags { flags: new, .. }) => {
+			for bit in 0..old.len().max(new.len()) as u32 {
+				let old = old.get(bit as usize).and_then(|flag| flag.as_ref());
+				let new = new.get(bit as usize).and_then(|flag| flag.as_ref());
+				match (old, new) {
+					(Some(old), Some(new)) if old != new => {
+						let kind = ChangeKind::Renamed { tag: bit, old: old.clone() };
+						ctx.add(format!("{name}.{new}"), kind, true, true);
+					}
+					(Some(old), None) => {
+						let path = format!("{name}.{old}");
+						ctx.add(path, ChangeKind::FlagRemoved { bit }, false, true);
+					}
+					(None, Some(new)) => {
+						let path = format!("{name}.{new}");
+						ctx.add(path, ChangeKind::FlagAdded { bit }, true, false);
+					}
+					_ => (),
+				}
+			}
+		}
+		_ => ctx.add(name, ChangeKind::ItemKindChanged, false, false),
+	}
+}
+
+fn check_def(path: &str, old: &StructDef, new: &StructDef, ctx: &mut Ctx) {
+	for tag in 0..old.fields.len().max(new.fields.len()) as u32 {
+		match (old.get_field_by_id(tag), new.get_field_by_id(tag)) {
+			(Some(old), Some(new)) => {
+				let path = format!("{path}.{}", new.name);
+				let same_type = same_type(&old.typeid, &new.typeid, ctx);
+				if old.name != new.name && !same_type {
+					ctx.add(path, ChangeKind::TagReused { tag }, false, false);
+					continue;
+				}
+				if old.name != new.name {
+					let kind = ChangeKind::Renamed { tag, old: old.name.clone() };
+					ctx.add(&path, kind, true, true);
+				}
+				if !same_type {
+					let old = old.typeid.name(ctx.old);
+					let new = new.typeid.name(ctx.new);
+					ctx.add(&path, ChangeKind::TypeChanged { old, new }, false, false);
+				}
+				// old data can miss newly required fields, old readers need no longer required ones
+				match (old.is_required(), new.is_required()) {
+					(false, true) => ctx.add(&path, ChangeKind::OptionalToRequired, false, true),
+					(true, false) => ctx.add(&path, ChangeKind::RequiredToOptional, true, false),
+					_ => (),
+				}
+			}
+			// unknown fields are skipped, missing ones are accepted only if not required
+			(Some(old), None) => {
+				let path = format!("{path}.{}", old.name);
+				ctx.add(path, ChangeKind::FieldRemoved { tag }, true, !old.is_required());
+			}
+			(None, Some(new)) => {
+				let path = format!("{path}.{}", new.name);
+				match old.reserved.has_tag(tag) {
+					true => ctx.add(path, ChangeKind::TagReused { tag }, false, false),
+					false => {
+						ctx.add(path, ChangeKind::FieldAdded { tag }, !new.is_required(), true)
+					}
+				}
+			}
+			(None, None) => (),
+		}
+	}
+}
+
+/// whether two types have the same layout, user types are matched by name
+fn same_type(old: &TypeId, new: &TypeId, ctx: &Ctx) -> bool {
+	let same_args = old.args.len() == new.args.len()
+		&& old.args.iter().zip(&new.args).all(|(old, new)| same_type(old, new, ctx));
+	if !same_args {
+		return false;
+	}
+	match (old.is_builtin(), new.is_builtin()) {
+		(true, true) => {
+			old.id == new.id
+				&& old.variant == new.variant
+				&& match (&old.item, &new.item) {
+					(Some(old), Some(new)) => same_type(old, new, ctx),
+					(old, new) => old.is_none() && new.is_none(),
+				}
+		}
+		(false, false) => {
+			let old = ctx.old.get(old.ns).get_by_id(old.id).unwrap();
+			let new = ctx.new.get(new.ns).get_by_id(new.id).unwrap();
+			old.name() == new.name()
+		}
+		_ => false,
+	}
+}
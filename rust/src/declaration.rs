@@ -1,10 +1,25 @@
-use std::collections::HashMap;
-
-use crate::{builtins::BUILT_INS_NAMES, errors::ImportError};
+use std::{
+	borrow::Cow,
+	collections::HashMap,
+	sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+	builtins::{
+		BINT_TYPEID, BOOL_TYPEID, BUILT_INS_NAMES, BUINT_TYPEID, DATE_TYPEID, DEC_TYPEID,
+		DUR_TYPEID, F32_TYPEID, F64_TYPEID, FIXED_ARR_TYPEID, I8_TYPEID, I16_TYPEID, I32_TYPEID,
+		I64_TYPEID, INST_TYPEID, INSTN_TYPEID, INSTZ_TYPEID, PARAM_TYPEID, SET_TYPEID, STR_TYPEID,
+		TIME_TYPEID, TUPLE_TYPEID, U8_TYPEID, U16_TYPEID, U32_TYPEID, U64_TYPEID, UUID_TYPEID,
+		VINT_TYPEID, VUINT_TYPEID,
+	},
+	errors::ImportError,
+	fingerprint,
+	value::Value,
+};
 
 /// encapsulate the content of a decleration file.
 ///
-/// can only be created through [`parse_declaration_file`](crate::parse_declaration_file).
+/// can only be created through [`parse_declaration_file`](crate::parse_declaration_file) or [`DeclFileBuilder`](crate::DeclFileBuilder).
 #[derive(Debug)]
 pub struct DeclFile {
 	/// name of the file, passed though name argument in [`parse_declaration_file`](crate::parse_declaration_file)
@@ -14,29 +29,72 @@ pub struct DeclFile {
 	#[doc(hidden)]
 	pub items: HashMap<u16, DeclItem>,
 	pub(crate) items_by_name: HashMap<String, u16>,
+	/// typeids of the items in definition order
+	pub(crate) order: Vec<u16>,
+	/// type aliases by name, holding the resolved type
+	#[doc(hidden)]
+	pub aliases: HashMap<String, TypeId>,
+	/// qualified paths of the nested modules, in declaration order
+	#[doc(hidden)]
+	pub modules: Vec<String>,
+	pub(crate) exports: Exports,
+	/// ids of the imported files
+	pub(crate) imports: Vec<u64>,
+	/// declared type of the root value
+	#[doc(hidden)]
+	pub root: Option<TypeId>,
 }
 
-#[derive(Debug)]
+/// declarations of other files made visible to importers through `export import`
+#[derive(Default, Debug)]
+pub(crate) struct Exports {
+	/// files re-exported without namespace
+	pub files: Vec<u64>,
+	/// files re-exported under a namespace
+	pub namespaces: HashMap<String, u64>,
+	/// selected items by exported name, holding their file id and qualified name
+	pub items: HashMap<String, (u64, String)>,
+}
+
+/// type of a field or a value.
+///
+/// builtin primitives are available as constants like [`TypeId::STR`], other types can be written as type expressions through [`DeclFileBuilder`](crate::DeclFileBuilder).
+#[derive(Debug, Clone)]
 pub struct TypeId {
 	pub ns: u64,
 	pub id: u16,
 	pub variant: u16,
 	pub item: Option<Box<TypeId>>,
+	pub args: Vec<TypeId>,
 	pub metadata: Option<Vec<(String, String)>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Field {
 	pub name: String,
 	pub tag: u32,
 	pub typeid: TypeId,
 	pub is_optional: bool,
+	/// value taken when the field is absent
+	pub default: Option<Value>,
+	pub annotations: Annotations,
 }
 #[derive(Default, Debug)]
 pub struct StructDef {
 	pub fields: Vec<Option<Field>>,
 	pub fields_by_name: HashMap<String, u32>,
 	pub required_fields: u32,
+	/// spread structs, their fields are merged into `fields`
+	pub bases: Vec<TypeId>,
+	pub reserved: Reserved,
+}
+
+/// tags and names that can not be used by fields or variants
+#[derive(Default, Debug, Clone)]
+pub struct Reserved {
+	/// inclusive ranges of tags
+	pub tags: Vec<(u32, u32)>,
+	pub names: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -44,6 +102,18 @@ pub struct EnumVariant {
 	pub name: String,
 	pub tag: u32,
 	pub def: Option<StructDef>,
+	pub annotations: Annotations,
+}
+
+/// informations attached to declared items, fields and variants
+#[derive(Default, Debug, Clone)]
+pub struct Annotations {
+	/// documentation comment, lines joined by "\n"
+	pub doc: Option<String>,
+	/// declared metadata, kept regardless of parse options
+	pub metadata: Option<Vec<(String, String)>>,
+	/// deprecation reason
+	pub deprecated: Option<String>,
 }
 
 #[derive(Debug)]
@@ -51,13 +121,25 @@ pub enum DeclItem {
 	Struct {
 		name: String,
 		typeid: u16,
+		params: Vec<String>,
 		def: StructDef,
+		annotations: Annotations,
 	},
 	Enum {
 		name: String,
 		typeid: u16,
+		params: Vec<String>,
 		variants: Vec<Option<EnumVariant>>,
 		variants_by_name: HashMap<String, u32>,
+		reserved: Reserved,
+		annotations: Annotations,
+	},
+	Flags {
+		name: String,
+		typeid: u16,
+		flags: Vec<Option<String>>,
+		flags_by_name: HashMap<String, u32>,
+		annotations: Annotations,
 	},
 }
 
@@ -68,6 +150,8 @@ pub type LoadResult<'a> = Result<&'a DeclFile, ImportError>;
 /// decleration providers are used by functions that need access to decleration files.
 ///
 /// decleration providers can provide decleration files from any source, guaranteed to be valid and the same for every access.
+///
+/// the providers of this crate are `Send` and `Sync`, a provider can be shared between threads through an `Arc`, loading and decoding in parallel.
 pub trait DeclProvider {
 	/// get a decleration file by its id.
 	///
@@ -78,21 +162,51 @@ pub trait DeclProvider {
 	///   
 	/// this method return `None` on fail, when the requested decleration file can not be found or it cant be parsed.
 	fn load<'a>(&'a self, name: &str) -> Result<&'a DeclFile, ImportError>;
+
+	/// get a decleration file by its name, loading its imports through another provider.
+	///
+	/// used by providers that delegate to others, like [`ChainProvider`](crate::ChainProvider), defaults to `load`.
+	fn load_with<'a>(
+		&'a self, name: &str, imports: &dyn DeclProvider,
+	) -> Result<&'a DeclFile, ImportError> {
+		let _ = imports;
+		self.load(name)
+	}
+
+	/// get a decleration file by its id, returns `None` if not provided by this provider.
+	///
+	/// used by providers that delegate to others, like [`ChainProvider`](crate::ChainProvider), defaults to `None`.
+	fn find<'a>(&'a self, id: u64) -> Option<&'a DeclFile> {
+		let _ = id;
+		None
+	}
+
+	/// the decleration files provided so far, the loaded ones for providers loading lazily.
+	///
+	/// used to find types by their fingerprint, like in [`decode`](crate::decode), defaults to none.
+	fn files(&self) -> Vec<&DeclFile> {
+		Vec::new()
+	}
 }
 
 impl DeclFile {
 	pub(crate) fn new(name: String) -> Self {
-		static mut DECLARE_ID_COUNTER: u64 = 0;
-		let id = unsafe {
-			DECLARE_ID_COUNTER += 1;
-			DECLARE_ID_COUNTER
-		};
-		DeclFile { name, id, items: HashMap::new(), items_by_name: HashMap::new() }
+		// ids are only required to be unique, files can be created from any thread
+		static DECLARE_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+		let id = DECLARE_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+		let (items, items_by_name, aliases) = (HashMap::new(), HashMap::new(), HashMap::new());
+		let (order, modules, exports) = (Vec::new(), Vec::new(), Exports::default());
+		let imports = Vec::new();
+		let root = None;
+		DeclFile { name, id, items, items_by_name, order, aliases, modules, exports, imports, root }
 	}
 
 	pub(crate) fn add_item(&mut self, item: DeclItem) {
-		self.items_by_name.insert(item.name().to_string(), item.typeid());
-		self.items.insert(item.typeid(), item);
+		let typeid = item.typeid();
+		self.items_by_name.insert(item.name().to_string(), typeid);
+		if self.items.insert(typeid, item).is_none() {
+			self.order.push(typeid);
+		}
 	}
 
 	#[doc(hidden)]
@@ -104,6 +218,25 @@ impl DeclFile {
 	pub fn get_by_id(&self, id: u16) -> Option<&DeclItem> {
 		self.items.get(&id)
 	}
+
+	pub(crate) fn add_alias(&mut self, name: String, typeid: TypeId) {
+		self.aliases.insert(name, typeid);
+	}
+	#[doc(hidden)]
+	pub fn get_alias(&self, name: &str) -> Option<&TypeId> {
+		self.aliases.get(name)
+	}
+	/// check if the name is used by an item or an alias
+	pub(crate) fn has_name(&self, name: &str) -> bool {
+		self.items_by_name.contains_key(name) || self.aliases.contains_key(name)
+	}
+	pub(crate) fn add_module(&mut self, path: String) {
+		self.modules.push(path);
+	}
+	/// check if a module of the qualified path exists
+	pub(crate) fn has_module(&self, path: &str) -> bool {
+		self.modules.iter().any(|m| m == path)
+	}
 }
 
 impl PartialEq<DeclFile> for DeclFile {
@@ -117,17 +250,63 @@ impl DeclItem {
 		match self {
 			Self::Struct { name, .. } => name,
 			Self::Enum { name, .. } => name,
+			Self::Flags { name, .. } => name,
 		}
 	}
 	pub fn typeid(&self) -> u16 {
 		match self {
 			Self::Struct { typeid, .. } => *typeid,
 			Self::Enum { typeid, .. } => *typeid,
+			Self::Flags { typeid, .. } => *typeid,
+		}
+	}
+	/// names of the generic parameters
+	pub fn params(&self) -> &[String] {
+		match self {
+			Self::Struct { params, .. } => params,
+			Self::Enum { params, .. } => params,
+			Self::Flags { .. } => &[],
+		}
+	}
+	/// documentation, metadata and deprecation of the item
+	pub fn annotations(&self) -> &Annotations {
+		match self {
+			Self::Struct { annotations, .. } => annotations,
+			Self::Enum { annotations, .. } => annotations,
+			Self::Flags { annotations, .. } => annotations,
+		}
+	}
+	/// whether the item was declared inline in a type, like `arr<struct { .. }>`
+	pub(crate) fn is_anonymous(&self) -> bool {
+		let name = self.name().rsplit_once('.').map_or(self.name(), |(_, name)| name);
+		name.starts_with("anonymous_struct_") || name.starts_with("anonymous_enum_")
+	}
+	pub(crate) fn set_annotations(&mut self, value: Annotations) {
+		match self {
+			Self::Struct { annotations, .. } => *annotations = value,
+			Self::Enum { annotations, .. } => *annotations = value,
+			Self::Flags { annotations, .. } => *annotations = value,
+		}
+	}
+	/// reserved tags and names of struct fields or enum variants
+	pub fn reserved(&self) -> Option<&Reserved> {
+		match self {
+			Self::Struct { def, .. } => Some(&def.reserved),
+			Self::Enum { reserved, .. } => Some(reserved),
+			Self::Flags { .. } => None,
 		}
 	}
+	/// deterministic hash of the binary layout of the item, covering tags, types and optionality.
+	///
+	/// it is independent of names and files, items of the same fingerprint encode compatible data.
+	pub fn fingerprint(&self, provider: &dyn DeclProvider) -> u64 {
+		fingerprint::fingerprint(self, provider)
+	}
 
-	pub fn new_enum(name: String, typeid: u16) -> Self {
-		Self::Enum { name, typeid, variants: vec![], variants_by_name: HashMap::new() }
+	pub fn new_enum(name: String, typeid: u16, params: Vec<String>) -> Self {
+		let (variants, variants_by_name) = (vec![], HashMap::new());
+		let (reserved, annotations) = (Reserved::default(), Annotations::default());
+		Self::Enum { name, typeid, params, variants, variants_by_name, reserved, annotations }
 	}
 
 	pub fn add_variant(&mut self, variant: EnumVariant) -> Result<(), ()> {
@@ -153,13 +332,53 @@ impl DeclItem {
 			_ => None,
 		}
 	}
+
+	pub fn new_flags(name: String, typeid: u16) -> Self {
+		let (flags, flags_by_name) = (vec![], HashMap::new());
+		Self::Flags { name, typeid, flags, flags_by_name, annotations: Annotations::default() }
+	}
+
+	pub fn add_flag(&mut self, name: String, bit: u32) -> Result<(), ()> {
+		match self {
+			Self::Flags { flags, flags_by_name, .. } => {
+				flags_by_name.insert(name.to_string(), bit);
+				add_item(flags, bit as usize, name)
+			}
+			_ => Err(()),
+		}
+	}
+	pub fn get_flag_by_name(&self, name: &str) -> Option<u32> {
+		match self {
+			Self::Flags { flags_by_name, .. } => flags_by_name.get(name).copied(),
+			_ => None,
+		}
+	}
+	pub fn get_flag_by_id(&self, bit: u32) -> Option<&str> {
+		match self {
+			Self::Flags { flags, .. } => flags.get(bit as usize).and_then(|v| v.as_deref()),
+			_ => None,
+		}
+	}
+	/// mask of all declared flags
+	pub fn flags_mask(&self) -> u64 {
+		match self {
+			Self::Flags { flags, .. } => flags
+				.iter()
+				.enumerate()
+				.filter(|(_, f)| f.is_some())
+				.fold(0, |m, (i, _)| m | 1 << i),
+			_ => 0,
+		}
+	}
 }
 
 impl StructDef {
 	pub fn add_field(&mut self, field: Field) -> Result<(), ()> {
-		self.required_fields += !field.is_optional as u32;
-		self.fields_by_name.insert(field.name.to_string(), field.tag);
-		add_item(&mut self.fields, field.tag as usize, field)
+		let (name, tag, is_required) = (field.name.to_string(), field.tag, field.is_required());
+		add_item(&mut self.fields, tag as usize, field)?;
+		self.required_fields += is_required as u32;
+		self.fields_by_name.insert(name, tag);
+		Ok(())
 	}
 	pub fn get_field_by_name(&self, name: &str) -> Option<&Field> {
 		let id = self.fields_by_name.get(name);
@@ -172,22 +391,91 @@ impl StructDef {
 
 impl Field {
 	pub fn new(name: String, tag: u32, typeid: TypeId, is_optional: bool) -> Self {
-		Self { name, tag, typeid, is_optional }
+		let annotations = Annotations::default();
+		Self { name, tag, typeid, is_optional, default: None, annotations }
+	}
+	/// whether the field must be present, fields with default value can be absent
+	pub fn is_required(&self) -> bool {
+		!self.is_optional && self.default.is_none()
+	}
+}
+
+impl Annotations {
+	pub fn is_empty(&self) -> bool {
+		self.doc.is_none() && self.metadata.is_none() && self.deprecated.is_none()
+	}
+}
+
+impl Reserved {
+	pub fn has_tag(&self, tag: u32) -> bool {
+		self.tags.iter().any(|(start, end)| (*start..=*end).contains(&tag))
+	}
+	pub fn has_name(&self, name: &str) -> bool {
+		self.names.iter().any(|n| n == name)
 	}
 }
 
 impl TypeId {
 	pub fn new(ns: u64, id: u16, metadata: Option<Vec<(String, String)>>) -> Self {
-		Self { ns, id, variant: 0, item: None, metadata }
+		Self { ns, id, variant: 0, item: None, args: Vec::new(), metadata }
 	}
 	pub fn with_variant(
 		ns: u64, id: u16, variant: u16, sub_type: Option<TypeId>,
 		metadata: Option<Vec<(String, String)>>,
 	) -> Self {
-		Self { ns, id, variant, item: sub_type.map(|t| Box::new(t)), metadata }
+		Self { ns, id, variant, item: sub_type.map(|t| Box::new(t)), args: Vec::new(), metadata }
+	}
+	pub fn tuple(items: Vec<TypeId>, metadata: Option<Vec<(String, String)>>) -> Self {
+		Self { ns: 0, id: TUPLE_TYPEID, variant: 0, item: None, args: items, metadata }
+	}
+	/// placeholder of the generic parameter at `index` of the containing item
+	pub fn param(index: u16, metadata: Option<Vec<(String, String)>>) -> Self {
+		Self { ns: 0, id: PARAM_TYPEID, variant: index, item: None, args: Vec::new(), metadata }
 	}
 
-	pub const ANY: Self = Self { ns: 0, id: 1, variant: 0, item: None, metadata: None };
+	pub const ANY: Self =
+		Self { ns: 0, id: 1, variant: 0, item: None, args: Vec::new(), metadata: None };
+	pub const BOOL: Self = Self::builtin(BOOL_TYPEID);
+	pub const U8: Self = Self::builtin(U8_TYPEID);
+	pub const U16: Self = Self::builtin(U16_TYPEID);
+	pub const U32: Self = Self::builtin(U32_TYPEID);
+	pub const U64: Self = Self::builtin(U64_TYPEID);
+	pub const I8: Self = Self::builtin(I8_TYPEID);
+	pub const I16: Self = Self::builtin(I16_TYPEID);
+	pub const I32: Self = Self::builtin(I32_TYPEID);
+	pub const I64: Self = Self::builtin(I64_TYPEID);
+	pub const F32: Self = Self::builtin(F32_TYPEID);
+	pub const F64: Self = Self::builtin(F64_TYPEID);
+	pub const DEC: Self = Self::builtin(DEC_TYPEID);
+	pub const VUINT: Self = Self::builtin(VUINT_TYPEID);
+	pub const VINT: Self = Self::builtin(VINT_TYPEID);
+	pub const BUINT: Self = Self::builtin(BUINT_TYPEID);
+	pub const BINT: Self = Self::builtin(BINT_TYPEID);
+	pub const STR: Self = Self::builtin(STR_TYPEID);
+	pub const INST: Self = Self::builtin(INST_TYPEID);
+	pub const INSTN: Self = Self::builtin(INSTN_TYPEID);
+	pub const DUR: Self = Self::builtin(DUR_TYPEID);
+	pub const UUID: Self = Self::builtin(UUID_TYPEID);
+	pub const INSTZ: Self = Self::builtin(INSTZ_TYPEID);
+	pub const DATE: Self = Self::builtin(DATE_TYPEID);
+	pub const TIME: Self = Self::builtin(TIME_TYPEID);
+
+	const fn builtin(id: u16) -> Self {
+		Self { ns: 0, id, variant: 0, item: None, args: Vec::new(), metadata: None }
+	}
+
+	/// copy of the type, with the given metadata added over its own
+	pub fn with_metadata(&self, metadata: Option<Vec<(String, String)>>) -> Self {
+		let mut typeid = self.clone();
+		for (name, value) in metadata.into_iter().flatten() {
+			let own = typeid.metadata.get_or_insert_with(Vec::new);
+			match own.iter_mut().find(|(own_name, _)| *own_name == name) {
+				Some(entry) => entry.1 = value,
+				None => own.push((name, value)),
+			}
+		}
+		typeid
+	}
 
 	pub fn is_any(&self) -> bool {
 		self.ns == 0 && self.id == 1
@@ -195,6 +483,36 @@ impl TypeId {
 	pub fn is_builtin(&self) -> bool {
 		self.ns == 0
 	}
+	pub fn is_param(&self) -> bool {
+		self.ns == 0 && self.id == PARAM_TYPEID
+	}
+	pub(crate) fn has_params(&self) -> bool {
+		self.is_param()
+			|| self.item.as_ref().is_some_and(|item| item.has_params())
+			|| self.args.iter().any(|arg| arg.has_params())
+	}
+
+	/// replace generic parameters placeholders with the given arguments
+	pub fn substitute(&self, args: &[TypeId]) -> Cow<'_, TypeId> {
+		if args.is_empty() || !self.has_params() {
+			return Cow::Borrowed(self);
+		}
+		if self.is_param() {
+			let mut arg = args[self.variant as usize].clone();
+			// metadata written on the parameter are kept
+			if arg.metadata.is_none() {
+				arg.metadata = self.metadata.clone();
+			}
+			return Cow::Owned(arg);
+		}
+
+		let mut typeid = self.clone();
+		if let Some(item) = &self.item {
+			typeid.item = Some(Box::new(item.substitute(args).into_owned()));
+		}
+		typeid.args = self.args.iter().map(|arg| arg.substitute(args).into_owned()).collect();
+		Cow::Owned(typeid)
+	}
 
 	pub fn name(&self, provider: &dyn DeclProvider) -> String {
 		if self.ns == 0 {
@@ -202,6 +520,20 @@ impl TypeId {
 			if self.id == 0x22 {
 				return format!("arr<{}>", self.item.as_ref().unwrap().name(provider));
 			}
+			// set
+			if self.id == SET_TYPEID {
+				return format!("set<{}>", self.item.as_ref().unwrap().name(provider));
+			}
+			// fixed length arr
+			if self.id == FIXED_ARR_TYPEID {
+				let item = self.item.as_ref().unwrap().name(provider);
+				return format!("arr<{item}, {}>", self.variant);
+			}
+			// tuple
+			if self.id == TUPLE_TYPEID {
+				let items: Vec<_> = self.args.iter().map(|item| item.name(provider)).collect();
+				return format!("({})", items.join(", "));
+			}
 			// map
 			if self.id == 0x23 {
 				return format!(
@@ -210,13 +542,23 @@ impl TypeId {
 					self.item.as_ref().unwrap().name(provider)
 				);
 			}
+			// generic parameter
+			if self.id == PARAM_TYPEID {
+				return format!("${}", self.variant);
+			}
 			// other builtin
 			BUILT_INS_NAMES[&self.id].to_string()
 		// user defined
 		} else {
 			let file = provider.get(self.ns);
+			let name = format!("`{}`.{}", file.name, file.get_by_id(self.id).unwrap().name());
 
-			format!("`{}`.{}", file.name, file.get_by_id(self.id).unwrap().name())
+			// generic arguments
+			if self.args.is_empty() {
+				return name;
+			}
+			let args: Vec<_> = self.args.iter().map(|arg| arg.name(provider)).collect();
+			format!("{name}<{}>", args.join(", "))
 		}
 	}
 }
@@ -230,6 +572,7 @@ impl PartialEq for TypeId {
 			&& self.id == other.id
 			&& self.variant == other.variant
 			&& self.item == other.item
+			&& self.args == other.args
 	}
 }
 
@@ -306,6 +649,9 @@ impl DeclProvider for FixedSetProviderRef<'_> {
 	fn load<'a>(&'a self, name: &str) -> Result<&'a DeclFile, ImportError> {
 		self.files_by_name.get(name).map(|f| *f).ok_or(ImportError::NotFound)
 	}
+	fn files(&self) -> Vec<&DeclFile> {
+		self.files.values().copied().collect()
+	}
 }
 
 /// decleration provider with fixed set of decleration files.
@@ -347,4 +693,7 @@ impl DeclProvider for FixedSetProvider {
 		let file = self.files_by_name.get(name).map(|ind| self.files.get(ind).unwrap());
 		file.ok_or(ImportError::NotFound)
 	}
+	fn files(&self) -> Vec<&DeclFile> {
+		self.files.values().collect()
+	}
 }
@@ -19,7 +19,11 @@ pub use nb::*;
 #[doc(hidden)]
 pub use rich::*;
 
-use crate::{DeclProvider, Value, encoding::item::decode_item};
+use crate::{
+	DeclFile, DeclProvider, Value,
+	declaration::DeclItem,
+	encoding::item::{decode_item, encode_item},
+};
 
 /// encode a given [`Value`] into its binary representation.
 ///
@@ -31,17 +35,43 @@ pub fn encode(value: &Value) -> Vec<u8> {
 	data
 }
 
+/// encode a given [`Value`] as the root type declared in a declaration file.
+///
+/// this function insert a header of the `decl_path` of the file declaring the type and its typeid, so the data is decoded against it.
+///
+/// if `fingerprint` is set, the header holds the [fingerprint](crate::internal::DeclItem::fingerprint) of the type, so decoders can check compatibility by it.
+///
+/// it returns `None` if the file declares no root type, or if the value does not match it.
+pub fn encode_root(
+	value: &Value, file: &DeclFile, provider: &dyn DeclProvider, fingerprint: bool,
+) -> Option<Vec<u8>> {
+	let rootid = file.root.as_ref()?;
+	let decl_file = if rootid.ns == file.id { file } else { provider.get(rootid.ns) };
+	let item = decl_file.get_by_id(rootid.id)?;
+
+	let mut data = Vec::new();
+	let fingerprint = fingerprint.then(|| item.fingerprint(provider));
+	encode_header(&mut data, &decl_file.name, fingerprint);
+	encode_vuint(&mut data, rootid.id as u64);
+	encode_item(&mut data, value, item, &[], provider)?;
+	Some(data)
+}
+
 /// decode a given binary data into a [`Value`].
 ///
 /// the data must start with a header specifing the path to the declaration file and the root value typeid.
 ///
 /// if the `decl_path` field is an empty string, the type is `any` implicitly.
 ///
+/// if the header holds a fingerprint, the root type must have the same fingerprint,
+/// when the type at `decl_path` does not match it, the type is looked up by the fingerprint in the
+/// [files](DeclProvider::files) of the provider, so data outlives moving or renaming its type.
+///
 /// it returns `None` if the data is invalid, or if there is unused space at the end of the input.
 pub fn decode(data: &[u8], provider: &dyn DeclProvider) -> Option<Value> {
 	let mut ind = 0;
 
-	let decl_path = decode_str(data, &mut ind)?;
+	let (decl_path, fingerprint) = decode_header(data, &mut ind)?;
 
 	// implicit any type if not decleration file specified
 	let value = if decl_path.is_empty() {
@@ -50,8 +80,14 @@ pub fn decode(data: &[u8], provider: &dyn DeclProvider) -> Option<Value> {
 	// else explicit type is required
 	} else {
 		let rootid = decode_vuint(data, &mut ind)? as u16;
-		let item = provider.load(&decl_path).ok()?.get_by_id(rootid)?;
-		decode_item(data, &mut ind, item, provider)?
+		let item = provider.load(&decl_path).ok().and_then(|file| file.get_by_id(rootid));
+		let item = match fingerprint {
+			None => item?,
+			Some(fingerprint) => item
+				.filter(|item| item.fingerprint(provider) == fingerprint)
+				.or_else(|| find_by_fingerprint(fingerprint, provider))?,
+		};
+		decode_item(data, &mut ind, item, &[], provider)?
 	};
 
 	// ensure all data is decoded
@@ -61,6 +97,12 @@ pub fn decode(data: &[u8], provider: &dyn DeclProvider) -> Option<Value> {
 	Some(value)
 }
 
+/// find a non generic item of the given fingerprint in the files of the provider
+fn find_by_fingerprint(fingerprint: u64, provider: &dyn DeclProvider) -> Option<&DeclItem> {
+	let mut items = provider.files().into_iter().flat_map(|file| file.items.values());
+	items.find(|item| item.params().is_empty() && item.fingerprint(provider) == fingerprint)
+}
+
 /// trait for types that can be serialized and deserialized.
 ///
 /// this trait is automatically implemented for every generated type in generated serialization code.
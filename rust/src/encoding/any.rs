@@ -4,25 +4,31 @@ use crate::{
 	Key, Value,
 	builtins::*,
 	encoding::{
-		decode_arr, decode_bool, decode_dur, decode_f32, decode_f64, decode_i8, decode_i16,
-		decode_i32, decode_i64, decode_inst, decode_instN, decode_map, decode_str, decode_u8,
-		decode_u8_arr, decode_u16, decode_u32, decode_u64, decode_uuid, decode_vint, decode_vuint,
-		encode_arr, encode_bool, encode_dur, encode_f64, encode_instN, encode_map, encode_str,
-		encode_u8_arr, encode_uuid, encode_vint, encode_vuint,
+		decode_arr, decode_bool, decode_date, decode_dec, decode_dur, decode_f32, decode_f64,
+		decode_fixed_arr, decode_i8, decode_i16, decode_i32, decode_i64, decode_inst, decode_instN,
+		decode_instZ, decode_map, decode_str, decode_time, decode_u8, decode_u8_arr, decode_u16,
+		decode_u32, decode_u64, decode_uuid, decode_vint, decode_vuint, encode_arr, encode_bool,
+		encode_date, encode_dec, encode_dur, encode_f64, encode_instN, encode_instZ, encode_map,
+		encode_str, encode_time, encode_u8_arr, encode_uuid, encode_vint, encode_vuint, is_unique,
 	},
 };
 
 macro_rules! encode_typeid_commons {
 	($enum:ident, $value:ident, $data:ident) => {
 		match $value {
-			$enum::Bool(_) => $data.push(BOOL_TYPEID),
-			$enum::Uint(_) => $data.push(VUINT_TYPEID),
-			$enum::Int(_) => $data.push(VINT_TYPEID),
-			$enum::BigInt(_) => $data.push(BINT_TYPEID),
-			$enum::Str(_) => $data.push(STR_TYPEID),
-			$enum::Inst(_) => $data.push(INSTN_TYPEID),
-			$enum::Dur(_) => $data.push(DUR_TYPEID),
-			$enum::UUID(_) => $data.push(UUID_TYPEID),
+			$enum::Bool(_) => $data.push(BOOL_TYPEID as u8),
+			$enum::Uint(_) => $data.push(VUINT_TYPEID as u8),
+			$enum::Int(_) => $data.push(VINT_TYPEID as u8),
+			$enum::BigInt(_) => $data.push(BINT_TYPEID as u8),
+			$enum::Str(_) => $data.push(STR_TYPEID as u8),
+			$enum::Inst(_) => $data.push(INSTN_TYPEID as u8),
+			$enum::InstZ(_) => $data.push(INSTZ_TYPEID as u8),
+			$enum::Date(_) => $data.push(DATE_TYPEID as u8),
+			$enum::Time(_) => $data.push(TIME_TYPEID as u8),
+			$enum::Dur(_) => $data.push(DUR_TYPEID as u8),
+			$enum::UUID(_) => $data.push(UUID_TYPEID as u8),
+			// keys have no other variant
+			#[allow(unreachable_patterns)]
 			_ => (),
 		}
 	};
@@ -36,8 +42,12 @@ macro_rules! encode_value_commons {
 			$enum::BigInt(nb) => encode_u8_arr($data, nb),
 			$enum::Str(str) => encode_str($data, str),
 			$enum::Inst(inst) => encode_instN($data, inst),
+			$enum::InstZ(inst) => encode_instZ($data, inst),
+			$enum::Date(date) => encode_date($data, date),
+			$enum::Time(time) => encode_time($data, time),
 			$enum::Dur(dur) => encode_dur($data, dur),
 			$enum::UUID(uuid) => encode_uuid($data, uuid),
+			#[allow(unreachable_patterns)]
 			_ => (),
 		}
 	};
@@ -45,15 +55,19 @@ macro_rules! encode_value_commons {
 
 fn enocde_value_typeid(data: &mut Vec<u8>, value: &Value) {
 	encode_typeid_commons!(Value, value, data);
-	if value.is_float() {
-		data.push(F64_TYPEID);
+	match value {
+		Value::Float(_) => data.push(F64_TYPEID as u8),
+		Value::Decimal(_) => data.push(DEC_TYPEID as u8),
+		_ => (),
 	}
 }
 // encode values of typed containers
 fn encode_value(data: &mut Vec<u8>, value: &Value) {
 	encode_value_commons!(Value, value, data);
-	if let Value::Float(nb) = value {
-		encode_f64(data, *nb);
+	match value {
+		Value::Float(nb) => encode_f64(data, *nb),
+		Value::Decimal(dec) => encode_dec(data, dec),
+		_ => (),
 	}
 }
 pub fn encode_any_key(data: &mut Vec<u8>, value: &Key) {
@@ -66,11 +80,15 @@ pub fn encode_any(data: &mut Vec<u8>, value: &Value) {
 
 	match value {
 		Value::Float(nb) => {
-			data.push(F64_TYPEID);
+			data.push(F64_TYPEID as u8);
 			encode_f64(data, *nb);
 		}
+		Value::Decimal(dec) => {
+			data.push(DEC_TYPEID as u8);
+			encode_dec(data, dec);
+		}
 		Value::Arr(arr) => {
-			data.push(ARR_TYPEID);
+			data.push(ARR_TYPEID as u8);
 			// all elements of the same type (except arrays and maps)
 			if let Some(first) = arr.first()
 				&& arr.iter().all(|v| discriminant(v) == discriminant(first))
@@ -80,13 +98,13 @@ pub fn encode_any(data: &mut Vec<u8>, value: &Value) {
 				encode_arr(data, arr, false, encode_value);
 			} else {
 				// it is arr<any>
-				data.push(ANY_TYPEID);
+				data.push(ANY_TYPEID as u8);
 				encode_arr(data, arr, false, encode_any);
 			}
 		}
 		Value::Map(map) => {
 			// typeid
-			data.push(MAP_TYPEID);
+			data.push(MAP_TYPEID as u8);
 			// are keys of the same type
 			let key_encoder: fn(&mut Vec<u8>, &Key) = if let Some(first) = map.keys().next()
 				&& map.keys().all(|key| discriminant(key) == discriminant(first))
@@ -95,7 +113,7 @@ pub fn encode_any(data: &mut Vec<u8>, value: &Value) {
 				|data, key| encode_value_commons!(Key, key, data)
 			} else {
 				// else keys are of type any
-				data.push(ANY_TYPEID);
+				data.push(ANY_TYPEID as u8);
 				encode_any_key
 			};
 			// is values of the same type (except arrays and maps)
@@ -107,7 +125,7 @@ pub fn encode_any(data: &mut Vec<u8>, value: &Value) {
 				encode_value
 			} else {
 				// else values are of type any
-				data.push(ANY_TYPEID);
+				data.push(ANY_TYPEID as u8);
 				encode_any
 			};
 			encode_map(data, map, false, key_encoder, value_encoder);
@@ -141,39 +159,67 @@ macro_rules! decode_value_commons {
 			UUID_TYPEID => Some($enum::UUID(decode_uuid($data, $ind)?)),
 			INST_TYPEID => Some($enum::Inst(decode_inst($data, $ind)?)),
 			INSTN_TYPEID => Some($enum::Inst(decode_instN($data, $ind)?)),
+			INSTZ_TYPEID => Some($enum::InstZ(decode_instZ($data, $ind)?)),
+			DATE_TYPEID => Some($enum::Date(decode_date($data, $ind)?)),
+			TIME_TYPEID => Some($enum::Time(decode_time($data, $ind)?)),
 			DUR_TYPEID => Some($enum::Dur(decode_dur($data, $ind)?)),
 			_ => None,
 		}
 	};
 }
 pub fn decode_any(data: &[u8], ind: &mut usize) -> Option<Value> {
-	let typeid = *data.get(*ind)?;
+	let typeid = *data.get(*ind)? as u16;
 	*ind += 1;
 	decode_value(data, ind, typeid)
 }
 pub fn decode_any_key(data: &[u8], ind: &mut usize) -> Option<Key> {
-	let typeid = *data.get(*ind)?;
+	let typeid = *data.get(*ind)? as u16;
 	*ind += 1;
 	decode_value_commons!(Key, typeid, data, ind, decode_any_key)
 }
-pub fn decode_key(data: &[u8], ind: &mut usize, id: u8) -> Option<Key> {
+pub fn decode_key(data: &[u8], ind: &mut usize, id: u16) -> Option<Key> {
 	decode_value_commons!(Key, id, data, ind, decode_any_key)
 }
-pub fn decode_value(data: &[u8], ind: &mut usize, id: u8) -> Option<Value> {
+pub fn decode_value(data: &[u8], ind: &mut usize, id: u16) -> Option<Value> {
 	match id {
 		F32_TYPEID => Some(Value::Float(decode_f32(data, ind)? as f64)),
 		F64_TYPEID => Some(Value::Float(decode_f64(data, ind)?)),
+		DEC_TYPEID => Some(Value::Decimal(decode_dec(data, ind)?)),
 
 		ARR_TYPEID => {
-			let itemid = *data.get(*ind)?;
+			let itemid = *data.get(*ind)? as u16;
 			*ind += 1;
 			Some(Value::Arr(decode_arr(data, ind, false, |data, ind| {
 				decode_value(data, ind, itemid)
 			})?))
 		}
+		SET_TYPEID => {
+			let itemid = *data.get(*ind)? as u16;
+			*ind += 1;
+			let items = decode_arr(data, ind, false, |data, ind| decode_key(data, ind, itemid))?;
+			if !is_unique(&items) {
+				return None;
+			}
+			Some(Value::Arr(items.into_iter().map(Value::from).collect()))
+		}
+		FIXED_ARR_TYPEID => {
+			let itemid = *data.get(*ind)? as u16;
+			*ind += 1;
+			let len = decode_vuint(data, ind)? as usize;
+			Some(Value::Arr(decode_fixed_arr(data, ind, len, |data, ind| {
+				decode_value(data, ind, itemid)
+			})?))
+		}
+		TUPLE_TYPEID => {
+			let len = decode_vuint(data, ind)? as usize;
+			let itemids = data.get(*ind..*ind + len)?.to_vec();
+			*ind += len;
+			let items = itemids.into_iter().map(|itemid| decode_value(data, ind, itemid as u16));
+			Some(Value::Arr(items.collect::<Option<_>>()?))
+		}
 		MAP_TYPEID => {
-			let keyid = *data.get(*ind)?;
-			let valueid = *data.get(*ind + 1)?;
+			let keyid = *data.get(*ind)? as u16;
+			let valueid = *data.get(*ind + 1)? as u16;
 			*ind += 2;
 			Some(Value::Map(Box::new(decode_map(
 				data,
@@ -1,6 +1,9 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+};
 
-use crate::encoding::nb::{decode_vuint, encode_vuint};
+use crate::encoding::nb::{decode_u64, decode_vuint, encode_u64, encode_vuint};
 
 // in_field = true, omit length field
 #[inline]
@@ -36,6 +39,32 @@ pub fn decode_str(data: &[u8], ind: &mut usize) -> Option<String> {
 	Some(String::from_utf8(decode_u8_arr(data, ind)?).ok()?)
 }
 
+/// byte following an empty `decl_path`, marking a header with a fingerprint
+pub const FINGERPRINT_MAGIC: u8 = 0xff;
+/// version of the header with a fingerprint
+pub const HEADER_VERSION: u8 = 1;
+
+/// encode the header up to the typeid, with the fingerprint of the root item if given
+pub fn encode_header(data: &mut Vec<u8>, decl_path: &str, fingerprint: Option<u64>) {
+	if let Some(fingerprint) = fingerprint {
+		data.extend_from_slice(&[0, FINGERPRINT_MAGIC, HEADER_VERSION]);
+		encode_u64(data, fingerprint);
+	}
+	encode_str(data, decl_path);
+}
+/// decode the header up to the typeid, returns the `decl_path` and the fingerprint if present
+pub fn decode_header(data: &[u8], ind: &mut usize) -> Option<(String, Option<u64>)> {
+	let mut fingerprint = None;
+	if data.get(*ind..*ind + 2)? == [0, FINGERPRINT_MAGIC] {
+		if *data.get(*ind + 2)? != HEADER_VERSION {
+			return None;
+		}
+		*ind += 3;
+		fingerprint = Some(decode_u64(data, ind)?);
+	}
+	Some((decode_str(data, ind)?, fingerprint))
+}
+
 #[inline]
 pub fn encode_arr<T>(
 	data: &mut Vec<u8>, value: &[T], in_field: bool, item_fn: impl Fn(&mut Vec<u8>, &T) -> (),
@@ -68,6 +97,66 @@ pub fn decode_arr<T>(
 	}
 }
 
+// sets are encoded like arrays, but decoding fail on duplicated items
+#[inline]
+pub fn encode_set<T>(
+	data: &mut Vec<u8>, value: &HashSet<T>, in_field: bool,
+	item_fn: impl Fn(&mut Vec<u8>, &T) -> (),
+) {
+	if !in_field {
+		encode_vuint(data, value.len() as u64)
+	}
+	for v in value {
+		item_fn(data, v);
+	}
+}
+#[inline]
+pub fn decode_set<T: Eq + Hash>(
+	data: &[u8], ind: &mut usize, in_field: bool, item_fn: impl Fn(&[u8], &mut usize) -> Option<T>,
+) -> Option<HashSet<T>> {
+	let items = decode_arr(data, ind, in_field, item_fn)?;
+	let len = items.len();
+	let set = HashSet::from_iter(items);
+	if set.len() != len { None } else { Some(set) }
+}
+#[inline]
+pub fn is_unique<T: Eq + Hash>(items: &[T]) -> bool {
+	let mut set = HashSet::with_capacity(items.len());
+	items.iter().all(|item| set.insert(item))
+}
+
+// apply an item encoder / decoder directly, used for tuple items
+#[inline]
+pub fn encode_with<T>(data: &mut Vec<u8>, value: &T, item_fn: impl Fn(&mut Vec<u8>, &T) -> ()) {
+	item_fn(data, value)
+}
+#[inline]
+pub fn decode_with<T>(
+	data: &[u8], ind: &mut usize, item_fn: impl Fn(&[u8], &mut usize) -> Option<T>,
+) -> Option<T> {
+	item_fn(data, ind)
+}
+
+// fixed length arrays and tuples have no length prefix
+#[inline]
+pub fn encode_fixed_arr<T>(
+	data: &mut Vec<u8>, value: &[T], item_fn: impl Fn(&mut Vec<u8>, &T) -> (),
+) {
+	for v in value {
+		item_fn(data, v);
+	}
+}
+#[inline]
+pub fn decode_fixed_arr<T>(
+	data: &[u8], ind: &mut usize, len: usize, item_fn: impl Fn(&[u8], &mut usize) -> Option<T>,
+) -> Option<Vec<T>> {
+	let mut vec = Vec::with_capacity(len);
+	for _ in 0..len {
+		vec.push(item_fn(data, ind)?);
+	}
+	Some(vec)
+}
+
 #[inline]
 pub fn encode_map<K, V>(
 	data: &mut Vec<u8>, value: &HashMap<K, V>, in_field: bool,
@@ -1,20 +1,23 @@
 use std::collections::HashMap;
 
 use crate::{
-	builtins::{ARR_TYPEID, BINT_TYPEID, MAP_TYPEID, STR_TYPEID}, declaration::{resolve_typeid, DeclItem, StructDef, TypeId}, encoding::{decode_arr, decode_key, decode_map, decode_value, decode_vuint}, DeclProvider, Key, Value
+	DeclProvider, Key, Value,
+	builtins::*,
+	declaration::{DeclItem, StructDef, TypeId, resolve_typeid},
+	encoding::*,
 };
 
 pub fn decode_item(
-	data: &[u8], ind: &mut usize, item: &DeclItem, provider: &dyn DeclProvider,
+	data: &[u8], ind: &mut usize, item: &DeclItem, args: &[TypeId], provider: &dyn DeclProvider,
 ) -> Option<Value> {
 	match item {
-		DeclItem::Struct { def, .. } => decode_struct(data, ind, def, provider),
+		DeclItem::Struct { def, .. } => decode_struct(data, ind, def, args, provider),
 		DeclItem::Enum { variants, .. } => {
 			let variant = variants.get(decode_vuint(data, ind)? as usize)?.as_ref()?;
 
 			// case has fields
 			if let Some(def) = &variant.def {
-				let mut value = decode_struct(data, ind, def, provider)?;
+				let mut value = decode_struct(data, ind, def, args, provider)?;
 				value.as_map_mut()?.insert(Key::enum_variant_key().clone(), variant.name.clone().into());
 				return Some(value);
 			};
@@ -22,6 +25,18 @@ pub fn decode_item(
 			// case unit enum variant
 			Some(Value::UnitVar(variant.name.clone()))
 		}
+		DeclItem::Flags { .. } => {
+			let mask = decode_vuint(data, ind)?;
+
+			// undeclared bits are invalid
+			if mask & !item.flags_mask() != 0 {
+				return None;
+			}
+			let bits = (0..64).filter(|bit| mask & 1 << bit != 0);
+			let flags =
+				bits.map(|bit| Value::UnitVar(item.get_flag_by_id(bit).unwrap().to_string()));
+			Some(Value::Arr(flags.collect()))
+		}
 	}
 }
 
@@ -30,7 +45,7 @@ fn decode_field_value(
 ) -> Option<Value> {
 	// case user defined type
 	Some(if typeid.ns != 0 {
-		decode_item(data, ind, resolve_typeid(typeid, provider), provider)?
+		decode_item(data, ind, resolve_typeid(typeid, provider), &typeid.args, provider)?
 
 	// case array
 	} else if typeid.id == ARR_TYPEID as u16 {
@@ -40,9 +55,33 @@ fn decode_field_value(
 			decode_field_value(data, ind, itemid, false, provider)
 		})?)
 
+	// case set
+	} else if typeid.id == SET_TYPEID {
+		let itemid = typeid.item.as_ref()?.id;
+
+		let items = decode_arr(data, ind, in_field, |data, ind| decode_key(data, ind, itemid))?;
+		if !is_unique(&items) {
+			return None;
+		}
+		Value::Arr(items.into_iter().map(Value::from).collect())
+
+	// case fixed length array
+	} else if typeid.id == FIXED_ARR_TYPEID {
+		let itemid = typeid.item.as_ref()?.as_ref();
+
+		Value::Arr(decode_fixed_arr(data, ind, typeid.variant as usize, |data, ind| {
+			decode_field_value(data, ind, itemid, false, provider)
+		})?)
+
+	// case tuple
+	} else if typeid.id == TUPLE_TYPEID {
+		let items = typeid.args.iter();
+		let items = items.map(|itemid| decode_field_value(data, ind, itemid, false, provider));
+		Value::Arr(items.collect::<Option<_>>()?)
+
 	// case map
 	} else if typeid.id == MAP_TYPEID as u16 {
-		let keyid = typeid.variant as u8;
+		let keyid = typeid.variant;
 		let itemid = typeid.item.as_ref()?.as_ref();
 
 		Value::Map(Box::new(decode_map(
@@ -53,11 +92,11 @@ fn decode_field_value(
 
 	// case builtins
 	} else {
-		decode_value(data, ind, typeid.id as u8)?
+		decode_value(data, ind, typeid.id)?
 	})
 }
 pub fn decode_struct(
-	data: &[u8], ind: &mut usize, def: &StructDef, provider: &dyn DeclProvider,
+	data: &[u8], ind: &mut usize, def: &StructDef, args: &[TypeId], provider: &dyn DeclProvider,
 ) -> Option<Value> {
 	let mut map = HashMap::new();
 	let mut required = def.required_fields;
@@ -79,17 +118,20 @@ pub fn decode_struct(
 		if map.contains_key(&name) {
 			return None;
 		}
-		required -= if field.is_optional { 0 } else { 1 };
+		required -= field.is_required() as u32;
 
 		// skip len field for types that dont use it
 		#[rustfmt::skip] 
 		if header & 0b111 == 0b101 && (field.typeid.ns != 0
-			|| !matches!(field.typeid.id as u8,	MAP_TYPEID | ARR_TYPEID | STR_TYPEID | BINT_TYPEID)) 
+			|| !matches!(field.typeid.id,	MAP_TYPEID | ARR_TYPEID | SET_TYPEID | STR_TYPEID | BINT_TYPEID)) 
 		{
 			decode_vuint(data, ind)?;
 		};
 
-		let value = decode_field_value(data, ind, &field.typeid, true, provider)?;
+		// generic fields are always sized, holding a standalone value
+		let typeid = field.typeid.substitute(args);
+		let in_field = !field.typeid.is_param();
+		let value = decode_field_value(data, ind, &typeid, in_field, provider)?;
 		map.insert(name, value);
 	}
 
@@ -98,6 +140,13 @@ pub fn decode_struct(
 		return None;
 	}
 
+	// absent fields take their default value
+	for field in def.fields.iter().filter_map(|f| f.as_ref()) {
+		if let Some(default) = &field.default {
+			map.entry(Key::from(field.name.clone())).or_insert_with(|| default.clone());
+		}
+	}
+
 	Some(Value::Map(Box::new(map)))
 }
 pub fn skip_field(data: &[u8], ind: &mut usize, header: u64) -> Option<()> {
@@ -113,4 +162,208 @@ pub fn skip_field(data: &[u8], ind: &mut usize, header: u64) -> Option<()> {
 		_ => return None,
 	};
 	Some(())
-}
\ No newline at end of file
+}
+
+/// encode a value of a declared item, inverse of `decode_item`
+pub fn encode_item(
+	data: &mut Vec<u8>, value: &Value, item: &DeclItem, args: &[TypeId],
+	provider: &dyn DeclProvider,
+) -> Option<()> {
+	let value = value.inner();
+	match item {
+		DeclItem::Struct { def, .. } => encode_struct(data, value.as_map()?, def, args, provider),
+		DeclItem::Enum { .. } => {
+			let variant = item.get_variant_by_name(value.enum_variant()?)?;
+			encode_vuint(data, variant.tag as u64);
+
+			match (&variant.def, value) {
+				(Some(def), Value::Map(map)) => encode_struct(data, map, def, args, provider),
+				(None, Value::UnitVar(_)) => Some(()),
+				_ => None,
+			}
+		}
+		DeclItem::Flags { .. } => {
+			let mut mask = 0u64;
+			for flag in value.as_slice()? {
+				let Value::UnitVar(flag) = flag else { return None };
+				mask |= 1 << item.get_flag_by_name(flag)?;
+			}
+			encode_vuint(data, mask);
+			Some(())
+		}
+	}
+}
+
+pub fn encode_struct(
+	data: &mut Vec<u8>, map: &HashMap<Key, Value>, def: &StructDef, args: &[TypeId],
+	provider: &dyn DeclProvider,
+) -> Option<()> {
+	// undeclared fields are invalid
+	let is_declared = |key: &Key| {
+		key == Key::enum_variant_key()
+			|| key.as_str().is_some_and(|name| def.get_field_by_name(name).is_some())
+	};
+	if !map.keys().all(is_declared) {
+		return None;
+	}
+
+	let mut fields = Vec::new();
+	for field in def.fields.iter().filter_map(|f| f.as_ref()) {
+		match map.get(&Key::from(field.name.as_str())) {
+			Some(value) => fields.push((field, value)),
+			None if field.is_required() => return None,
+			None => (),
+		}
+	}
+
+	encode_vuint(data, fields.len() as u64);
+	for (field, value) in fields {
+		// generic fields are always sized, holding a standalone value
+		let typeid = field.typeid.substitute(args);
+		encode_field(data, field.tag, &typeid, field.typeid.is_param(), value, provider)?;
+	}
+	Some(())
+}
+
+fn encode_field(
+	data: &mut Vec<u8>, tag: u32, typeid: &TypeId, is_param: bool, value: &Value,
+	provider: &dyn DeclProvider,
+) -> Option<()> {
+	let header = (tag as u64) << 3;
+
+	// fixed size values, varints and values holding their length
+	let mlen = match typeid.id {
+		_ if typeid.ns != 0 || is_param => None,
+		BOOL_TYPEID | U8_TYPEID | I8_TYPEID => Some(0b000),
+		U16_TYPEID | I16_TYPEID => Some(0b001),
+		U32_TYPEID | I32_TYPEID | F32_TYPEID | DATE_TYPEID => Some(0b010),
+		U64_TYPEID | I64_TYPEID | F64_TYPEID | INST_TYPEID | DUR_TYPEID | TIME_TYPEID => {
+			Some(0b011)
+		}
+		VUINT_TYPEID | VINT_TYPEID => Some(0b100),
+		STR_TYPEID | BINT_TYPEID => Some(0b101),
+		_ => None,
+	};
+	if let Some(mlen) = mlen {
+		encode_vuint(data, header | mlen);
+		return encode_field_value(data, value, typeid, true, provider);
+	}
+
+	// else sized by the len field, reserve 2 bytes for it
+	encode_vuint(data, header | 0b101);
+	let size_ind = data.len();
+	data.extend_from_slice(&[0, 0]);
+
+	// arrays, sets and maps in fields omit their length, taking the len field
+	let in_field =
+		typeid.ns == 0 && !is_param && matches!(typeid.id, ARR_TYPEID | SET_TYPEID | MAP_TYPEID);
+	encode_field_value(data, value, typeid, in_field, provider)?;
+
+	encode_vuint_pre_aloc(data, (data.len() - size_ind - 2) as u64, size_ind, 2);
+	Some(())
+}
+
+fn encode_field_value(
+	data: &mut Vec<u8>, value: &Value, typeid: &TypeId, in_field: bool, provider: &dyn DeclProvider,
+) -> Option<()> {
+	let value = value.inner();
+
+	// case user defined type
+	if typeid.ns != 0 {
+		return encode_item(data, value, resolve_typeid(typeid, provider), &typeid.args, provider);
+	}
+
+	match typeid.id {
+		ARR_TYPEID | SET_TYPEID => {
+			let items = value.as_slice()?;
+			let itemid = typeid.item.as_ref()?.as_ref();
+
+			// set items must be unique keys
+			if typeid.id == SET_TYPEID {
+				let keys = items.iter().map(|item| Key::try_from(item.clone()).ok());
+				if !is_unique(&keys.collect::<Option<Vec<_>>>()?) {
+					return None;
+				}
+			}
+
+			if !in_field {
+				encode_vuint(data, items.len() as u64);
+			}
+			for item in items {
+				encode_field_value(data, item, itemid, false, provider)?;
+			}
+		}
+		FIXED_ARR_TYPEID => {
+			let items = value.as_slice()?;
+			let itemid = typeid.item.as_ref()?.as_ref();
+
+			if items.len() != typeid.variant as usize {
+				return None;
+			}
+			for item in items {
+				encode_field_value(data, item, itemid, false, provider)?;
+			}
+		}
+		TUPLE_TYPEID => {
+			let items = value.as_slice()?;
+
+			if items.len() != typeid.args.len() {
+				return None;
+			}
+			for (item, itemid) in items.iter().zip(&typeid.args) {
+				encode_field_value(data, item, itemid, false, provider)?;
+			}
+		}
+		MAP_TYPEID => {
+			let map = value.as_map()?;
+			let itemid = typeid.item.as_ref()?.as_ref();
+
+			if !in_field {
+				encode_vuint(data, map.len() as u64);
+			}
+			for (key, item) in map.iter() {
+				encode_primitive(data, &Value::from(key.clone()), typeid.variant)?;
+				encode_field_value(data, item, itemid, false, provider)?;
+			}
+		}
+		id => encode_primitive(data, value, id)?,
+	}
+	Some(())
+}
+
+fn encode_primitive(data: &mut Vec<u8>, value: &Value, id: u16) -> Option<()> {
+	match (id, value) {
+		(ANY_TYPEID, value) => encode_any(data, value),
+		(BOOL_TYPEID, Value::Bool(b)) => encode_bool(data, *b),
+
+		(U8_TYPEID, Value::Uint(nb)) => encode_u8(data, u8::try_from(*nb).ok()?),
+		(U16_TYPEID, Value::Uint(nb)) => encode_u16(data, u16::try_from(*nb).ok()?),
+		(U32_TYPEID, Value::Uint(nb)) => encode_u32(data, u32::try_from(*nb).ok()?),
+		(U64_TYPEID, Value::Uint(nb)) => encode_u64(data, *nb),
+
+		(I8_TYPEID, Value::Int(nb)) => encode_i8(data, i8::try_from(*nb).ok()?),
+		(I16_TYPEID, Value::Int(nb)) => encode_i16(data, i16::try_from(*nb).ok()?),
+		(I32_TYPEID, Value::Int(nb)) => encode_i32(data, i32::try_from(*nb).ok()?),
+		(I64_TYPEID, Value::Int(nb)) => encode_i64(data, *nb),
+
+		(VUINT_TYPEID, Value::Uint(nb)) => encode_vuint(data, *nb),
+		(VINT_TYPEID, Value::Int(nb)) => encode_vint(data, *nb),
+		(BINT_TYPEID, Value::BigInt(nb)) => encode_u8_arr(data, nb),
+
+		(F32_TYPEID, Value::Float(nb)) => encode_f32(data, *nb as f32),
+		(F64_TYPEID, Value::Float(nb)) => encode_f64(data, *nb),
+		(DEC_TYPEID, Value::Decimal(dec)) => encode_dec(data, dec),
+
+		(STR_TYPEID, Value::Str(str)) => encode_str(data, str),
+
+		(UUID_TYPEID, Value::UUID(uuid)) => encode_uuid(data, uuid),
+		(INST_TYPEID, Value::Inst(inst)) => encode_inst(data, inst),
+		(INSTN_TYPEID, Value::Inst(inst)) => encode_instN(data, inst),
+		(INSTZ_TYPEID, Value::InstZ(inst)) => encode_instZ(data, inst),
+		(DATE_TYPEID, Value::Date(date)) => encode_date(data, date),
+		(TIME_TYPEID, Value::Time(time)) => encode_time(data, time),
+		(DUR_TYPEID, Value::Dur(dur)) => encode_dur(data, dur),
+		_ => return None,
+	}
+	Some(())
+}
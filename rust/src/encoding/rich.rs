@@ -1,6 +1,12 @@
-use chrono::{DateTime, TimeDelta, Timelike, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveTime, TimeDelta, Timelike, Utc};
 
-use crate::encoding::{decode_i64, decode_u32, encode_i64, encode_u32};
+use crate::{
+	Decimal,
+	encoding::{
+		decode_i16, decode_i32, decode_i64, decode_u8, decode_u32, decode_u64, decode_vint,
+		encode_i16, encode_i32, encode_i64, encode_u32, encode_u64, encode_vint,
+	},
+};
 
 #[inline]
 pub fn encode_uuid(data: &mut Vec<u8>, value: &[u8; 16]) {
@@ -33,6 +39,42 @@ pub fn decode_instN(data: &[u8], ind: &mut usize) -> Option<DateTime<Utc>> {
 	DateTime::from_timestamp_millis(decode_i64(data, ind)?)?.with_nanosecond(decode_u32(data, ind)?)
 }
 
+#[inline]
+pub fn encode_instZ(data: &mut Vec<u8>, value: &DateTime<FixedOffset>) {
+	encode_i64(data, value.timestamp_millis());
+	encode_i16(data, (value.offset().local_minus_utc() / 60) as i16);
+}
+#[inline]
+pub fn decode_instZ(data: &[u8], ind: &mut usize) -> Option<DateTime<FixedOffset>> {
+	let inst = DateTime::from_timestamp_millis(decode_i64(data, ind)?)?;
+	let offset = FixedOffset::east_opt(decode_i16(data, ind)? as i32 * 60)?;
+	Some(inst.with_timezone(&offset))
+}
+
+// dates are days since unix epoch
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
+#[inline]
+pub fn encode_date(data: &mut Vec<u8>, value: &NaiveDate) {
+	encode_i32(data, value.num_days_from_ce() - EPOCH_DAYS_FROM_CE);
+}
+#[inline]
+pub fn decode_date(data: &[u8], ind: &mut usize) -> Option<NaiveDate> {
+	NaiveDate::from_num_days_from_ce_opt(decode_i32(data, ind)?.checked_add(EPOCH_DAYS_FROM_CE)?)
+}
+
+// times are nanoseconds since midnight
+#[inline]
+pub fn encode_time(data: &mut Vec<u8>, value: &NaiveTime) {
+	let secs = value.num_seconds_from_midnight() as u64;
+	encode_u64(data, secs * 1_000_000_000 + value.nanosecond() as u64);
+}
+#[inline]
+pub fn decode_time(data: &[u8], ind: &mut usize) -> Option<NaiveTime> {
+	let value = decode_u64(data, ind)?;
+	let (secs, nanos) = ((value / 1_000_000_000) as u32, (value % 1_000_000_000) as u32);
+	NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos)
+}
+
 #[inline]
 pub fn encode_dur(data: &mut Vec<u8>, value: &TimeDelta) {
 	encode_i64(data, value.num_nanoseconds().unwrap());
@@ -41,3 +83,14 @@ pub fn encode_dur(data: &mut Vec<u8>, value: &TimeDelta) {
 pub fn decode_dur(data: &[u8], ind: &mut usize) -> Option<TimeDelta> {
 	Some(TimeDelta::nanoseconds(decode_i64(data, ind)?))
 }
+
+#[inline]
+pub fn encode_dec(data: &mut Vec<u8>, value: &Decimal) {
+	encode_vint(data, value.value);
+	data.push(value.scale);
+}
+#[inline]
+pub fn decode_dec(data: &[u8], ind: &mut usize) -> Option<Decimal> {
+	let value = decode_vint(data, ind)?;
+	Some(Decimal::new(value, decode_u8(data, ind)?))
+}
@@ -15,6 +15,9 @@ struct ParseErrorData {
 	// offset and size of file in msg
 	at_offset: u16,
 	at_size: u16,
+
+	// label of the provider the file came from
+	origin: Option<Box<str>>,
 }
 
 /// error encountered during parsing.
@@ -25,7 +28,11 @@ pub struct ParseError {
 
 impl Display for ParseError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		write!(f, "{}", self.data.msg)
+		write!(f, "{}", self.data.msg)?;
+		match &self.data.origin {
+			Some(origin) => write!(f, "\n  from \"{origin}\""),
+			None => Ok(()),
+		}
 	}
 }
 
@@ -39,6 +46,15 @@ impl ParseError {
 		let ParseErrorData { msg, at_offset, at_size, .. } = self.data.deref();
 		&msg[*at_offset as usize..(at_offset + at_size) as usize]
 	}
+	/// label of the provider the file came from, when loaded through a [`ChainProvider`](crate::ChainProvider)
+	pub fn origin(&self) -> Option<&str> {
+		self.data.origin.as_deref()
+	}
+	/// set the origin if not set, the innermost provider is the relevant one
+	pub(crate) fn with_origin(mut self, origin: &str) -> Self {
+		self.data.origin.get_or_insert_with(|| origin.into());
+		self
+	}
 
 	const AT_PRE: &'static str = "\n  --> ";
 	pub(crate) fn with_pos<T>(mut msg: String, pos: Pos, file: &str) -> Result<T, ParseError> {
@@ -46,7 +62,8 @@ impl ParseError {
 		write!(msg, "{}{file}:{pos}\"", Self::AT_PRE).unwrap();
 		let at_size = file.len() as u16;
 		let msg = msg.into_boxed_str();
-		Err(Self { data: Box::new(ParseErrorData { pos, msg, at_offset, at_size }) })
+		let data = ParseErrorData { pos, msg, at_offset, at_size, origin: None };
+		Err(Self { data: Box::new(data) })
 	}
 	pub(crate) fn new<T>(mut msg: String, file: &str) -> Result<T, ParseError> {
 		let at_offset = (msg.len() + Self::AT_PRE.len()) as u16;
@@ -54,7 +71,8 @@ impl ParseError {
 		let pos = Pos::new(0, 0);
 		let at_size = file.len() as u16;
 		let msg = msg.into_boxed_str();
-		Err(Self { data: Box::new(ParseErrorData { pos, msg, at_offset, at_size }) })
+		let data = ParseErrorData { pos, msg, at_offset, at_size, origin: None };
+		Err(Self { data: Box::new(data) })
 	}
 }
 
@@ -0,0 +1,113 @@
+use crate::{
+	DeclProvider,
+	declaration::{DeclItem, StructDef, TypeId},
+	encoding::encode_vuint,
+};
+
+// markers separating the kinds of items and types
+const STRUCT: u8 = 1;
+const ENUM: u8 = 2;
+const FLAGS: u8 = 3;
+const BUILTIN: u8 = 4;
+const USER: u8 = 5;
+const RECURSIVE: u8 = 6;
+
+/// FNV-1a hash of the layout of an item, see [`DeclItem::fingerprint`]
+pub(crate) fn fingerprint(item: &DeclItem, provider: &dyn DeclProvider) -> u64 {
+	let mut data = Vec::new();
+	write_item(&mut data, item, &mut Vec::new(), provider);
+
+	let mut hash = 0xcbf29ce484222325u64;
+	for byte in data {
+		hash ^= byte as u64;
+		hash = hash.wrapping_mul(0x100000001b3);
+	}
+	hash
+}
+
+/// write the layout of an item, `stack` holds the items being written to break recursion
+fn write_item<'a>(
+	data: &mut Vec<u8>, item: &'a DeclItem, stack: &mut Vec<&'a DeclItem>,
+	provider: &'a dyn DeclProvider,
+) {
+	stack.push(item);
+	match item {
+		DeclItem::Struct { def, .. } => {
+			data.push(STRUCT);
+			write_def(data, def, stack, provider);
+		}
+		DeclItem::Enum { variants, .. } => {
+			data.push(ENUM);
+			let variants: Vec<_> = variants.iter().flatten().collect();
+			encode_vuint(data, variants.len() as u64);
+			for variant in variants {
+				encode_vuint(data, variant.tag as u64);
+				match &variant.def {
+					Some(def) => {
+						data.push(1);
+						write_def(data, def, stack, provider);
+					}
+					None => data.push(0),
+				}
+			}
+		}
+		DeclItem::Flags { flags, .. } => {
+			data.push(FLAGS);
+			let bits: Vec<_> =
+				flags.iter().enumerate().filter(|(_, flag)| flag.is_some()).collect();
+			encode_vuint(data, bits.len() as u64);
+			for (bit, _) in bits {
+				encode_vuint(data, bit as u64);
+			}
+		}
+	}
+	stack.pop();
+}
+fn write_def<'a>(
+	data: &mut Vec<u8>, def: &'a StructDef, stack: &mut Vec<&'a DeclItem>,
+	provider: &'a dyn DeclProvider,
+) {
+	let fields: Vec<_> = def.fields.iter().flatten().collect();
+	encode_vuint(data, fields.len() as u64);
+	for field in fields {
+		encode_vuint(data, field.tag as u64);
+		data.push(field.is_optional as u8);
+		write_type(data, &field.typeid, stack, provider);
+	}
+}
+/// types are written by layout, user types by the layout of their item, metadata is erased
+fn write_type<'a>(
+	data: &mut Vec<u8>, typeid: &'a TypeId, stack: &mut Vec<&'a DeclItem>,
+	provider: &'a dyn DeclProvider,
+) {
+	if typeid.is_builtin() {
+		data.push(BUILTIN);
+		encode_vuint(data, typeid.id as u64);
+		encode_vuint(data, typeid.variant as u64);
+		match &typeid.item {
+			Some(item) => {
+				data.push(1);
+				write_type(data, item, stack, provider);
+			}
+			None => data.push(0),
+		}
+	} else {
+		let item = provider.get(typeid.ns).get_by_id(typeid.id).unwrap();
+		// recursive items are referenced by their depth in the stack
+		match stack.iter().position(|parent| std::ptr::eq(*parent, item)) {
+			Some(depth) => {
+				data.push(RECURSIVE);
+				encode_vuint(data, depth as u64);
+			}
+			None => {
+				data.push(USER);
+				write_item(data, item, stack, provider);
+			}
+		}
+	}
+	// generic arguments and tuple items
+	encode_vuint(data, typeid.args.len() as u64);
+	for arg in &typeid.args {
+		write_type(data, arg, stack, provider);
+	}
+}
@@ -1,23 +1,43 @@
 use std::{
-	cell::RefCell,
-	collections::HashMap,
-	fs::{canonicalize, read_to_string},
+	borrow::Borrow,
+	collections::{HashMap, HashSet},
+	fs::{canonicalize, metadata, read_to_string},
+	hash::Hash,
 	io,
 	path::{Path, PathBuf, absolute},
+	sync::RwLock,
+	time::SystemTime,
 };
 
 use crate::{
-	DeclFile, DeclProvider, ParseError, ParseOptions, errors::ImportError, parse_declaration_file,
+	BundleProvider, ChainProvider, DeclFile, DeclProvider, ParseOptions, SourceProvider,
+	declaration::{DeclItem, StructDef},
+	errors::ImportError,
+	parse_declaration_file,
+};
+
+// the providers must stay shareable between threads
+const _: fn() = || {
+	fn assert<T: Send + Sync>() {}
+	assert::<FSProvider>();
+	assert::<SourceProvider>();
+	assert::<ChainProvider>();
+	assert::<BundleProvider>();
 };
 
 /// provider that loads declerations from the file system.
 ///
 /// this provider syncronously loads decleration files from the file system, and caches them for future use.
 ///
-/// this provider works only in a specifed root directory, and loads files of any extension.
+/// this provider works only in a specifed root directory, and loads files of any extension, files outside it are not found.
 ///
 /// it can fails safely when loading.
 ///
+/// cached files changed on disk are reloaded through [`refresh`](FSProvider::refresh), or on load when created [`with_hot_reload`](FSProvider::with_hot_reload).
+/// the outdated versions are kept until [`purge`](FSProvider::purge), since references to them can still exist.
+///
+/// it is thread safe, see [`DeclProvider`].
+///
 /// ## example
 /// ```
 /// let provider = FSProvider::new("/path/to/decls").unwrap();
@@ -38,12 +58,29 @@ use crate::{
 pub struct FSProvider {
 	root: PathBuf,
 	parse_options: ParseOptions,
-	cache: RefCell<ProviderCache>,
+	cache: RwLock<ProviderCache<PathBuf>>,
+	/// modification time of the loaded files by id
+	stamps: RwLock<HashMap<u64, SystemTime>>,
+	hot_reload: bool,
 }
+/// append only cache, files are boxed and only removed by `purge` through `&mut`, so references to them stay valid
+///
+/// the lock around it is not held while parsing, since the imports of the parsed file load through the same provider
 #[derive(Debug, Default)]
-struct ProviderCache {
+pub(crate) struct ProviderCache<K> {
 	files: HashMap<u64, Box<DeclFile>>,
-	files_by_name: HashMap<PathBuf, u64>,
+	files_by_name: HashMap<K, u64>,
+}
+
+/// changes found by [`FSProvider::refresh`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RefreshReport {
+	/// names of the reloaded files, changed on disk or importing a changed file
+	pub files: Vec<String>,
+	/// file name and type name of the added, removed and changed types
+	pub types: Vec<(String, String)>,
+	/// names of the files that failed to reload with their error, removed files are dropped, others keep their previous version
+	pub errors: Vec<(String, ImportError)>,
 }
 
 impl FSProvider {
@@ -53,46 +90,228 @@ impl FSProvider {
 	}
 	/// creates a `FSProvider` working on a given root directory with given options.
 	pub fn with_options(root: impl Into<PathBuf>, parse_options: ParseOptions) -> io::Result<Self> {
-		Ok(Self { root: canonicalize(root.into())?, parse_options, cache: Default::default() })
+		let (cache, stamps) = (Default::default(), Default::default());
+		let root = canonicalize(root.into())?;
+		Ok(Self { root, parse_options, cache, stamps, hot_reload: false })
+	}
+	/// enables hot reloading, loading a cached file refreshes the provider if it or its imports changed on disk.
+	///
+	/// files failing to reload keep their previous version until they change again, and every reload keeps the outdated files until [`purge`](FSProvider::purge).
+	pub fn with_hot_reload(mut self) -> Self {
+		self.hot_reload = true;
+		self
 	}
 
 	/// load a declaration file at a given path.
 	///
 	/// returns a reference to the cached file if used before, else load it and returns `LoadFileError` if an error occurs.
 	pub fn load_file<'a>(&'a self, path: impl AsRef<Path>) -> Result<&'a DeclFile, ImportError> {
-		let path = absolute(Path::join(&self.root, path.as_ref()))
+		self.load_file_with(path.as_ref(), self)
+	}
+	fn load_file_with<'a>(
+		&'a self, path: &Path, imports: &dyn DeclProvider,
+	) -> Result<&'a DeclFile, ImportError> {
+		let path = absolute(Path::join(&self.root, path))
 			.map_err(|e| ImportError::Other(e.to_string()))?;
-		{
-			let cache = self.cache.borrow();
-			if let Some(id) = cache.files_by_name.get(&path) {
-				return Ok(unsafe {
-					&*(cache.files.get(&id).unwrap().as_ref() as *const DeclFile)
-				});
+		let cached = self.cache.read().unwrap().get_by_name(&path);
+		if let Some(file) = cached {
+			if !self.hot_reload || !self.is_stale(file) {
+				return Ok(file);
 			}
+			self.refresh_with(imports);
+			// removed from disk
+			return self.cache.read().unwrap().get_by_name(&path).ok_or(ImportError::NotFound);
 		}
 
+		// files outside root are not found, so other providers in a chain can be tried
 		if !path.starts_with(&self.root) {
-			return Err(ImportError::Other(format!(
-				"importing outside root \"{}\"",
-				path.display()
-			)));
+			return Err(ImportError::NotFound);
 		}
-		let source = read_to_string(&path).map_err(|e| ImportError::Other(e.to_string()))?;
+		let file = self.parse_file(&path, imports)?;
+		Ok(self.cache.write().unwrap().insert(path, file))
+	}
+	fn parse_file(&self, path: &Path, imports: &dyn DeclProvider) -> Result<DeclFile, ImportError> {
+		let io_err = |e: io::Error| match e.kind() {
+			io::ErrorKind::NotFound => ImportError::NotFound,
+			_ => ImportError::Other(e.to_string()),
+		};
+		// taken before reading, so changes while reading are seen by the next check
+		let modified = metadata(path).and_then(|meta| meta.modified()).map_err(io_err)?;
+		let source = read_to_string(path).map_err(io_err)?;
 		let file_name = path.to_str().unwrap().to_string();
-		let file = parse_declaration_file(&source, file_name, &self.parse_options, self)
+		let file = parse_declaration_file(&source, file_name, &self.parse_options, imports)
 			.map_err(ImportError::Parse)?;
 
-		let mut cache = self.cache.borrow_mut();
-		let id = file.id;
-		cache.files.insert(file.id, Box::new(file));
-		cache.files_by_name.insert(path, id);
+		self.stamps.write().unwrap().insert(file.id, modified);
+		Ok(file)
+	}
 
-		Ok(unsafe { &*(cache.files.get(&id).unwrap().as_ref() as *const DeclFile) })
+	/// whether a cached file or one of its imports changed on disk
+	fn is_stale(&self, file: &DeclFile) -> bool {
+		let stamp = self.stamps.read().unwrap().get(&file.id).copied();
+		let modified = metadata(&file.name).and_then(|meta| meta.modified()).ok();
+		// imports from other providers in a chain are not tracked
+		modified != stamp
+			|| file.imports.iter().any(|id| {
+				let import = self.cache.read().unwrap().find(*id);
+				import.is_some_and(|import| self.is_stale(import))
+			})
+	}
+
+	/// reload the cached files that changed on disk, and the files importing them.
+	///
+	/// the reloaded files get new ids, references to the old files stay valid but outdated, the old files are kept in memory until [`purge`](FSProvider::purge).
+	///
+	/// returns the reloaded files, the types that changed ignoring their annotations, and the files that failed to reload.
+	pub fn refresh(&self) -> RefreshReport {
+		self.refresh_with(self)
+	}
+	fn refresh_with(&self, imports: &dyn DeclProvider) -> RefreshReport {
+		let files: Vec<(PathBuf, &DeclFile)> = {
+			let cache = self.cache.read().unwrap();
+			cache
+				.files_by_name
+				.iter()
+				.map(|(path, id)| (path.clone(), cache.get(*id)))
+				.collect()
+		};
+
+		// changed files, then the files importing them
+		let mut pending: Vec<&(PathBuf, &DeclFile)> = {
+			let stamps = self.stamps.read().unwrap();
+			let is_changed = |(path, file): &&(PathBuf, &DeclFile)| {
+				let modified = metadata(path).and_then(|meta| meta.modified()).ok();
+				modified != stamps.get(&file.id).copied()
+			};
+			files.iter().filter(is_changed).collect()
+		};
+		loop {
+			let importers = files.iter().filter(|(_, file)| {
+				let is_pending = |id: &u64| pending.iter().any(|(_, pending)| pending.id == *id);
+				!is_pending(&file.id) && file.imports.iter().any(is_pending)
+			});
+			let importers: Vec<_> = importers.collect();
+			if importers.is_empty() {
+				break;
+			}
+			pending.extend(importers);
+		}
+
+		let mut report = RefreshReport::default();
+		while !pending.is_empty() {
+			// files are reloaded after their imports
+			let ind = pending.iter().position(|(_, file)| {
+				!file
+					.imports
+					.iter()
+					.any(|id| pending.iter().any(|(_, pending)| pending.id == *id))
+			});
+			let (path, old) = pending.remove(ind.unwrap());
+
+			let modified = metadata(path).and_then(|meta| meta.modified()).ok();
+			let new = match self.parse_file(path, imports) {
+				Ok(new) => self.cache.write().unwrap().replace(path.clone(), new),
+				// removed files are dropped, with all their types
+				Err(ImportError::NotFound) => {
+					self.cache.write().unwrap().files_by_name.remove(path);
+					self.stamps.write().unwrap().remove(&old.id);
+					let names = old.items_by_name.keys();
+					report.types.extend(names.map(|name| (old.name.clone(), name.clone())));
+					report.errors.push((old.name.clone(), ImportError::NotFound));
+					continue;
+				}
+				Err(error) => {
+					report.errors.push((old.name.clone(), error));
+					// kept until changed again, not retried on every load
+					if let Some(modified) = modified {
+						self.stamps.write().unwrap().insert(old.id, modified);
+					}
+					continue;
+				}
+			};
+			// the old file is no more checked, for the importers failing to reload
+			if let Some(modified) = modified {
+				self.stamps.write().unwrap().insert(old.id, modified);
+			}
+
+			report.files.push(new.name.clone());
+			let mut names: Vec<_> =
+				old.items_by_name.keys().chain(new.items_by_name.keys()).collect();
+			names.sort();
+			names.dedup();
+			for name in names {
+				let is_same = match (old.get_by_name(name), new.get_by_name(name)) {
+					(Some(old), Some(new)) => same_item(old, new, imports),
+					_ => false,
+				};
+				if !is_same {
+					report.types.push((new.name.clone(), name.clone()));
+				}
+			}
+		}
+		report
+	}
+
+	/// drop the outdated files kept after reloading.
+	///
+	/// taking `&mut self` ensures no reference to them exists, a provider shared through an `Arc` needs to be unique.
+	pub fn purge(&mut self) {
+		let cache = self.cache.get_mut().unwrap();
+		cache.purge();
+		self.stamps.get_mut().unwrap().retain(|id, _| cache.files.contains_key(id));
+	}
+}
+impl<K: Eq + Hash> ProviderCache<K> {
+	/// the returned reference outlives the lock guard, valid as long as the provider lives
+	pub fn get<'a>(&self, id: u64) -> &'a DeclFile {
+		self.find(id).unwrap()
+	}
+	pub fn find<'a>(&self, id: u64) -> Option<&'a DeclFile> {
+		// SAFETY: files are boxed and only removed through `purge`, taking the provider mutably
+		self.files.get(&id).map(|file| unsafe { &*(file.as_ref() as *const DeclFile) })
+	}
+	/// the current files, without the outdated ones
+	pub fn files<'a>(&self) -> Vec<&'a DeclFile> {
+		self.files_by_name.values().map(|id| self.get(*id)).collect()
+	}
+	pub fn get_by_name<'a, Q>(&self, name: &Q) -> Option<&'a DeclFile>
+	where
+		K: Borrow<Q>,
+		Q: Eq + Hash + ?Sized,
+	{
+		self.files_by_name.get(name).map(|id| self.get(*id))
+	}
+	/// add a loaded file, keeping the cached one if another thread loaded it meanwhile
+	pub fn insert<'a>(&mut self, name: K, file: DeclFile) -> &'a DeclFile {
+		if let Some(cached) = self.get_by_name(&name) {
+			return cached;
+		}
+		self.replace(name, file)
+	}
+	/// add a reloaded file under a name, the replaced file is kept for the references to it
+	pub fn replace<'a>(&mut self, name: K, file: DeclFile) -> &'a DeclFile {
+		let id = file.id;
+		self.files.insert(id, Box::new(file));
+		self.files_by_name.insert(name, id);
+		self.get(id)
+	}
+	/// drop the files not reachable by name, directly or through imports
+	pub fn purge(&mut self) {
+		let mut kept = HashSet::new();
+		let mut pending: Vec<u64> = self.files_by_name.values().copied().collect();
+		while let Some(id) = pending.pop() {
+			if let Some(file) = self.files.get(&id)
+				&& kept.insert(id)
+			{
+				pending.extend(&file.imports);
+			}
+		}
+		self.files.retain(|id, _| kept.contains(id));
 	}
 }
 impl DeclProvider for FSProvider {
 	fn get(&self, id: u64) -> &DeclFile {
-		unsafe { &*(self.cache.borrow().files.get(&id).unwrap().as_ref() as *const DeclFile) }
+		self.cache.read().unwrap().get(id)
 	}
 	/// gets a decleration file with a given name.
 	///
@@ -100,4 +319,54 @@ impl DeclProvider for FSProvider {
 	fn load<'a>(&'a self, name: &str) -> Result<&'a DeclFile, ImportError> {
 		self.load_file(name)
 	}
+	fn load_with<'a>(
+		&'a self, name: &str, imports: &dyn DeclProvider,
+	) -> Result<&'a DeclFile, ImportError> {
+		self.load_file_with(name.as_ref(), imports)
+	}
+	fn find(&self, id: u64) -> Option<&DeclFile> {
+		self.cache.read().unwrap().find(id)
+	}
+	fn files(&self) -> Vec<&DeclFile> {
+		self.cache.read().unwrap().files()
+	}
+}
+
+/// whether two versions of an item have the same name, parameters and members
+fn same_item(old: &DeclItem, new: &DeclItem, provider: &dyn DeclProvider) -> bool {
+	if old.name() != new.name() || old.params() != new.params() {
+		return false;
+	}
+	match (old, new) {
+		(DeclItem::Struct { def: old, .. }, DeclItem::Struct { def: new, .. }) => {
+			same_def(old, new, provider)
+		}
+		(DeclItem::Enum { variants: old, .. }, DeclItem::Enum { variants: new, .. }) => {
+			old.len() == new.len()
+				&& old.iter().zip(new).all(|(old, new)| match (old, new) {
+					(Some(old), Some(new)) => {
+						old.name == new.name
+							&& match (&old.def, &new.def) {
+								(Some(old), Some(new)) => same_def(old, new, provider),
+								(old, new) => old.is_none() && new.is_none(),
+							}
+					}
+					(old, new) => old.is_none() && new.is_none(),
+				})
+		}
+		(DeclItem::Flags { flags: old, .. }, DeclItem::Flags { flags: new, .. }) => old == new,
+		_ => false,
+	}
+}
+fn same_def(old: &StructDef, new: &StructDef, provider: &dyn DeclProvider) -> bool {
+	old.fields.len() == new.fields.len()
+		&& old.fields.iter().zip(&new.fields).all(|(old, new)| match (old, new) {
+			(Some(old), Some(new)) => {
+				old.name == new.name
+					&& old.is_optional == new.is_optional
+					&& old.default == new.default
+					&& old.typeid.name(provider) == new.typeid.name(provider)
+			}
+			(old, new) => old.is_none() && new.is_none(),
+		})
 }
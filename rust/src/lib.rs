@@ -24,29 +24,42 @@
 //!
 //! this crate supports every feature of the structom specification.
 
+mod builder;
 pub(crate) mod builtins;
+pub mod bundle;
+mod chain_provider;
+pub mod compat;
 mod declaration;
 pub mod encoding;
 mod errors;
+mod fingerprint;
 mod fs_decl_provider;
 mod parser;
+pub mod reflect;
+mod source_provider;
 mod stringify;
 mod value;
 
+pub use builder::{DeclFileBuilder, EnumBuilder, StructBuilder, TypeExpr};
+pub use bundle::BundleProvider;
+pub use chain_provider::ChainProvider;
 pub use declaration::{
-	DeclFile, DeclProvider, FixedSetProvider, FixedSetProviderRef, VoidProvider,
+	DeclFile, DeclProvider, FixedSetProvider, FixedSetProviderRef, TypeId, VoidProvider,
 };
-pub use encoding::{Serialized, decode, encode};
-pub use errors::ParseError;
-pub use fs_decl_provider::FSProvider;
-pub use parser::{ParseOptions, parse, parse_declaration_file};
-pub use stringify::{StringifyOptions, stringify};
-pub use value::{Key, Value};
+pub use encoding::{Serialized, decode, encode, encode_root};
+pub use errors::{ImportError, ParseError};
+pub use fs_decl_provider::{FSProvider, RefreshReport};
+pub use parser::{OnWarning, ParseOptions, RootType, parse, parse_as, parse_declaration_file};
+pub use source_provider::SourceProvider;
+pub use stringify::{StringifyOptions, stringify, stringify_declarations};
+pub use value::{Decimal, Key, Value};
 
 #[doc(hidden)]
 pub mod internal {
 	pub use crate::builtins::*;
-	pub use crate::declaration::{DeclItem, EnumVariant, Field, StructDef, TypeId};
+	pub use crate::declaration::{
+		Annotations, DeclItem, EnumVariant, Field, Reserved, StructDef, TypeId,
+	};
 }
 
 #[cfg(test)]
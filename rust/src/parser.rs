@@ -4,28 +4,51 @@ pub mod tokenizer;
 mod utils;
 mod value;
 
+use std::{fmt, sync::Arc};
+
 use crate::{
 	DeclProvider, ParseError, Value,
 	declaration::{DeclFile, TypeId},
-	errors::{ImportError, unexpected_token},
+	errors::{ImportError, err},
 	parser::{
-		declaration::{DeclContext, parse_declarations},
-		tokenizer::tokenize,
+		declaration::{imported_root, parse_declarations},
+		tokenizer::{Docs, tokenize},
+		utils::unexpected_token,
 	},
 };
 
+pub(crate) use declaration::{DeclContext, parse_type_expr};
+
 /// parsing options.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ParseOptions {
 	/// whether to keep metadata in result, default: `false`.
 	pub metadata: bool,
 
 	pub relative_paths: bool,
+
+	/// called with non fatal issues, like using deprecated fields, default: `None`.
+	///
+	/// it can capture state, like a list collecting the warnings.
+	pub on_warning: Option<OnWarning>,
+}
+
+/// callback receiving parsing warnings.
+pub type OnWarning = Arc<dyn Fn(&str) + Send + Sync>;
+
+impl fmt::Debug for ParseOptions {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ParseOptions")
+			.field("metadata", &self.metadata)
+			.field("relative_paths", &self.relative_paths)
+			.field("on_warning", &self.on_warning.as_ref().map(|_| "Fn(&str)"))
+			.finish()
+	}
 }
 
 impl Default for ParseOptions {
 	fn default() -> Self {
-		Self { metadata: false, relative_paths: true }
+		Self { metadata: false, relative_paths: true, on_warning: None }
 	}
 }
 
@@ -42,30 +65,31 @@ impl Default for ParseOptions {
 pub fn parse_declaration_file(
 	source: &str, name: String, options: &ParseOptions, provider: &dyn DeclProvider,
 ) -> Result<DeclFile, ParseError> {
-	let tokens = tokenize(source)?;
+	let mut docs = Docs::new();
+	let tokens = tokenize(source, &name, Some(&mut docs))?;
 	let mut ind = 0;
 
 	let mut file = DeclFile::new(name);
-	parse_declarations(&mut file, &tokens, &mut ind, provider, options)?;
+	parse_declarations(&mut file, &tokens, &docs, &mut ind, provider, options)?;
 
 	// ensure all tokens have been consumed
 	if ind != tokens.len() - 1 {
-		return Err(unexpected_token(&tokens[ind], tokens[ind].pos()));
+		return unexpected_token(&tokens[ind], tokens[ind].pos(), &file.name);
 	}
 	// ensure file is not empty
-	if file.items.len() == 0 {
-		return Err(ParseError::TypeError(format!("no declaration in file \"{}\"", file.name)));
+	if file.items.is_empty() {
+		return err!(format!("no declaration in file \"{}\"", file.name), &file.name);
 	}
 
 	Ok(file)
 }
 
-struct MiddleProvider<'a> {
+struct MiddleProvider<'a, 'b> {
 	provider: &'a dyn DeclProvider,
-	ctx: &'a DeclContext<'a>,
+	ctx: &'a DeclContext<'b>,
 }
 
-impl DeclProvider for MiddleProvider<'_> {
+impl DeclProvider for MiddleProvider<'_, '_> {
 	fn get<'a>(&'a self, id: u64) -> &'a DeclFile {
 		if id == self.ctx.file.id { self.ctx.file } else { self.provider.get(id) }
 	}
@@ -78,6 +102,8 @@ impl DeclProvider for MiddleProvider<'_> {
 ///
 /// the source is made up of optional declerations at top, followed by a root value.
 ///
+/// the root value is checked against the type declared through `root`, or else the one declared by the imported files.
+///
 /// it takes parsing options and a decleration provider used to resolve imports.
 ///
 /// for info on how the values are represented, see the [`Value`] documentation.
@@ -91,17 +117,88 @@ impl DeclProvider for MiddleProvider<'_> {
 pub fn parse(
 	source: &str, options: &ParseOptions, provider: &dyn DeclProvider,
 ) -> Result<Value, ParseError> {
-	let tokens = tokenize(source)?;
+	parse_root(source, None, options, provider)
+}
+
+/// expected type of the root value passed to [`parse_as`].
+#[derive(Debug, Clone, Copy)]
+pub enum RootType<'a> {
+	/// a resolved typeid.
+	Id(&'a TypeId),
+	/// an item or type alias by its qualified name in a declaration file.
+	Named(&'a DeclFile, &'a str),
+}
+impl<'a> From<&'a TypeId> for RootType<'a> {
+	fn from(typeid: &'a TypeId) -> Self {
+		Self::Id(typeid)
+	}
+}
+impl<'a> From<(&'a DeclFile, &'a str)> for RootType<'a> {
+	fn from((file, name): (&'a DeclFile, &'a str)) -> Self {
+		Self::Named(file, name)
+	}
+}
+
+/// parse a structom file into a [`Value`], checking its root value against an expected type.
+///
+/// same as [`parse`], but the root value is inferred from the expected type, so it can be written without a type prefix.
+///
+/// the declaration file of the expected type must be reachable through the provider.
+///
+/// ## example
+/// ```
+/// let config = provider.load("config.stomd").unwrap();
+/// let value = parse_as(
+/// 	"{ port: 8080 }", (config, "Config"), &ParseOptions::default(), &provider
+/// ).unwrap();
+/// ```
+pub fn parse_as<'a>(
+	source: &str, root: impl Into<RootType<'a>>, options: &ParseOptions,
+	provider: &dyn DeclProvider,
+) -> Result<Value, ParseError> {
+	let rootid = match root.into() {
+		RootType::Id(typeid) => typeid.clone(),
+		RootType::Named(file, name) => match (file.get_alias(name), file.get_by_name(name)) {
+			(Some(alias), _) => alias.clone(),
+			(_, Some(item)) if !item.params().is_empty() => {
+				let count = item.params().len();
+				let msg = format!("generic type \"{name}\" requires {count} type arguments");
+				return err!(msg, &file.name);
+			}
+			(_, Some(item)) => TypeId::new(file.id, item.typeid(), None),
+			_ => return err!(format!("undefined type \"{name}\""), &file.name),
+		},
+	};
+	parse_root(source, Some(&rootid), options, provider)
+}
+
+/// parse the declarations and the root value, against `expected` if passed
+fn parse_root(
+	source: &str, expected: Option<&TypeId>, options: &ParseOptions, provider: &dyn DeclProvider,
+) -> Result<Value, ParseError> {
+	let tokens = tokenize(source, "root", None)?;
 	let mut ind = 0;
 
 	let mut root_file = DeclFile::new("root".to_string());
-	let ctx = parse_declarations(&mut root_file, &tokens, &mut ind, provider, options)?;
-
+	let ctx = parse_declarations(&mut root_file, &tokens, &[], &mut ind, provider, options)?;
 	let _provider = MiddleProvider { provider, ctx: &ctx };
-	let value = value::parse_value(&tokens, &mut ind, &TypeId::ANY, &ctx, &_provider, options)?;
+
+	let any = TypeId::ANY;
+	let rootid = match (expected, &ctx.file.root) {
+		// the declared root must agree with the expected type
+		(Some(expected), Some(rootid)) if expected != rootid => {
+			let (rootid, expected) = (rootid.name(&_provider), expected.name(&_provider));
+			let msg = format!("root type \"{rootid}\" does not match expected type \"{expected}\"");
+			return err!(msg, &ctx.file.name);
+		}
+		(Some(rootid), _) | (None, Some(rootid)) => rootid,
+		(None, None) => imported_root(&ctx)?.unwrap_or(&any),
+	};
+
+	let value = value::parse_value(&tokens, &mut ind, rootid, &ctx, &_provider, options)?;
 	// ensure all tokens have been consumed
 	if tokens.len() - 1 != ind {
-		return Err(unexpected_token(&tokens[ind], tokens[ind].pos()));
+		return unexpected_token(&tokens[ind], tokens[ind].pos(), &ctx.file.name);
 	}
 
 	Ok(value)
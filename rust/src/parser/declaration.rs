@@ -2,17 +2,20 @@ use std::collections::HashMap;
 
 use crate::{
 	DeclFile, DeclProvider, ParseError,
-	builtins::{ARR_TYPEID, BUILT_INS_IDS, MAP_TYPEID},
-	declaration::{DeclItem, EnumVariant, Field, StructDef, TypeId},
+	builtins::{
+		ANY_TYPEID, ARR_TYPEID, BUILT_INS_IDS, DEC_TYPEID, F32_TYPEID, F64_TYPEID,
+		FIXED_ARR_TYPEID, MAP_TYPEID, PARAM_TYPEID, SET_TYPEID, TUPLE_TYPEID,
+	},
+	declaration::{Annotations, DeclItem, EnumVariant, Field, Reserved, StructDef, TypeId},
 	errors::{ImportError, err},
 	parser::{
-		ParseOptions,
-		tokenizer::Token,
+		MiddleProvider, ParseOptions,
+		tokenizer::{Pos, Token, tokenize},
 		utils::{
 			consume_ident, consume_str, consume_symbol, consume_uint, count_prefix, end_of_input,
-			parse_struct_like, remove_n_suffix, try_consume_ident, try_consume_symbol,
-			unexpected_token,
+			parse_struct_like, remove_n_suffix, try_consume_symbol, unexpected_token,
 		},
+		value::parse_value,
 	},
 };
 
@@ -22,30 +25,191 @@ pub struct DeclContext<'a> {
 	pub file: &'a mut DeclFile,
 	pub no_ns_imports: Vec<&'a DeclFile>,
 	pub ns_imports: HashMap<&'a str, &'a DeclFile>,
+	/// selected imported items by local name, holding their file and qualified name
+	pub item_imports: HashMap<&'a str, (&'a DeclFile, String)>,
 	/// current named item id
 	pub cur_id: u16,
+	/// generic parameters of the current item
+	pub params: Vec<String>,
+	/// qualified path of the current module, empty at top level
+	pub module: String,
+	/// documentation comments of the file
+	pub docs: &'a [(usize, &'a str)],
 }
 
 impl<'a> DeclContext<'a> {
 	pub fn new(file: &'a mut DeclFile, provider: &'a dyn DeclProvider) -> Self {
-		Self { file, no_ns_imports: Vec::new(), ns_imports: HashMap::new(), cur_id: 0, provider }
+		let (no_ns_imports, ns_imports, item_imports) =
+			(Vec::new(), HashMap::new(), HashMap::new());
+		let (cur_id, params, module) = (0, Vec::new(), String::new());
+		let docs = &[];
+		Self {
+			file,
+			no_ns_imports,
+			ns_imports,
+			item_imports,
+			cur_id,
+			params,
+			module,
+			provider,
+			docs,
+		}
+	}
+
+	/// qualify a name declared in the current module
+	pub fn qualify(&self, name: &str) -> String {
+		if self.module.is_empty() { name.to_string() } else { format!("{}.{name}", self.module) }
+	}
+}
+
+/// consume a name starting with `first`, following nested modules through "."
+///
+/// the name is searched from the `scope` module outward, returns its qualified form if found.
+pub(crate) fn consume_path(
+	tokens: &[Token], ind: &mut usize, file: &DeclFile, scope: &str, first: &str,
+) -> Option<String> {
+	let mut scope = Some(scope);
+	while let Some(cur) = scope {
+		let mut name = if cur.is_empty() { first.to_string() } else { format!("{cur}.{first}") };
+		let mut end = *ind;
+		while file.has_module(&name)
+			&& let (Some(Token::Symbol('.', _)), Some(Token::Ident(segment, _))) =
+				(tokens.get(end), tokens.get(end + 1))
+		{
+			name = format!("{name}.{segment}");
+			end += 2;
+		}
+		if file.has_name(&name) {
+			*ind = end;
+			return Some(name);
+		}
+
+		// then the outer module
+		scope = (!cur.is_empty()).then(|| cur.rsplit_once('.').map_or("", |(outer, _)| outer));
+	}
+	None
+}
+
+/// consume a name visible to importers of `file`, its own items and its re-exports
+fn consume_exported<'a>(
+	tokens: &[Token], ind: &mut usize, file: &'a DeclFile, provider: &'a dyn DeclProvider,
+	first: &str,
+) -> Result<Option<(&'a DeclFile, String)>, String> {
+	if let Some(name) = consume_path(tokens, ind, file, "", first) {
+		return Ok(Some((file, name)));
+	}
+
+	let exports = &file.exports;
+	if let Some((ns, name)) = exports.items.get(first) {
+		return Ok(Some((provider.get(*ns), name.clone())));
+	}
+	// namespaced re-exports are followed by "." name
+	if let Some(ns) = exports.namespaces.get(first)
+		&& let (Some(Token::Symbol('.', _)), Some(Token::Ident(name, _))) =
+			(tokens.get(*ind), tokens.get(*ind + 1))
+	{
+		let mut end = *ind + 2;
+		let found = consume_exported(tokens, &mut end, provider.get(*ns), provider, name)?;
+		if found.is_some() {
+			*ind = end;
+		}
+		return Ok(found);
+	}
+
+	let files = exports.files.iter().map(|ns| provider.get(*ns));
+	consume_unique(tokens, ind, files, provider, first)
+}
+
+/// consume a name visible through one of `files`, errors if different items are found
+fn consume_unique<'a>(
+	tokens: &[Token], ind: &mut usize, files: impl Iterator<Item = &'a DeclFile>,
+	provider: &'a dyn DeclProvider, first: &str,
+) -> Result<Option<(&'a DeclFile, String)>, String> {
+	let mut found: Option<(&DeclFile, String, usize)> = None;
+	for file in files {
+		let mut end = *ind;
+		let Some((decl_file, name)) = consume_exported(tokens, &mut end, file, provider, first)?
+		else {
+			continue;
+		};
+		// the same item reached through several files is not ambiguous
+		if let Some((other, other_name, _)) = &found
+			&& (other.id != decl_file.id || *other_name != name)
+		{
+			let (other, decl_file) = (&other.name, &decl_file.name);
+			return Err(format!(
+				"ambiguous name \"{first}\", found in \"{other}\" and \"{decl_file}\""
+			));
+		}
+		found = Some((decl_file, name, end));
 	}
+
+	Ok(found.map(|(file, name, end)| {
+		*ind = end;
+		(file, name)
+	}))
+}
+
+/// resolve a user type name, in the current file, then the imports.
+///
+/// returns the declaring file and the qualified name of the item or alias.
+pub(crate) fn resolve_type_name<'c>(
+	tokens: &[Token], ind: &mut usize, ctx: &'c DeclContext<'_>, type_name: &str, pos: Pos,
+) -> Result<(&'c DeclFile, String), ParseError> {
+	let (file, provider) = (&ctx.file.name, ctx.provider);
+
+	if let Some(name) = consume_path(tokens, ind, &*ctx.file, &ctx.module, type_name) {
+		return Ok((&*ctx.file, name));
+	}
+
+	if let Some((decl_file, name)) = ctx.item_imports.get(type_name) {
+		return Ok((*decl_file, name.clone()));
+	}
+
+	let files = ctx.no_ns_imports.iter().map(|file| *file);
+	let found = consume_unique(tokens, ind, files, provider, type_name);
+	if let Some(found) = found.or_else(|msg| err!(msg, pos, file))? {
+		return Ok(found);
+	}
+
+	if try_consume_symbol('.', tokens, ind, file)? {
+		let ns = type_name;
+		let type_name = consume_ident(tokens, ind, file)?;
+
+		let Some(ns_file) = ctx.ns_imports.get(ns) else {
+			return err!(format!("undefined namespace \"{ns}\""), pos, file);
+		};
+		let found = consume_exported(tokens, ind, ns_file, provider, type_name);
+		let Some(found) = found.or_else(|msg| err!(msg, pos, file))? else {
+			let msg = format!("undefined type \"{type_name}\" in namespace \"{ns}\"");
+			return err!(msg, pos, file);
+		};
+		return Ok(found);
+	}
+
+	err!(format!("undefined type \"{type_name}\""), pos, file)
 }
 
 // resolve tag, also parse [tag] specifier
 fn resolve_tag(
 	tokens: &[Token], ind: &mut usize, cur_tag: &mut u64, tag_type: &str, max_tag: u64,
-	item_type: &str, item_name: &str, ctx: &mut DeclContext<'_>,
+	item_type: &str, item_name: &str, reserved: Option<&Reserved>, ctx: &mut DeclContext<'_>,
 ) -> Result<u32, ParseError> {
 	let pos = tokens[*ind].pos();
 	let file = &ctx.file.name;
-	if *cur_tag > max_tag {
+	let is_reserved = |tag: u64| reserved.is_some_and(|r| tag <= max_tag && r.has_tag(tag as u32));
+
+	// auto tags skip reserved ones
+	let mut tag = *cur_tag;
+	while is_reserved(tag) {
+		tag += 1;
+	}
+	if tag > max_tag {
 		let msg = format!(
 			"maximum number of {tag_type}s ({max_tag}) reached at {item_type} \"{item_name}\"",
 		);
 		return err!(msg, pos, file);
 	}
-	let mut tag = *cur_tag;
 
 	if try_consume_symbol('[', tokens, ind, file)? {
 		let spec_tag = consume_uint(tokens, ind, &ctx.file.name)?;
@@ -67,20 +231,49 @@ fn resolve_tag(
 		consume_symbol(']', tokens, ind, file)?;
 	}
 
+	if is_reserved(tag) {
+		let msg = format!("{tag_type} ({tag}) is reserved at {item_type} \"{item_name}\"");
+		return err!(msg, pos, file);
+	}
+
 	*cur_tag = tag + 1;
 	Ok(tag as u32)
 }
 
-/// grammer: "import" str ["as" ident]
+/// grammer: ["export"] "import" (str ["as" ident] | "{" (path ["as" ident]),* "}" "from" str)
 fn parse_import<'a>(
 	tokens: &'a [Token], ind: &mut usize, imports: &mut Vec<u64>, ctx: &mut DeclContext<'a>,
 	options: &ParseOptions,
 ) -> Result<(), ParseError> {
 	let pos = tokens[*ind].pos();
-	*ind += 1; // skip "import"
+	let is_export = matches!(tokens[*ind], Token::Ident("export", _));
+	*ind += if is_export { 2 } else { 1 }; // skip ["export"] "import"
 
 	let cur_file = &ctx.file.name;
 
+	// selected items (path start, path end, local name), resolved once the file is loaded
+	let mut selected = Vec::new();
+	let is_selective = matches!(tokens.get(*ind), Some(Token::Symbol('{', _)));
+	if is_selective {
+		parse_struct_like!((tokens, '{', '}'), cur_file, ind => {
+			let start = *ind;
+			// local name defaults to the last segment of the path
+			let mut local = consume_ident(tokens, ind, cur_file)?;
+			while try_consume_symbol('.', tokens, ind, cur_file)? {
+				local = consume_ident(tokens, ind, cur_file)?;
+			}
+			let end = *ind;
+			if let Some(Token::Ident("as", _)) = tokens.get(*ind) {
+				*ind += 1;
+				local = consume_ident(tokens, ind, cur_file)?;
+			}
+			selected.push((start, end, local));
+		});
+		if consume_ident(tokens, ind, cur_file)? != "from" {
+			return unexpected_token(&tokens[*ind - 1], tokens[*ind - 1].pos(), cur_file);
+		}
+	}
+
 	let mut path = consume_str(tokens, ind, cur_file)?;
 	// resolve path
 	let path_owner;
@@ -113,19 +306,52 @@ fn parse_import<'a>(
 	}
 	imports.push(imported.id);
 
-	if matches!(try_consume_ident(tokens, ind, cur_file)?, Some("as")) {
+	if is_selective {
+		for (start, end, local) in selected {
+			let (pos, file) = (tokens[start].pos(), &ctx.file.name);
+			let Token::Ident(first, _) = tokens[start] else { unreachable!() };
+
+			let mut path_end = start + 1;
+			let found = consume_exported(tokens, &mut path_end, imported, ctx.provider, first)
+				.or_else(|msg| err!(msg, pos, file))?;
+			let Some((item_file, name)) = found.filter(|_| path_end == end) else {
+				let msg = format!("undefined item \"{first}\" in declaration file \"{path}\"");
+				return err!(msg, pos, file);
+			};
+
+			if ctx.file.has_name(local)
+				|| ctx.file.has_module(local)
+				|| ctx.ns_imports.contains_key(local)
+				|| ctx.item_imports.contains_key(local)
+			{
+				let msg = format!("importing \"{local}\" with name already in use");
+				return err!(msg, pos, file);
+			}
+			if is_export {
+				ctx.file.exports.items.insert(local.to_string(), (item_file.id, name.clone()));
+			}
+			ctx.item_imports.insert(local, (item_file, name));
+		}
+	} else if let Some(Token::Ident("as", _)) = tokens.get(*ind) {
+		*ind += 1;
 		let ns = consume_ident(tokens, ind, cur_file)?;
 		if ctx.ns_imports.contains_key(ns) {
 			let msg = format!("importing \"{path}\" into used namespace \"{ns}\"");
 			return err!(msg, pos, cur_file);
 		}
-		if ctx.file.get_by_name(ns).is_some() {
+		if ctx.file.has_name(ns) || ctx.item_imports.contains_key(ns) {
 			let msg =
 				format!("importing \"{path}\" into namespace named like existing item \"{ns}\"");
 			return err!(msg, pos, cur_file);
 		}
+		if is_export {
+			ctx.file.exports.namespaces.insert(ns.to_string(), imported.id);
+		}
 		ctx.ns_imports.insert(ns, imported);
 	} else {
+		if is_export {
+			ctx.file.exports.files.push(imported.id);
+		}
 		ctx.no_ns_imports.push(imported)
 	}
 
@@ -139,20 +365,24 @@ fn parse_anonymous_item(
 ) -> Result<TypeId, ParseError> {
 	let typeid = ctx.cur_id;
 	ctx.cur_id += 1;
+	// anonymous items are not generic, and cant use the parent parameters
+	let params = std::mem::take(&mut ctx.params);
 	match consume_ident(tokens, ind, &ctx.file.name)? {
 		"struct" => {
-			let name = format!("anonymous_struct_{typeid:x}");
+			let name = ctx.qualify(&format!("anonymous_struct_{typeid:x}"));
 			let def = parse_fields(tokens, ind, &name, ctx, options)?;
-			ctx.file.add_item(DeclItem::Struct { name, typeid, def });
+			let (params, annotations) = (vec![], Annotations::default());
+			ctx.file.add_item(DeclItem::Struct { name, typeid, params, def, annotations });
 		}
 		"enum" => {
-			let name = format!("anonymous_enum_{typeid:x}");
-			let mut decl = DeclItem::new_enum(name, typeid);
+			let name = ctx.qualify(&format!("anonymous_enum_{typeid:x}"));
+			let mut decl = DeclItem::new_enum(name, typeid, vec![]);
 			parse_enum_body(tokens, ind, &mut decl, ctx, options)?;
 			_ = ctx.file.add_item(decl);
 		}
 		_ => unreachable!(),
 	}
+	ctx.params = params;
 	return Ok(TypeId::new(ctx.file.id, typeid, metadata));
 }
 
@@ -187,28 +417,235 @@ pub fn parse_metadata(
 	Ok(metadata)
 }
 
+/// grammer: (doc_comment | "@" ident "(" str ")")*
+///
+/// `@deprecated` holds the deprecation reason, other metadata are kept regardless of options.
+///
+/// doc comments are taken from the ones preceding the consumed tokens, others are ignored.
+fn parse_annotations(
+	tokens: &[Token], ind: &mut usize, ctx: &DeclContext<'_>,
+) -> Result<Annotations, ParseError> {
+	let file = &ctx.file.name;
+	let mut annotations = Annotations::default();
+	loop {
+		let start = ctx.docs.partition_point(|(at, _)| *at < *ind);
+		for (_, line) in ctx.docs[start..].iter().take_while(|(at, _)| *at == *ind) {
+			match &mut annotations.doc {
+				Some(doc) => (doc.push('\n'), doc.push_str(line)).0,
+				None => annotations.doc = Some(line.to_string()),
+			}
+		}
+
+		match tokens.get(*ind) {
+			Some(Token::Symbol('@', pos)) => {
+				*ind += 1;
+				let name = consume_ident(tokens, ind, file)?;
+				consume_symbol('(', tokens, ind, file)?;
+				let value = consume_str(tokens, ind, file)?.to_string();
+				consume_symbol(')', tokens, ind, file)?;
+
+				let metadata = annotations.metadata.as_deref().unwrap_or_default();
+				let is_declared = match name {
+					"deprecated" => annotations.deprecated.is_some(),
+					_ => metadata.iter().any(|(n, _)| name == n),
+				};
+				if is_declared {
+					return err!(
+						format!("declaring a metadata \"{name}\" multiple times"),
+						*pos,
+						file
+					);
+				}
+
+				match name {
+					"deprecated" => annotations.deprecated = Some(value),
+					_ => {
+						annotations.metadata.get_or_insert_default().push((name.to_string(), value))
+					}
+				}
+			}
+			_ => return Ok(annotations),
+		}
+	}
+}
+
+/// whether a root statement is next, values of an item named "root" are not
+fn is_root_statement(tokens: &[Token], ind: usize) -> bool {
+	matches!(tokens.get(ind), Some(Token::Ident("root", _)))
+		&& matches!(tokens.get(ind + 1), Some(Token::Ident(..) | Token::Symbol('@', _)))
+}
+
+/// grammer: "root" typeid
+fn parse_root(
+	tokens: &[Token], ind: &mut usize, ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<(), ParseError> {
+	let pos = tokens[*ind].pos();
+	*ind += 1; // skip "root"
+
+	if ctx.file.root.is_some() {
+		return err!("declaring root type multiple times".to_string(), pos, &ctx.file.name);
+	}
+
+	// only non generic items have a standalone binary representation
+	let typeid = parse_typeid(tokens, ind, ctx, options)?;
+	if typeid.ns == 0 || !typeid.args.is_empty() {
+		let msg = "root type must be a non generic struct, enum or flags".to_string();
+		return err!(msg, pos, &ctx.file.name);
+	}
+
+	ctx.file.root = Some(typeid);
+	Ok(())
+}
+
+/// root type declared by the imported files, they must agree on it
+pub(crate) fn imported_root<'c>(
+	ctx: &'c DeclContext<'_>,
+) -> Result<Option<&'c TypeId>, ParseError> {
+	let mut root: Option<&TypeId> = None;
+	for file in ctx.no_ns_imports.iter().chain(ctx.ns_imports.values()) {
+		let Some(file_root) = &file.root else { continue };
+		if root.is_some_and(|root| root != file_root) {
+			let msg = "imported files declare different root types, declare one explicitly";
+			return err!(msg.to_string(), &ctx.file.name);
+		}
+		root = Some(file_root);
+	}
+	Ok(root)
+}
+
+/// whether a reserved statement is next, "reserved" can still name a field
+fn is_reserved_statement(tokens: &[Token], ind: usize) -> bool {
+	matches!(tokens.get(ind), Some(Token::Ident("reserved", _)))
+		&& matches!(tokens.get(ind + 1), Some(Token::Uint(..) | Token::Str(..)))
+}
+
+/// grammer: "reserved" reserve ("," reserve)* ";"
+/// reserve: uint [".." uint] | str
+///
+/// ranges are inclusive, `used` holds the tags and names already taken.
+fn parse_reserved(
+	tokens: &[Token], ind: &mut usize, reserved: &mut Reserved, used: &[(u32, String)], item: &str,
+	file: &str,
+) -> Result<(), ParseError> {
+	*ind += 1; // skip "reserved"
+
+	loop {
+		let pos = tokens[*ind].pos();
+		if let Some(Token::Str(name, _)) = tokens.get(*ind) {
+			*ind += 1;
+			if used.iter().any(|(_, used)| used == name) {
+				return err!(format!("reserving used name \"{name}\" at \"{item}\""), pos, file);
+			}
+			reserved.names.push(name.to_string());
+		} else {
+			let start = consume_uint(tokens, ind, file)?;
+			let mut end = start;
+			if try_consume_symbol('.', tokens, ind, file)? {
+				consume_symbol('.', tokens, ind, file)?;
+				end = consume_uint(tokens, ind, file)?;
+			}
+
+			if start > end || end > u32::MAX as u64 {
+				let msg = format!("invalid reserved range ({start}..{end}) at \"{item}\"");
+				return err!(msg, pos, file);
+			}
+			if let Some((tag, name)) =
+				used.iter().find(|(tag, _)| (start..=end).contains(&(*tag as u64)))
+			{
+				let msg = format!("reserving tag ({tag}) used by \"{name}\" at \"{item}\"");
+				return err!(msg, pos, file);
+			}
+			reserved.tags.push((start as u32, end as u32));
+		}
+
+		if !try_consume_symbol(',', tokens, ind, file)? {
+			break;
+		}
+	}
+
+	consume_symbol(';', tokens, ind, file)?;
+	Ok(())
+}
+
 // macro since it depend on a specific form of parse_typeid
 // decleration take mut ctx since it add inline structs, while value not
 macro_rules! parse_typeid_general {
 	($args:expr) => {{
 		let (tokens, ind, metadata, ctx, options) = $args;
 
+		let pos = tokens[*ind].pos();
+
+		// tuple
+		if let Some(Token::Symbol('(', _)) = tokens.get(*ind) {
+			let mut items = Vec::new();
+			parse_struct_like!((tokens, '(', ')'), &ctx.file.name, ind => {
+				items.push(parse_typeid(tokens, ind, ctx, options)?);
+			});
+			if items.is_empty() {
+				return err!("tuple must have at least one item".to_string(), pos, &ctx.file.name);
+			}
+			return Ok(TypeId::tuple(items, metadata));
+		}
+
 		let file = &ctx.file.name;
 		let provider = ctx.provider;
-		let pos = tokens[*ind].pos();
 		let type_name = consume_ident(tokens, ind, file)?;
 
+		// generic parameters of the current item
+		if let Some(index) = ctx.params.iter().position(|param| param == type_name) {
+			return Ok(TypeId::param(index as u16, metadata));
+		}
+
 		if type_name == "arr" {
 			consume_symbol('<', tokens, ind, file)?;
 			let itemid = parse_typeid(tokens, ind, ctx, options)?;
+
+			// fixed length
+			if try_consume_symbol(',', tokens, ind, &ctx.file.name)? {
+				let len_pos = tokens[*ind].pos();
+				let len = consume_uint(tokens, ind, &ctx.file.name)?;
+				if len == 0 || len > u16::MAX as u64 {
+					let msg = format!("array length must be between 1 and {}, got {len}", u16::MAX);
+					return err!(msg, len_pos, &ctx.file.name);
+				}
+				consume_symbol('>', tokens, ind, &ctx.file.name)?;
+				let (len, itemid) = (len as u16, Some(itemid));
+				return Ok(TypeId::with_variant(0, FIXED_ARR_TYPEID, len, itemid, metadata));
+			}
+
 			consume_symbol('>', tokens, ind, &ctx.file.name)?;
 			return Ok(TypeId::with_variant(0, ARR_TYPEID, 0, Some(itemid), metadata));
 		}
 
+		if type_name == "set" {
+			consume_symbol('<', tokens, ind, file)?;
+			let itemid = parse_typeid(tokens, ind, ctx, options)?;
+			// items must be hashable
+			let is_hashable = itemid.is_builtin()
+				&& !matches!(
+					itemid.id,
+					ANY_TYPEID | ARR_TYPEID | MAP_TYPEID | SET_TYPEID | TUPLE_TYPEID
+						| FIXED_ARR_TYPEID | F32_TYPEID | F64_TYPEID | DEC_TYPEID | PARAM_TYPEID
+				);
+			if !is_hashable {
+				let item = itemid.name(provider);
+				let msg = format!("set item must be a hashable primitive, got: {item}");
+				return err!(msg, pos, &ctx.file.name);
+			}
+			consume_symbol('>', tokens, ind, &ctx.file.name)?;
+			return Ok(TypeId::with_variant(0, SET_TYPEID, 0, Some(itemid), metadata));
+		}
+
 		if type_name == "map" {
 			consume_symbol('<', tokens, ind, file)?;
 			let keyid = parse_typeid(tokens, ind, ctx, options)?;
-			if (!keyid.is_builtin() || keyid.id == ARR_TYPEID || keyid.id == MAP_TYPEID) {
+			if (!keyid.is_builtin()
+				|| matches!(
+					keyid.id,
+					ARR_TYPEID | MAP_TYPEID | SET_TYPEID | TUPLE_TYPEID | FIXED_ARR_TYPEID | DEC_TYPEID
+						| PARAM_TYPEID
+				))
+			{
 				let msg = format!("map key must be primitive, got: {}", keyid.name(provider));
 				return err!(msg, pos, &ctx.file.name);
 			}
@@ -224,31 +661,37 @@ macro_rules! parse_typeid_general {
 			return Ok(TypeId::new(0, *id, metadata));
 		}
 
-		if let Some(item) = ctx.file.get_by_name(type_name) {
-			return Ok(TypeId::new(ctx.file.id, item.typeid(), metadata));
-		}
+		// user defined items and aliases, possibly inside modules
+		let (decl_file, type_name) = resolve_type_name(tokens, ind, ctx, type_name, pos)?;
 
-		for file in ctx.no_ns_imports.iter() {
-			if let Some(item) = file.get_by_name(type_name) {
-				return Ok(TypeId::new(file.id, item.typeid(), metadata));
-			}
+		// aliases are replaced by their type, metadata at usage are added over theirs
+		if let Some(alias) = decl_file.get_alias(&type_name) {
+			return Ok(alias.with_metadata(metadata));
 		}
-
-		if try_consume_symbol('.', tokens, ind, file)? {
-			let ns = type_name;
-			let type_name = consume_ident(tokens, ind, file)?;
-
-			let Some(ns_file) = ctx.ns_imports.get(ns) else {
-				return err!(format!("undefined namespace \"{ns}\""), pos, file);
-			};
-			let Some(item) = ns_file.get_by_name(type_name) else {
-				let msg = format!("undefined type \"{type_name}\" in namespace \"{ns}\"");
-				return err!(msg, pos, file);
-			};
-			return Ok(TypeId::new(ns_file.id, item.typeid(), metadata));
+		let item = decl_file.get_by_name(&type_name).unwrap();
+		let mut typeid = TypeId::new(decl_file.id, item.typeid(), metadata);
+
+		// generic items require their arguments
+		let params_count = item.params().len();
+		if params_count != 0 {
+			let name = item.name().to_string();
+			if !matches!(tokens.get(*ind), Some(Token::Symbol('<', _))) {
+				let msg = format!("generic type \"{name}\" requires {params_count} type arguments");
+				return err!(msg, pos, &ctx.file.name);
+			}
+			parse_struct_like!((tokens, '<', '>'), &ctx.file.name, ind => {
+				typeid.args.push(parse_typeid(tokens, ind, ctx, options)?);
+			});
+			if typeid.args.len() != params_count {
+				let msg = format!(
+					"generic type \"{name}\" requires {params_count} type arguments, got {}",
+					typeid.args.len()
+				);
+				return err!(msg, pos, &ctx.file.name);
+			}
 		}
 
-		return err!(format!("undefined type \"{type_name}\""), pos, file);
+		return Ok(typeid);
 	}};
 }
 pub(crate) use parse_typeid_general;
@@ -266,8 +709,140 @@ fn parse_typeid(
 	parse_typeid_general!((tokens, ind, metadata, ctx, options))
 }
 
-/// grammer: "{" (field_def ",")* "}
-/// field_def: [tag] (ident | str) ["?"] ":" type_id
+/// parse a standalone type expression, used by [`DeclFileBuilder`](crate::DeclFileBuilder)
+pub(crate) fn parse_type_expr(
+	source: &str, ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<TypeId, ParseError> {
+	let tokens = tokenize(source, &ctx.file.name, None)?;
+	let mut ind = 0;
+
+	let typeid = parse_typeid(&tokens, &mut ind, ctx, options)?;
+	if !matches!(tokens[ind], Token::EOF(_)) {
+		return unexpected_token(&tokens[ind], tokens[ind].pos(), &ctx.file.name);
+	}
+	Ok(typeid)
+}
+
+/// grammer: annotations [tag] (ident | str) ["?"] ":" type_id ["=" value]
+fn parse_field(
+	tokens: &[Token], ind: &mut usize, def: &mut StructDef, cur_tag: &mut u64, item: &str,
+	ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<(), ParseError> {
+	let annotations = parse_annotations(tokens, ind, ctx)?;
+	let pos = tokens[*ind].pos();
+
+	// named like that to prevent an arguments tower
+	const MTAG: u64 = u32::MAX as u64;
+	let reserved = Some(&def.reserved);
+	let tag = resolve_tag(tokens, ind, cur_tag, "field tag", MTAG, "struct", item, reserved, ctx)?;
+
+	let file = &ctx.file.name;
+
+	let name = match tokens.get(*ind) {
+		Some(Token::Ident(ident, _)) => ident.to_string(),
+		Some(Token::Str(str, _)) => str.to_string(),
+		Some(Token::EOF(_)) | None => return end_of_input(file),
+		Some(token) => return unexpected_token(token, token.pos(), file),
+	};
+	*ind += 1;
+
+	if def.get_field_by_name(&name).is_some() {
+		let msg = format!("declaring a field \"{name}\" mutliple times at struct \"{item}\"");
+		return err!(msg, pos, file);
+	}
+	if def.reserved.has_name(&name) {
+		return err!(format!("field \"{name}\" is reserved at struct \"{item}\""), pos, file);
+	}
+
+	let is_optional = try_consume_symbol('?', tokens, ind, file)?;
+	consume_symbol(':', tokens, ind, file)?;
+
+	let typeid = parse_typeid(tokens, ind, ctx, options)?;
+
+	// default value
+	let mut default = None;
+	if try_consume_symbol('=', tokens, ind, &ctx.file.name)? {
+		if typeid.is_any() || typeid.has_params() {
+			let msg = format!("field \"{name}\" at struct \"{item}\" can not have a default");
+			return err!(msg, pos, &ctx.file.name);
+		}
+		let provider = MiddleProvider { provider: ctx.provider, ctx };
+		default = Some(parse_value(tokens, ind, &typeid, ctx, &provider, options)?);
+	}
+
+	if def
+		.add_field(Field { name, tag, typeid, is_optional, default, annotations })
+		.is_err()
+	{
+		let msg =
+			format!("field tag ({tag}) is lower than a field declared before at struct \"{item}\"");
+		return err!(msg, pos, &ctx.file.name);
+	}
+	Ok(())
+}
+
+/// grammer: ".." type_id
+fn parse_spread(
+	tokens: &[Token], ind: &mut usize, def: &mut StructDef, cur_tag: &mut u64, item: &str,
+	ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<(), ParseError> {
+	let pos = tokens[*ind].pos();
+	consume_symbol('.', tokens, ind, &ctx.file.name)?;
+	consume_symbol('.', tokens, ind, &ctx.file.name)?;
+
+	let base = parse_typeid(tokens, ind, ctx, options)?;
+	let file = &ctx.file.name;
+
+	// only structs can be spread
+	let base_item = match base.ns {
+		0 => None,
+		ns if ns == ctx.file.id => ctx.file.get_by_id(base.id),
+		ns => ctx.provider.get(ns).get_by_id(base.id),
+	};
+	let Some(DeclItem::Struct { name: base_name, def: base_def, .. }) = base_item else {
+		let msg = format!("spreading non struct type at struct \"{item}\"");
+		return err!(msg, pos, file);
+	};
+
+	// inherited fields keep their tags
+	for field in base_def.fields.iter().filter_map(|f| f.as_ref()) {
+		let Field { name, tag, .. } = field;
+		if def.get_field_by_name(name).is_some() {
+			let msg = format!(
+				"field \"{name}\" of struct \"{base_name}\" collides with a field at struct \"{item}\""
+			);
+			return err!(msg, pos, file);
+		}
+		if def.get_field_by_id(*tag).is_some() {
+			let msg = format!(
+				"field tag ({tag}) of struct \"{base_name}\" collides with a field at struct \"{item}\""
+			);
+			return err!(msg, pos, file);
+		}
+		if def.reserved.has_name(name) || def.reserved.has_tag(*tag) {
+			let msg = format!(
+				"field \"{name}\" of struct \"{base_name}\" is reserved at struct \"{item}\""
+			);
+			return err!(msg, pos, file);
+		}
+
+		// generic bases take their arguments
+		let typeid = field.typeid.substitute(&base.args).into_owned();
+		// fields are stored in tag order, tags below the declared fields can not be inserted
+		if def.add_field(Field { typeid, ..field.clone() }).is_err() {
+			let msg = format!(
+				"field tag ({tag}) of struct \"{base_name}\" is lower than a field declared before at struct \"{item}\""
+			);
+			return err!(msg, pos, file);
+		}
+		*cur_tag = (*cur_tag).max(*tag as u64 + 1);
+	}
+
+	def.bases.push(base);
+	Ok(())
+}
+
+/// grammer: "{" ((field_def | spread) "," | reserved)* "}
 fn parse_fields(
 	tokens: &[Token], ind: &mut usize, item: &str, ctx: &mut DeclContext<'_>,
 	options: &ParseOptions,
@@ -277,138 +852,315 @@ fn parse_fields(
 	let mut cur_tag = 0;
 
 	parse_struct_like!((tokens, '{', '}'), &ctx.file.name, ind => {
-		let pos = tokens[*ind].pos();
+		if let Some(Token::Symbol('.', _)) = tokens.get(*ind) {
+			parse_spread(tokens, ind, &mut def, &mut cur_tag, item, ctx, options)?;
+		} else if is_reserved_statement(tokens, *ind) {
+			let used = def.fields.iter().filter_map(|f| f.as_ref());
+			let used = used.map(|f| (f.tag, f.name.clone())).collect::<Vec<_>>();
+			parse_reserved(tokens, ind, &mut def.reserved, &used, item, &ctx.file.name)?;
+		} else {
+			parse_field(tokens, ind, &mut def, &mut cur_tag, item, ctx, options)?;
+		}
+	});
+
+	if def.fields.is_empty() {
+		let msg = format!("struct \"{item}\" must have at least one field");
+		return err!(msg, start_pos, &ctx.file.name);
+	}
 
-		// named like that to prevent an arguments tower
-		const MTAG: u64 = u32::MAX as u64;
-		let tag = resolve_tag(tokens, ind, &mut cur_tag, "field tag", MTAG, "struct", item, ctx)?;
+	return Ok(def);
+}
 
-		let file = &ctx.file.name;
+/// grammer: annotations [tag] ident [struct_def]
+fn parse_variant(
+	tokens: &[Token], ind: &mut usize, decl: &mut DeclItem, cur_tag: &mut u64,
+	ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<(), ParseError> {
+	let annotations = parse_annotations(tokens, ind, ctx)?;
+	let name = decl.name();
+	let pos = tokens[*ind].pos();
 
-		let name = match tokens.get(*ind) {
-			Some(Token::Ident(ident, _)) => ident.to_string(),
-			Some(Token::Str(str, _)) => str.to_string(),
-			Some(Token::EOF(_)) | None => return end_of_input(file),
-			Some(token) => return unexpected_token(token, token.pos(), file),
-		};
-		*ind += 1;
+	// named like that to prevent an arguments tower
+	const MTAG: u64 = u32::MAX as u64;
+	let reserved = decl.reserved();
+	let tag = resolve_tag(tokens, ind, cur_tag, "variant tag", MTAG, "enum", name, reserved, ctx)?;
 
-		if def.get_field_by_name(&name).is_some() {
-			let msg = format!("declaring a field \"{name}\" mutliple times at struct \"{item}\"");
-			return err!(msg, pos, file);
+	let file = &ctx.file.name;
+
+	let variant = consume_ident(tokens, ind, file)?;
+	if decl.get_variant_by_name(variant).is_some() {
+		let msg = format!("declaring variant \"{variant}\" mutliple times at enum \"{name}\"");
+		return err!(msg, pos, file);
+	}
+	if reserved.is_some_and(|r| r.has_name(variant)) {
+		return err!(format!("variant \"{variant}\" is reserved at enum \"{name}\""), pos, file);
+	}
+
+	let field_def = match tokens.get(*ind) {
+		Some(Token::Symbol('{', _)) => {
+			Some(parse_fields(tokens, ind, &format!("{name}.{variant}"), ctx, options)?)
 		}
+		_ => None,
+	};
 
-		let is_optional = try_consume_symbol('?', tokens, ind, file)?;
-		consume_symbol(':', tokens, ind, file)?;
+	let variant = EnumVariant { name: variant.to_string(), tag, def: field_def, annotations };
+	_ = decl.add_variant(variant);
+	Ok(())
+}
 
-		let typeid = parse_typeid(tokens, ind, ctx, options)?;
+/// grammer: "{" (variant "," | reserved)* "}
+fn parse_enum_body(
+	tokens: &[Token], ind: &mut usize, decl: &mut DeclItem, ctx: &mut DeclContext<'_>,
+	options: &ParseOptions,
+) -> Result<(), ParseError> {
+	let start_pos = tokens[*ind].pos();
+	let mut cur_tag = 0;
 
-		def.add_field(Field { name, tag, typeid, is_optional });
+	parse_struct_like!((tokens, '{', '}'), &ctx.file.name, ind => {
+		if is_reserved_statement(tokens, *ind) {
+			let DeclItem::Enum { name, variants, reserved, .. } = &mut *decl else {
+				unreachable!()
+			};
+			let used = variants.iter().filter_map(|v| v.as_ref());
+			let used = used.map(|v| (v.tag, v.name.clone())).collect::<Vec<_>>();
+			parse_reserved(tokens, ind, reserved, &used, name, &ctx.file.name)?;
+		} else {
+			parse_variant(tokens, ind, decl, &mut cur_tag, ctx, options)?;
+		}
 	});
 
-	if def.fields.is_empty() {
-		let msg = format!("struct \"{item}\" must have at least one field");
+	// ensure at least one variant
+	if let DeclItem::Enum { variants, .. } = &decl
+		&& variants.is_empty()
+	{
+		let msg = format!("enum \"{}\" must have at least one variant", decl.name());
 		return err!(msg, start_pos, &ctx.file.name);
 	}
 
-	return Ok(def);
+	Ok(())
 }
 
-/// grammer: "{" (variant ",")* "}
-/// variant: [tag] ident [struct_def]
-fn parse_enum_body(
+/// grammer: "{" ([tag] ident ",")* "}"
+fn parse_flags_body(
 	tokens: &[Token], ind: &mut usize, decl: &mut DeclItem, ctx: &mut DeclContext<'_>,
-	options: &ParseOptions,
 ) -> Result<(), ParseError> {
 	let start_pos = tokens[*ind].pos();
-	let mut cur_tag = 0;
+	let mut cur_bit = 0;
 
 	parse_struct_like!((tokens, '{', '}'), &ctx.file.name, ind => {
 		let name = decl.name();
 		let pos = tokens[*ind].pos();
 
-		// named like that to prevent an arguments tower
-		const MTAG: u64 = u32::MAX as u64;
-		let tag = resolve_tag(tokens, ind, &mut cur_tag, "variant tag", MTAG, "enum", name, ctx)?;
+		// flags are bits of a u64 mask
+		let bit = resolve_tag(tokens, ind, &mut cur_bit, "flag bit", 63, "flags", name, None, ctx)?;
 
 		let file = &ctx.file.name;
 
-		let variant = consume_ident(tokens, ind, file)?;
-		if decl.get_variant_by_name(variant).is_some() {
-			let msg = format!("declaring variant \"{variant}\" mutliple times at enum \"{name}\"");
+		let flag = consume_ident(tokens, ind, file)?;
+		if decl.get_flag_by_name(flag).is_some() {
+			let msg = format!("declaring flag \"{flag}\" mutliple times at flags \"{name}\"");
 			return err!(msg, pos, file);
 		}
 
-		let field_def = match tokens.get(*ind) {
-			Some(Token::Symbol('{', _)) => {
-				Some(parse_fields(tokens, ind, &format!("{name}.{variant}"), ctx, options)?)
-			}
-			_ => None,
-		};
-
-		decl.add_variant(EnumVariant { name: variant.to_string(), tag, def: field_def });
+		_ = decl.add_flag(flag.to_string(), bit);
 	});
 
-	// ensure at least one variant
-	if let DeclItem::Enum { variants, .. } = &decl
-		&& variants.is_empty()
-	{
-		let msg = format!("enum \"{}\" must have at least one variant", decl.name());
+	// ensure at least one flag
+	if decl.flags_mask() == 0 {
+		let msg = format!("flags \"{}\" must have at least one flag", decl.name());
 		return err!(msg, start_pos, &ctx.file.name);
 	}
 
 	Ok(())
 }
 
-/// reolve item tag and name collisions
-fn parse_item_common<'a>(
-	tokens: &'a [Token], ind: &mut usize, ctx: &mut DeclContext<'a>,
-) -> Result<(&'a str, u16), ParseError> {
-	*ind += 1; // skip struct / enum
+/// grammer: ["<" ident ("," ident)* ">"]
+fn parse_generic_params(
+	tokens: &[Token], ind: &mut usize, item: &str, ctx: &mut DeclContext<'_>,
+) -> Result<Vec<String>, ParseError> {
+	let mut params = Vec::<String>::new();
+	if !matches!(tokens.get(*ind), Some(Token::Symbol('<', _))) {
+		return Ok(params);
+	}
+
+	let file = &ctx.file.name;
+	parse_struct_like!((tokens, '<', '>'), file, ind => {
+		let pos = tokens[*ind].pos();
+		let param = consume_ident(tokens, ind, file)?;
+		if params.iter().any(|p| p == param) {
+			let msg = format!("declaring parameter \"{param}\" mutliple times at \"{item}\"");
+			return err!(msg, pos, file);
+		}
+		params.push(param.to_string());
+	});
 
+	Ok(params)
+}
+
+/// parse item name, and check for name collisions, returns the qualified name
+fn parse_item_name(
+	tokens: &[Token], ind: &mut usize, ctx: &DeclContext<'_>,
+) -> Result<String, ParseError> {
 	let pos = tokens[*ind].pos();
 	let file_name = &ctx.file.name;
 
 	let name = consume_ident(tokens, ind, file_name)?;
-	if ctx.file.get_by_name(name).is_some() {
-		return err!(format!("declaring item \"{name}\" mutliple times"), pos, file_name);
+	let qualified = ctx.qualify(name);
+	if ctx.file.has_name(&qualified) || ctx.file.has_module(&qualified) {
+		return err!(format!("declaring item \"{qualified}\" mutliple times"), pos, file_name);
 	}
 	if ctx.ns_imports.contains_key(name) {
 		let msg = format!("declaring item \"{name}\" with name similar to existing namespace");
 		return err!(msg, pos, file_name);
 	}
+	if ctx.item_imports.contains_key(name) {
+		let msg = format!("declaring item \"{name}\" with name similar to imported item");
+		return err!(msg, pos, file_name);
+	}
+
+	Ok(qualified)
+}
+
+/// reolve item tag and name collisions, and generic parameters
+fn parse_item_common(
+	tokens: &[Token], ind: &mut usize, ctx: &mut DeclContext<'_>,
+) -> Result<(String, u16), ParseError> {
+	*ind += 1; // skip struct / enum / flags
+
+	let name = parse_item_name(tokens, ind, ctx)?;
+
+	ctx.params = parse_generic_params(tokens, ind, &name, ctx)?;
 
 	let mut cur_id = ctx.cur_id as u64;
-	let id = resolve_tag(tokens, ind, &mut cur_id, "item id", u16::MAX as u64, "item", name, ctx)?;
+	let max_id = u16::MAX as u64;
+	let id = resolve_tag(tokens, ind, &mut cur_id, "item id", max_id, "item", &name, None, ctx)?;
 	ctx.cur_id = cur_id as u16;
 
 	Ok((name, id as u16))
 }
 
-pub fn parse_declarations<'a>(
-	file: &'a mut DeclFile, tokens: &'a [Token], ind: &mut usize, provider: &'a dyn DeclProvider,
+/// grammer: "type" ident "=" type_id
+fn parse_alias(
+	tokens: &[Token], ind: &mut usize, ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<(), ParseError> {
+	*ind += 1; // skip "type"
+
+	let name = parse_item_name(tokens, ind, ctx)?;
+	consume_symbol('=', tokens, ind, &ctx.file.name)?;
+	let typeid = parse_typeid(tokens, ind, ctx, options)?;
+
+	ctx.file.add_alias(name, typeid);
+	Ok(())
+}
+
+/// grammer: "mod" ident "{" (annotations item)* "}"
+fn parse_module(
+	tokens: &[Token], ind: &mut usize, ctx: &mut DeclContext<'_>, options: &ParseOptions,
+) -> Result<(), ParseError> {
+	*ind += 1; // skip "mod"
+
+	let name = parse_item_name(tokens, ind, ctx)?;
+	ctx.file.add_module(name.clone());
+	let parent = std::mem::replace(&mut ctx.module, name);
+
+	consume_symbol('{', tokens, ind, &ctx.file.name)?;
+	while !try_consume_symbol('}', tokens, ind, &ctx.file.name)? {
+		let annotations = parse_annotations(tokens, ind, ctx)?;
+		if !parse_item(tokens, ind, annotations, ctx, options)? {
+			return unexpected_token(&tokens[*ind], tokens[*ind].pos(), &ctx.file.name);
+		}
+	}
+
+	ctx.module = parent;
+	Ok(())
+}
+
+/// grammer: alias | module | "struct" struct_def | "enum" enum_def | "flags" flags_def
+///
+/// returns false if no item is next.
+fn parse_item(
+	tokens: &[Token], ind: &mut usize, annotations: Annotations, ctx: &mut DeclContext<'_>,
 	options: &ParseOptions,
+) -> Result<bool, ParseError> {
+	let Some(Token::Ident(ident, pos)) = tokens.get(*ind) else {
+		return Ok(false);
+	};
+
+	match *ident {
+		"type" | "mod" if !annotations.is_empty() => {
+			let msg = "only structs, enums and flags can be annotated".to_string();
+			return err!(msg, *pos, &ctx.file.name);
+		}
+		"type" => parse_alias(tokens, ind, ctx, options)?,
+		"mod" => parse_module(tokens, ind, ctx, options)?,
+		"struct" => {
+			let (name, id) = parse_item_common(tokens, ind, ctx)?;
+			let def = parse_fields(tokens, ind, &name, ctx, options)?;
+			let params = std::mem::take(&mut ctx.params);
+			let decl = DeclItem::Struct { name, typeid: id, params, def, annotations };
+			_ = ctx.file.add_item(decl);
+		}
+		"enum" => {
+			let (name, id) = parse_item_common(tokens, ind, ctx)?;
+			let mut decl = DeclItem::new_enum(name, id, ctx.params.clone());
+			parse_enum_body(tokens, ind, &mut decl, ctx, options)?;
+			ctx.params.clear();
+			decl.set_annotations(annotations);
+			_ = ctx.file.add_item(decl);
+		}
+		"flags" => {
+			let (name, id) = parse_item_common(tokens, ind, ctx)?;
+			if !ctx.params.is_empty() {
+				let msg = format!("flags \"{name}\" can not have generic parameters");
+				return err!(msg, *pos, &ctx.file.name);
+			}
+			let mut decl = DeclItem::new_flags(name, id);
+			parse_flags_body(tokens, ind, &mut decl, ctx)?;
+			decl.set_annotations(annotations);
+			_ = ctx.file.add_item(decl);
+		}
+		_ => return Ok(false),
+	}
+	Ok(true)
+}
+
+pub fn parse_declarations<'a>(
+	file: &'a mut DeclFile, tokens: &'a [Token], docs: &'a [(usize, &'a str)], ind: &mut usize,
+	provider: &'a dyn DeclProvider, options: &ParseOptions,
 ) -> Result<DeclContext<'a>, ParseError> {
 	let mut ctx = DeclContext::new(file, provider);
+	ctx.docs = docs;
 	let mut imports = Vec::<u64>::new();
 
-	while let Some(Token::Ident(ident, _)) = tokens.get(*ind) {
-		match *ident {
-			"import" => parse_import(tokens, ind, &mut imports, &mut ctx, options)?,
-			"struct" => {
-				let (name, id) = parse_item_common(tokens, ind, &mut ctx)?;
-				let def = parse_fields(tokens, ind, name, &mut ctx, options)?;
-				_ = ctx.file.add_item(DeclItem::Struct { name: name.to_string(), typeid: id, def });
-			}
-			"enum" => {
-				let (name, id) = parse_item_common(tokens, ind, &mut ctx)?;
-				let mut decl = DeclItem::new_enum(name.to_string(), id);
-				parse_enum_body(tokens, ind, &mut decl, &mut ctx, options)?;
-				_ = ctx.file.add_item(decl);
-			}
-			_ => break,
+	loop {
+		// the root value can start with metadata too, so they are consumed only before items
+		let start = *ind;
+		let annotations = parse_annotations(tokens, ind, &ctx)?;
+
+		let is_import = matches!(
+			(tokens.get(*ind), tokens.get(*ind + 1)),
+			(Some(Token::Ident("import", _)), _)
+				| (Some(Token::Ident("export", _)), Some(Token::Ident("import", _)))
+		);
+		let is_root = is_root_statement(tokens, *ind);
+		// doc comments before imports and root are normal comments
+		if (is_import || is_root)
+			&& (annotations.metadata.is_some() || annotations.deprecated.is_some())
+		{
+			let msg = "only structs, enums and flags can have metadata".to_string();
+			return err!(msg, tokens[*ind].pos(), &ctx.file.name);
+		}
+
+		if is_import {
+			parse_import(tokens, ind, &mut imports, &mut ctx, options)?;
+		} else if is_root {
+			parse_root(tokens, ind, &mut ctx, options)?;
+		} else if !parse_item(tokens, ind, annotations, &mut ctx, options)? {
+			*ind = start;
+			break;
 		}
 	}
 
+	ctx.file.imports = imports;
 	Ok(ctx)
 }
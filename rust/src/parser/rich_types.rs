@@ -1,4 +1,4 @@
-use chrono::{DateTime, TimeDelta, Timelike};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Timelike};
 
 use crate::{
 	ParseError, Value,
@@ -67,6 +67,31 @@ pub fn parse_inst(
 
 	Ok(Value::Inst(inst.with_timezone(&chrono::Utc)))
 }
+/// like inst, but the offset is kept
+pub fn parse_instz(source: &str, pos: Pos, file: &str) -> Result<Value, ParseError> {
+	let Ok(inst) = DateTime::parse_from_rfc3339(source) else {
+		return err!(format!("invalid instZ ({source})"), pos, file);
+	};
+
+	// millisecond precision, offset in minutes
+	if inst.nanosecond() % 1000000 != 0 || inst.offset().local_minus_utc() % 60 != 0 {
+		return err!(format!("invalid instZ ({source})"), pos, file);
+	}
+
+	Ok(Value::InstZ(inst))
+}
+pub fn parse_date(source: &str, pos: Pos, file: &str) -> Result<Value, ParseError> {
+	let Ok(date) = NaiveDate::parse_from_str(source, "%Y-%m-%d") else {
+		return err!(format!("invalid date ({source})"), pos, file);
+	};
+	Ok(Value::Date(date))
+}
+pub fn parse_time(source: &str, pos: Pos, file: &str) -> Result<Value, ParseError> {
+	let Ok(time) = NaiveTime::parse_from_str(source, "%H:%M:%S%.f") else {
+		return err!(format!("invalid time ({source})"), pos, file);
+	};
+	Ok(Value::Time(time))
+}
 
 struct DurParseCTX<'a> {
 	val: i64,
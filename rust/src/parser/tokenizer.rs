@@ -9,6 +9,7 @@ use num_bigint::BigInt;
 use num_traits::Num;
 
 use crate::{
+	Decimal,
 	errors::{ParseError, err},
 	parser::utils::{StrExt, end_of_input, unexpected_token, while_matching},
 };
@@ -74,6 +75,7 @@ pub enum Token<'s> {
 	Int(i64, Pos),
 	BigInt(BigInt, Pos),
 	Float(f64, Pos),
+	Decimal(Decimal, Pos),
 
 	Symbol(char, Pos),
 
@@ -90,6 +92,7 @@ impl Token<'_> {
 			Token::Int(_, ind) => *ind,
 			Token::BigInt(_, ind) => *ind,
 			Token::Float(_, ind) => *ind,
+			Token::Decimal(_, ind) => *ind,
 			Token::Symbol(_, ind) => *ind,
 			Token::EOF(ind) => *ind,
 		}
@@ -105,6 +108,7 @@ impl Display for Token<'_> {
 			Token::Int(nb, _) => write!(f, "{nb}"),
 			Token::BigInt(nb, _) => write!(f, "{nb}"),
 			Token::Float(nb, _) => write!(f, "{nb}"),
+			Token::Decimal(nb, _) => write!(f, "{nb}dec"),
 			Token::Symbol(symbol, _) => write!(f, "{symbol}"),
 			Token::EOF(_) => write!(f, "end_of_file"),
 		}
@@ -217,7 +221,17 @@ fn parse_str(source: &str, pos: &mut Pos, file: &str) -> Result<String, ParseErr
 	return Ok(res);
 }
 
-/// grammer: ["+" | "-"] (dec_part | [dec_part] "." dec_part) [("e" | "E") ["+" | "-"] dec_part]
+/// decimals are written like floats without exponent, the scale is the count of fraction digits
+fn parse_dec(nb_source: &str, pos: Pos, file: &str) -> Result<Decimal, ParseError> {
+	let (int, frac) = nb_source.split_once('.').unwrap_or((nb_source, ""));
+	let (Ok(value), Ok(scale)) = (format!("{int}{frac}").parse::<i64>(), u8::try_from(frac.len()))
+	else {
+		return err!(format!("decimal ({nb_source}) out of range"), pos, file);
+	};
+	Ok(Decimal::new(value, scale))
+}
+
+/// grammer: ["+" | "-"] (dec_part | [dec_part] "." dec_part) ([("e" | "E") ["+" | "-"] dec_part] | "dec")
 fn parse_float<'a>(
 	source: &'a str, mut ind: usize, pos: Pos, file: &str,
 ) -> Result<(Token<'a>, usize, Pos), ParseError> {
@@ -236,6 +250,16 @@ fn parse_float<'a>(
 		}
 	}
 
+	// decimal
+	let suffix_end =
+		while_matching(source, ind, |c| matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9'));
+	if &source[ind..suffix_end] == "dec" {
+		let nb_source = strip_dashes_in_nb(&source[start_ind..ind], pos, file)?;
+		let value = parse_dec(&nb_source, pos, file)?;
+		let new_pos = pos + (suffix_end - start_ind) as u32;
+		return Ok((Token::Decimal(value, pos), suffix_end, new_pos));
+	}
+
 	// exponent
 	if let Some('e' | 'E') = source.char_at(ind) {
 		let e_ind = ind;
@@ -260,7 +284,7 @@ fn parse_float<'a>(
 	Ok((Token::Float(value, pos), ind, new_pos))
 }
 
-/// grammer: ["+" | "-"] (dec_part | "0x" hex_part | "0b" bin_part) ["bint"]
+/// grammer: ["+" | "-"] (dec_part | "0x" hex_part | "0b" bin_part) ["bint" | "dec"]
 fn parse_int<'a>(
 	source: &'a str, mut ind: usize, pos: Pos, file: &str,
 ) -> Result<(Token<'a>, usize, Pos), ParseError> {
@@ -292,8 +316,11 @@ fn parse_int<'a>(
 	let nb_source = strip_dashes_in_nb(&source[dg_start..end_ind], pos, file)?;
 	ind = end_ind;
 
-	// float path
-	if matches!(source.char_at(ind), Some('.' | 'e' | 'E')) {
+	// float path, two dots are a range
+	let is_range = source.char_at(ind + 1) == Some('.');
+	if matches!(source.char_at(ind), Some('e' | 'E'))
+		|| source.char_at(ind) == Some('.') && !is_range
+	{
 		return parse_float(source, start_ind, pos, file);
 	}
 
@@ -312,6 +339,12 @@ fn parse_int<'a>(
 		return Ok((Token::BigInt(value, pos), ind, new_pos));
 	}
 
+	// decimal path
+	if suffix == "dec" && base == 10 {
+		let value = parse_dec(&format!("{}{nb_source}", if neg { "-" } else { "" }), pos, file)?;
+		return Ok((Token::Decimal(value, pos), ind, new_pos));
+	}
+
 	if suffix != "" {
 		return err!(format!("invalid suffix \"{suffix}\""), pos, file);
 	}
@@ -335,8 +368,15 @@ fn parse_int<'a>(
 	}
 }
 
+/// documentation comment lines without the leading "///", by the index of the token following them
+pub type Docs<'s> = Vec<(usize, &'s str)>;
+
 /// simplify the source into sequence of tokens
-pub fn tokenize<'a>(source: &'a str, file: &str) -> Result<Vec<Token<'a>>, ParseError> {
+///
+/// documentation comments are collected into `docs` if passed, else they are normal comments.
+pub fn tokenize<'a>(
+	source: &'a str, file: &str, mut docs: Option<&mut Docs<'a>>,
+) -> Result<Vec<Token<'a>>, ParseError> {
 	let mut tokens = Vec::<Token>::new();
 	let mut ind: usize = 0;
 	let mut pos = Pos::new(1, 1);
@@ -352,13 +392,21 @@ pub fn tokenize<'a>(source: &'a str, file: &str) -> Result<Vec<Token<'a>>, Parse
 		match cur_char {
 			' ' | '\t' | '\r' => inc!(1),
 			'\n' => {
+				ind += 1;
 				pos.line += 1;
 				pos.col = 1;
 			}
 
 			'.' => {
+				// case range
+				if source.char_at(ind + 1) == Some('.') {
+					tokens.push(Token::Symbol('.', pos));
+					inc!(1);
+					tokens.push(Token::Symbol('.', pos));
+					inc!(1);
+				}
 				// case fractionless float
-				if matches!(source.char_at(ind + 1), Some('0'..='9')) {
+				else if matches!(source.char_at(ind + 1), Some('0'..='9')) {
 					let token;
 					(token, ind, pos) = parse_float(source, ind, pos, file)?;
 					tokens.push(token);
@@ -368,7 +416,8 @@ pub fn tokenize<'a>(source: &'a str, file: &str) -> Result<Vec<Token<'a>>, Parse
 				}
 			}
 			// one char tokens
-			',' | ':' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | '?' | '@' => {
+			',' | ':' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | '?' | '@' | '|' | '='
+			| ';' => {
 				tokens.push(Token::Symbol(cur_char, pos));
 				inc!(1);
 			}
@@ -428,7 +477,15 @@ pub fn tokenize<'a>(source: &'a str, file: &str) -> Result<Vec<Token<'a>>, Parse
 					// single line
 					Some('/') => {
 						// move to the end of the line or input
-						ind = source.find_after('\n', ind + 2).unwrap_or(source.len());
+						let end = source.find_after('\n', ind + 2).unwrap_or(source.len());
+						// documentation comment
+						if let Some(docs) = &mut docs
+							&& source.char_at(ind + 2) == Some('/')
+						{
+							let doc = source[ind + 3..end].trim_end();
+							docs.push((tokens.len(), doc.strip_prefix(' ').unwrap_or(doc)));
+						}
+						ind = end;
 					}
 					// multi line
 					Some('*') => {
@@ -143,8 +143,10 @@ macro_rules! parse_struct_like {
 		} else {
 			loop {
 				$eacher;
-				// comma after the last field is optional
-				let has_comma = try_consume_symbol(',', tokens, $ind, $file)?;
+				// comma after the last field is optional, statements ending with ";" need none
+				use $crate::parser::tokenizer::Token::Symbol;
+				let has_comma = try_consume_symbol(',', tokens, $ind, $file)?
+					|| matches!(tokens[*$ind - 1], Symbol(';', _));
 				if try_consume_symbol(end_char, tokens, $ind, $file)? {
 					break;
 				}
@@ -1,22 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-	DeclProvider, Key, ParseError, ParseOptions, Value,
-	builtins::BUILT_INS_IDS,
+	Decimal, DeclProvider, Key, ParseError, ParseOptions, Value,
+	builtins::{
+		ANY_TYPEID, ARR_TYPEID, BUILT_INS_IDS, DATE_TYPEID, DEC_TYPEID, F32_TYPEID, F64_TYPEID,
+		FIXED_ARR_TYPEID, INSTZ_TYPEID, MAP_TYPEID, PARAM_TYPEID, SET_TYPEID, TIME_TYPEID,
+		TUPLE_TYPEID,
+	},
 	declaration::{DeclItem, EnumVariant, StructDef, TypeId, resolve_typeid},
-	errors::{end_of_input, unexpected_token},
+	errors::err,
 	parser::{
-		declaration::{DeclContext, parse_metadata, parse_typeid_general},
-		rich_types::{parse_dur, parse_inst, parse_uuid},
-		tokenizer::Token,
-		utils::{consume_ident, consume_str, consume_symbol, struct_like_end, struct_like_start},
+		declaration::{DeclContext, parse_metadata, parse_typeid_general, resolve_type_name},
+		rich_types::{parse_date, parse_dur, parse_inst, parse_instz, parse_time, parse_uuid},
+		tokenizer::{Pos, Token},
+		utils::{
+			consume_ident, consume_str, consume_symbol, consume_uint, end_of_input,
+			parse_struct_like, try_consume_symbol, unexpected_token,
+		},
 	},
 };
 
-pub fn mismatch_types<T>(expected: &str, found: &str, ind: usize) -> Result<T, ParseError> {
-	Err(ParseError::TypeError(format!("expected type {expected}, found {found} at {ind}",)))
+pub fn mismatch_types<T>(
+	expected: &str, found: &str, pos: Pos, file: &str,
+) -> Result<T, ParseError> {
+	err!(format!("expected type {expected}, found {found}"), pos, file)
 }
-fn check_range_nb(nb: i64, signed: bool, bits: u8, ind: usize) -> Result<i64, ParseError> {
+fn check_range_nb(
+	nb: i64, signed: bool, bits: u8, pos: Pos, file: &str,
+) -> Result<i64, ParseError> {
 	// compute range
 	let (min, max) = match signed {
 		false => (0, (1 << bits) - 1),
@@ -24,42 +35,38 @@ fn check_range_nb(nb: i64, signed: bool, bits: u8, ind: usize) -> Result<i64, Pa
 	};
 	// check range
 	if nb < min || nb > max {
-		return Err(ParseError::TypeError(format!(
-			"number ({nb}) is out of range for {}{bits} number at {ind}",
-			if signed { "i" } else { "u" }
-		)));
+		let sign = if signed { "i" } else { "u" };
+		return err!(format!("number ({nb}) is out of range for {sign}{bits} number"), pos, file);
 	}
 	Ok(nb)
 }
 
-fn parse_small_ints(nb: i64, typeid: &TypeId, ind: usize) -> Result<Value, ParseError> {
+fn parse_small_ints(nb: i64, typeid: &TypeId, pos: Pos, file: &str) -> Result<Value, ParseError> {
 	Ok(match typeid.id {
-		0x10 => Value::Uint(check_range_nb(nb, false, 8, ind)? as u64),
-		0x11 => Value::Uint(check_range_nb(nb, false, 16, ind)? as u64),
-		0x12 => Value::Uint(check_range_nb(nb, false, 32, ind)? as u64),
-		0x14 => Value::Int(check_range_nb(nb, true, 8, ind)?),
-		0x15 => Value::Int(check_range_nb(nb, true, 16, ind)?),
-		0x16 => Value::Int(check_range_nb(nb, true, 32, ind)?),
+		0x10 => Value::Uint(check_range_nb(nb, false, 8, pos, file)? as u64),
+		0x11 => Value::Uint(check_range_nb(nb, false, 16, pos, file)? as u64),
+		0x12 => Value::Uint(check_range_nb(nb, false, 32, pos, file)? as u64),
+		0x14 => Value::Int(check_range_nb(nb, true, 8, pos, file)?),
+		0x15 => Value::Int(check_range_nb(nb, true, 16, pos, file)?),
+		0x16 => Value::Int(check_range_nb(nb, true, 32, pos, file)?),
 		_ => unreachable!(),
 	})
 }
 
 fn parse_typeid(
-	tokens: &[Token], ind: &mut usize, loc: &impl Fn() -> String, ctx: &DeclContext<'_>,
-	options: &ParseOptions,
+	tokens: &[Token], ind: &mut usize, ctx: &DeclContext<'_>, options: &ParseOptions,
 ) -> Result<TypeId, ParseError> {
-	let metadata = parse_metadata(tokens, ind, loc, options)?;
-
-	let typename = consume_ident(tokens, ind)?;
+	let metadata = parse_metadata(tokens, ind, options, &ctx.file.name)?;
 
-	//parse_typeid_general!((tokens, ind, typename, loc, metadata, ctx, options), |_| loc())
+	parse_typeid_general!((tokens, ind, metadata, ctx, options))
 }
 
 fn parse_arr(
 	tokens: &[Token], ind: &mut usize, typeid: &TypeId, ctx: &DeclContext,
 	provider: &dyn DeclProvider, options: &ParseOptions,
 ) -> Result<Value, ParseError> {
-	consume_symbol('[', tokens, ind)?;
+	let pos = tokens[*ind].pos();
+	let file = &ctx.file.name;
 
 	// replace any typeid with arr<any>
 	let typeid = if typeid.is_any() {
@@ -69,27 +76,69 @@ fn parse_arr(
 	};
 
 	let mut arr = Vec::new();
-	let mut watched_comma = true;
 	let itemid = typeid.item.as_ref().unwrap().as_ref();
-
-	// loop through items
-	loop {
-		if struct_like_start(tokens, ind, &mut watched_comma, ']')? {
-			break;
+	// sets track their items to reject duplicates
+	let mut set_items = (typeid.id == SET_TYPEID).then(HashSet::new);
+
+	parse_struct_like!((tokens, '[', ']'), file, ind => {
+		let item_pos = tokens[*ind].pos();
+		let item = parse_value(tokens, ind, itemid, ctx, provider, options)?;
+		if let Some(set_items) = &mut set_items {
+			let Ok(key) = Key::try_from(item.inner().clone()) else {
+				return err!("set item must be a primitive".to_string(), item_pos, file);
+			};
+			if !set_items.insert(key) {
+				return err!(format!("duplicated set item {}", item.inner()), item_pos, file);
+			}
 		}
-
-		arr.push(parse_value(tokens, ind, itemid, ctx, provider, options)?);
-
-		struct_like_end(tokens, ind, &mut watched_comma);
+		arr.push(item);
+	});
+
+	// fixed length arrays
+	if typeid.id == FIXED_ARR_TYPEID && arr.len() != typeid.variant as usize {
+		let (len, found) = (typeid.variant, arr.len());
+		let msg = format!("expected {len} items for {}, found {found}", typeid.name(provider));
+		return err!(msg, pos, file);
 	}
 
 	Ok(Value::Arr(arr))
 }
+/// tuples are written between parentheses, or brackets when the type is known
+fn parse_tuple(
+	tokens: &[Token], ind: &mut usize, typeid: &TypeId, (start_char, end_char): (char, char),
+	ctx: &DeclContext, provider: &dyn DeclProvider, options: &ParseOptions,
+) -> Result<Value, ParseError> {
+	let pos = tokens[*ind].pos();
+	let file = &ctx.file.name;
+
+	let mut items = Vec::new();
+	parse_struct_like!((tokens, start_char, end_char), file, ind => {
+		// items of untyped tuples are any
+		let itemid = match typeid.args.get(items.len()) {
+			_ if typeid.is_any() => &TypeId::ANY,
+			Some(itemid) => itemid,
+			None => {
+				let msg = format!("too many items for tuple {}", typeid.name(provider));
+				return err!(msg, tokens[*ind].pos(), file);
+			}
+		};
+		items.push(parse_value(tokens, ind, itemid, ctx, provider, options)?);
+	});
+
+	if !typeid.is_any() && items.len() != typeid.args.len() {
+		let (len, found) = (typeid.args.len(), items.len());
+		let msg =
+			format!("expected {len} items for tuple {}, found {found}", typeid.name(provider));
+		return err!(msg, pos, file);
+	}
+
+	Ok(Value::Arr(items))
+}
 fn parse_map(
 	tokens: &[Token], ind: &mut usize, typeid: &TypeId, ctx: &DeclContext,
 	provider: &dyn DeclProvider, options: &ParseOptions,
 ) -> Result<Value, ParseError> {
-	consume_symbol('{', tokens, ind)?;
+	let file = &ctx.file.name;
 
 	// replace any typeid with map<any, any>
 	let typeid = if typeid.is_any() {
@@ -101,15 +150,9 @@ fn parse_map(
 	let mut map = HashMap::new();
 	let keyid = &TypeId::new(0, typeid.variant, None);
 	let itemid = typeid.item.as_ref().unwrap().as_ref();
-	let mut watched_comma = true;
 
-	// loop through items
-	loop {
-		if struct_like_start(tokens, ind, &mut watched_comma, '}')? {
-			break;
-		}
-
-		let key_ind = tokens[*ind].pos();
+	parse_struct_like!((tokens, '{', '}'), file, ind => {
+		let key_pos = tokens[*ind].pos();
 		*ind += 1; // skip key
 		let key = match tokens.get(*ind - 1) {
 			Some(Token::Ident(key, _)) => Key::from(*key),
@@ -117,33 +160,33 @@ fn parse_map(
 			// [key]
 			Some(Token::Symbol('[', _)) => {
 				let key = parse_value(tokens, ind, keyid, ctx, provider, options)?;
-				consume_symbol(']', tokens, ind)?;
+				consume_symbol(']', tokens, ind, file)?;
 				// Value => Key
-				key.try_into().map_err(|_| {
-					ParseError::TypeError(format!("map key can only be a primitive at {key_ind}"))
-				})?
+				let Ok(key) = key.try_into() else {
+					return err!("map key can only be a primitive".to_string(), key_pos, file);
+				};
+				key
 			}
-			_ => return Err(unexpected_token(tokens[*ind - 1].to_string(), key_ind)),
+			Some(Token::EOF(_)) | None => return end_of_input(file),
+			Some(token) => return unexpected_token(token, key_pos, file),
 		};
 
 		if let Key::Str(_) = &key
 			&& !matches!(keyid.id, 1 | 0x20)
 		{
-			mismatch_types(&keyid.name(provider), "str", key_ind)?
+			mismatch_types(&keyid.name(provider), "str", key_pos, file)?
 		}
 
 		// check for collision
 		if map.contains_key(&key) {
-			return Err(ParseError::TypeError(format!("duplicated map key {key:?} at {key_ind}",)));
+			return err!(format!("duplicated map key {key:?}"), key_pos, file);
 		}
 
-		consume_symbol(':', tokens, ind)?;
+		consume_symbol(':', tokens, ind, file)?;
 
 		let value = parse_value(tokens, ind, itemid, ctx, provider, options)?;
 		map.insert(key, value);
-
-		struct_like_end(tokens, ind, &mut watched_comma);
-	}
+	});
 
 	Ok(Value::Map(Box::new(map)))
 }
@@ -155,7 +198,7 @@ enum ResolveDefResult<'a> {
 }
 fn resolve_item_def<'a>(
 	tokens: &[Token], ind: &mut usize, map: &mut HashMap<Key, Value>, item: &'a DeclItem,
-	variant: Option<&'a EnumVariant>, start_ind: usize,
+	variant: Option<&'a EnumVariant>, file: &str,
 ) -> Result<ResolveDefResult<'a>, ParseError> {
 	use ResolveDefResult::*;
 
@@ -165,14 +208,15 @@ fn resolve_item_def<'a>(
 			Some(variant) => variant,
 			// case Type.variant
 			_ => {
-				consume_symbol('.', tokens, ind)?;
-				let variant = consume_ident(tokens, ind)?;
-				item.get_variant_by_name(variant).ok_or_else(|| {
-					ParseError::TypeError(format!(
-						"variant \"{variant}\" not found in enum \"{}\" at {start_ind}",
-						item.name()
-					))
-				})?
+				consume_symbol('.', tokens, ind, file)?;
+				let pos = tokens[*ind].pos();
+				let variant = consume_ident(tokens, ind, file)?;
+				let Some(found) = item.get_variant_by_name(variant) else {
+					let msg =
+						format!("variant \"{variant}\" not found in enum \"{}\"", item.name());
+					return err!(msg, pos, file);
+				};
+				found
 			}
 		};
 
@@ -194,89 +238,178 @@ fn resolve_item_def<'a>(
 }
 fn parse_item(
 	tokens: &[Token], ind: &mut usize, typeid: &TypeId, variant: Option<&EnumVariant>,
-	start_ind: usize, ctx: &DeclContext, provider: &dyn DeclProvider, options: &ParseOptions,
+	start_pos: Pos, ctx: &DeclContext, provider: &dyn DeclProvider, options: &ParseOptions,
 ) -> Result<Value, ParseError> {
+	let file = &ctx.file.name;
 	let item = resolve_typeid(typeid, provider);
 	let mut map = HashMap::new();
 
 	// resolve definition
 	use ResolveDefResult::*;
-	let (def, variant) = match resolve_item_def(tokens, ind, &mut map, item, variant, start_ind)? {
+	let (def, variant) = match resolve_item_def(tokens, ind, &mut map, item, variant, file)? {
 		Norm(def, variant) => (def, variant),
 		CaseUnitVariant(variant) => return Ok(Value::UnitVar(variant.to_string())),
 	};
 
 	let mut required = def.required_fields;
+	let item_name = || match variant {
+		"" => typeid.name(provider),
+		variant => format!("{}.{variant}", typeid.name(provider)),
+	};
 
-	consume_symbol('{', tokens, ind)?;
-	let mut watched_comma = true;
-
-	// loop through fields
-	loop {
-		if struct_like_start(tokens, ind, &mut watched_comma, '}')? {
-			break;
-		}
-
+	parse_struct_like!((tokens, '{', '}'), file, ind => {
+		let pos = tokens[*ind].pos();
 		let name = match tokens.get(*ind) {
 			Some(Token::Ident(key, _)) => *key,
 			Some(Token::Str(key, _)) => key,
-			_ => return Err(unexpected_token(tokens[*ind].to_string(), tokens[*ind].pos())),
+			Some(Token::EOF(_)) | None => return end_of_input(file),
+			Some(token) => return unexpected_token(token, pos, file),
 		};
 
 		// check for existence
-		let field = &def.get_field_by_name(name).ok_or_else(|| {
-			ParseError::TypeError(format!(
-				"struct {}{} doesnt contain field \"{name}\" at {ind}",
-				typeid.name(provider),
-				if variant.is_empty() { "".to_string() } else { format!(".{variant}") }
-			))
-		})?;
+		let Some(field) = def.get_field_by_name(name) else {
+			let msg = format!("struct {} doesnt contain field \"{name}\"", item_name());
+			return err!(msg, pos, file);
+		};
 
 		// check for collision
 		let key = Key::from(name);
 		if map.contains_key(&key) {
-			return Err(ParseError::TypeError(format!(
-				"duplicated field \"{name}\" at {}",
-				tokens[*ind].pos()
-			)));
+			return err!(format!("duplicated field \"{name}\""), pos, file);
+		}
+		// deprecated fields are still accepted
+		if let (Some(reason), Some(on_warning)) =
+			(&field.annotations.deprecated, &options.on_warning)
+		{
+			on_warning(&format!("field \"{name}\" is deprecated: {reason}, at {file}:{pos}"));
 		}
 		*ind += 1;
 
-		consume_symbol(':', tokens, ind)?;
+		consume_symbol(':', tokens, ind, file)?;
 
-		if !field.is_optional {
+		if field.is_required() {
 			required -= 1;
 		}
 
-		let value = parse_value(tokens, ind, &field.typeid, ctx, provider, options)?;
+		// generic fields take the type arguments
+		let field_typeid = field.typeid.substitute(&typeid.args);
+		let value = parse_value(tokens, ind, &field_typeid, ctx, provider, options)?;
 		map.insert(key, value);
-
-		struct_like_end(tokens, ind, &mut watched_comma);
-	}
+	});
 
 	// case of missing required fields
 	if required != 0 {
-		return Err(ParseError::TypeError(format!(
-			"struct {}{} is missing required fields at {start_ind}",
-			typeid.name(provider),
-			if variant.is_empty() { "".to_string() } else { format!(".{variant}") }
-		)));
+		return err!(format!("struct {} is missing required fields", item_name()), start_pos, file);
+	}
+
+	// absent fields take their default value
+	for field in def.fields.iter().filter_map(|f| f.as_ref()) {
+		if let Some(default) = &field.default {
+			map.entry(Key::from(field.name.as_str())).or_insert_with(|| default.clone());
+		}
 	}
 
 	Ok(Value::Map(Box::new(map)))
 }
 
+// parse flags
+fn add_flag(
+	name: &str, pos: Pos, item: &DeclItem, mask: &mut u64, file: &str,
+) -> Result<(), ParseError> {
+	let Some(bit) = item.get_flag_by_name(name) else {
+		let msg = format!("flag \"{name}\" not found in flags \"{}\"", item.name());
+		return err!(msg, pos, file);
+	};
+	if *mask & 1 << bit != 0 {
+		return err!(format!("duplicated flag \"{name}\""), pos, file);
+	}
+	*mask |= 1 << bit;
+	Ok(())
+}
+fn flags_value(item: &DeclItem, mask: u64) -> Value {
+	let bits = (0..64).filter(|bit| mask & 1 << bit != 0);
+	Value::Arr(
+		bits.map(|bit| Value::UnitVar(item.get_flag_by_id(bit).unwrap().into()))
+			.collect(),
+	)
+}
+/// grammer: flag ("|" [path "."] flag)*
+fn parse_flags(
+	tokens: &[Token], ind: &mut usize, item: &DeclItem, first: &str, pos: Pos, file: &str,
+) -> Result<Value, ParseError> {
+	let mut mask = 0;
+	add_flag(first, pos, item, &mut mask, file)?;
+
+	while try_consume_symbol('|', tokens, ind, file)? {
+		let pos = tokens[*ind].pos();
+
+		// either Type.flag or flag
+		let mut path = vec![consume_ident(tokens, ind, file)?];
+		while try_consume_symbol('.', tokens, ind, file)? {
+			path.push(consume_ident(tokens, ind, file)?);
+		}
+		if path.len() > 1 && path[path.len() - 2] != item.name() {
+			let msg = format!("expected flag of \"{}\", found \"{}\"", item.name(), path.join("."));
+			return err!(msg, pos, file);
+		}
+
+		add_flag(path.last().unwrap(), pos, item, &mut mask, file)?;
+	}
+
+	Ok(flags_value(item, mask))
+}
+/// grammer: "[" (flag ",")* "]"
+fn parse_flags_arr(
+	tokens: &[Token], ind: &mut usize, item: &DeclItem, file: &str,
+) -> Result<Value, ParseError> {
+	let mut mask = 0;
+	parse_struct_like!((tokens, '[', ']'), file, ind => {
+		let pos = tokens[*ind].pos();
+		add_flag(consume_ident(tokens, ind, file)?, pos, item, &mut mask, file)?;
+	});
+	Ok(flags_value(item, mask))
+}
+
+/// check an explicit type against the implicit one, returning the type of the value
+fn check_explicit_type<'t>(
+	typeid: &'t TypeId, explicit_type: &'t TypeId, pos: Pos, provider: &dyn DeclProvider,
+	file: &str,
+) -> Result<&'t TypeId, ParseError> {
+	if !typeid.is_any() && typeid != explicit_type {
+		let (expected, found) = (typeid.name(provider), explicit_type.name(provider));
+		return mismatch_types(&expected, &found, pos, file);
+	}
+	// replace with the explicit type if implicit is any
+	Ok(if typeid.is_any() { explicit_type } else { typeid })
+}
+/// whether the parentheses at `ind` are a tuple type followed by array like items
+fn is_tuple_type_prefix(tokens: &[Token], ind: usize) -> bool {
+	let mut depth = 0;
+	for (offset, token) in tokens[ind..].iter().enumerate() {
+		match token {
+			Token::Symbol('(', _) => depth += 1,
+			Token::Symbol(')', _) if depth == 1 => {
+				return matches!(tokens.get(ind + offset + 1), Some(Token::Symbol('[', _)));
+			}
+			Token::Symbol(')', _) => depth -= 1,
+			_ => (),
+		}
+	}
+	false
+}
+
 fn parse_ident(
 	ident: &str, tokens: &[Token], ind: &mut usize, typeid: &TypeId, provider: &dyn DeclProvider,
 	ctx: &DeclContext<'_>, options: &ParseOptions,
 ) -> Result<Value, ParseError> {
-	let start_ind = tokens[*ind - 1].pos();
+	let start_pos = tokens[*ind - 1].pos();
+	let file = &ctx.file.name;
 
 	match ident {
 		// bool
 		"true" | "false" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 8) {
-				mismatch_types(&typeid.name(provider), "bool", *ind)?;
+				mismatch_types(&typeid.name(provider), "bool", start_pos, file)?;
 			}
 			Ok(Value::Bool(ident == "true"))
 		}
@@ -284,13 +417,13 @@ fn parse_ident(
 		// float constants
 		"nan" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x18..=0x1a) {
-				mismatch_types(&typeid.name(provider), "f64", *ind)?;
+				mismatch_types(&typeid.name(provider), "f64", start_pos, file)?;
 			}
 			Ok(Value::Float(f64::NAN))
 		}
 		"inf" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x18..=0x1a) {
-				mismatch_types(&typeid.name(provider), "f64", *ind)?;
+				mismatch_types(&typeid.name(provider), "f64", start_pos, file)?;
 			}
 			Ok(Value::Float(f64::INFINITY))
 		}
@@ -298,27 +431,51 @@ fn parse_ident(
 		// rich types
 		"uuid" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x33) {
-				mismatch_types(&typeid.name(provider), "uuid", *ind)?;
+				mismatch_types(&typeid.name(provider), "uuid", start_pos, file)?;
 			}
-			parse_uuid(consume_str(tokens, ind)?, *ind)
+			let pos = tokens[*ind].pos();
+			parse_uuid(consume_str(tokens, ind, file)?, pos, file)
 		}
 		"inst" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x30) {
-				mismatch_types(&typeid.name(provider), "inst", *ind)?;
+				mismatch_types(&typeid.name(provider), "inst", start_pos, file)?;
 			}
-			parse_inst(consume_str(tokens, ind)?, false, *ind)
+			let pos = tokens[*ind].pos();
+			parse_inst(consume_str(tokens, ind, file)?, false, pos, file)
 		}
 		"instN" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x31) {
-				mismatch_types(&typeid.name(provider), "instN", *ind)?;
+				mismatch_types(&typeid.name(provider), "instN", start_pos, file)?;
 			}
-			parse_inst(consume_str(tokens, ind)?, true, *ind)
+			let pos = tokens[*ind].pos();
+			parse_inst(consume_str(tokens, ind, file)?, true, pos, file)
 		}
 		"dur" => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x32) {
-				mismatch_types(&typeid.name(provider), "dur", *ind)?;
+				mismatch_types(&typeid.name(provider), "dur", start_pos, file)?;
+			}
+			parse_dur(tokens, ind, file)
+		}
+		"instZ" => {
+			if typeid.ns != 0 || !matches!(typeid.id, 1 | INSTZ_TYPEID) {
+				mismatch_types(&typeid.name(provider), "instZ", start_pos, file)?;
+			}
+			let pos = tokens[*ind].pos();
+			parse_instz(consume_str(tokens, ind, file)?, pos, file)
+		}
+		"date" => {
+			if typeid.ns != 0 || !matches!(typeid.id, 1 | DATE_TYPEID) {
+				mismatch_types(&typeid.name(provider), "date", start_pos, file)?;
 			}
-			parse_dur(consume_str(tokens, ind)?, start_ind, tokens[*ind - 1].pos())
+			let pos = tokens[*ind].pos();
+			parse_date(consume_str(tokens, ind, file)?, pos, file)
+		}
+		"time" => {
+			if typeid.ns != 0 || !matches!(typeid.id, 1 | TIME_TYPEID) {
+				mismatch_types(&typeid.name(provider), "time", start_pos, file)?;
+			}
+			let pos = tokens[*ind].pos();
+			parse_time(consume_str(tokens, ind, file)?, pos, file)
 		}
 
 		// maps, arrs, structs and enums
@@ -330,38 +487,46 @@ fn parse_ident(
 				if let Some(variant) = item.get_variant_by_name(ident) {
 					#[rustfmt::skip]
 					return parse_item(
-						tokens, ind, typeid, Some(variant), start_ind, ctx, provider, options,
+						tokens, ind, typeid, Some(variant), start_pos, ctx, provider, options,
 					);
 				}
+				// same for flags
+				if item.get_flag_by_name(ident).is_some() {
+					return parse_flags(tokens, ind, item, ident, start_pos, file);
+				}
 			}
 
 			*ind -= 1;
 
 			// parse explicit type
-			let explicit_type =
-				parse_typeid(tokens, ind, &|| format!("at index {start_ind}"), ctx, options)?;
-
-			// check against the implicit type
-			if typeid != &explicit_type {
-				return Err(mismatch_types(
-					&typeid.name(provider),
-					&explicit_type.name(provider),
-					*ind,
-				)?);
-			}
-			// replace with the explicit type if implicit is any
-			let typeid = if typeid.is_any() { &explicit_type } else { typeid };
+			let explicit_type = parse_typeid(tokens, ind, ctx, options)?;
+			let typeid = check_explicit_type(typeid, &explicit_type, start_pos, provider, file)?;
 
 			// builtins
 			if typeid.ns == 0 {
 				match typeid.id {
-					0x22 => parse_arr(tokens, ind, typeid, ctx, provider, options),
+					0x22 | SET_TYPEID | FIXED_ARR_TYPEID => {
+						parse_arr(tokens, ind, typeid, ctx, provider, options)
+					}
 					0x23 => parse_map(tokens, ind, typeid, ctx, provider, options),
-					_ => Err(unexpected_token(ident, start_ind)),
+					TUPLE_TYPEID => {
+						let delimiters = match tokens.get(*ind) {
+							Some(Token::Symbol('(', _)) => ('(', ')'),
+							_ => ('[', ']'),
+						};
+						parse_tuple(tokens, ind, typeid, delimiters, ctx, provider, options)
+					}
+					_ => unexpected_token(ident, start_pos, file),
 				}
 			// user types
+			} else if let item @ DeclItem::Flags { .. } = resolve_typeid(typeid, provider) {
+				// case Type.flag
+				consume_symbol('.', tokens, ind, file)?;
+				let pos = tokens[*ind].pos();
+				let flag = consume_ident(tokens, ind, file)?;
+				parse_flags(tokens, ind, item, flag, pos, file)
 			} else {
-				parse_item(tokens, ind, typeid, None, start_ind, ctx, provider, options)
+				parse_item(tokens, ind, typeid, None, start_pos, ctx, provider, options)
 			}
 		}
 	}
@@ -371,9 +536,9 @@ pub fn parse_value(
 	tokens: &[Token], ind: &mut usize, typeid: &TypeId, ctx: &DeclContext<'_>,
 	provider: &dyn DeclProvider, options: &ParseOptions,
 ) -> Result<Value, ParseError> {
-	let start_ind = *ind;
-
-	let metadata = parse_metadata(tokens, ind, &|| format!("at index {start_ind}"), options)?;
+	let file = &ctx.file.name;
+	let metadata = parse_metadata(tokens, ind, options, file)?;
+	let start_pos = tokens[*ind].pos();
 	*ind += 1;
 	let value = match tokens.get(*ind - 1) {
 		Some(Token::Ident(ident, _)) => {
@@ -381,98 +546,137 @@ pub fn parse_value(
 		}
 		// ananonymous arrays
 		Some(Token::Symbol('[', _)) => {
-			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x22) {
-				return mismatch_types(&typeid.name(provider), "arr", *ind);
+			*ind -= 1;
+			if typeid.ns == 0 && typeid.id == TUPLE_TYPEID {
+				parse_tuple(tokens, ind, typeid, ('[', ']'), ctx, provider, options)?
+			} else if typeid.ns != 0
+				&& let item @ DeclItem::Flags { .. } = resolve_typeid(typeid, provider)
+			{
+				parse_flags_arr(tokens, ind, item, file)?
+			} else {
+				if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x22 | SET_TYPEID | FIXED_ARR_TYPEID)
+				{
+					return mismatch_types(&typeid.name(provider), "arr", start_pos, file);
+				}
+				parse_arr(tokens, ind, typeid, ctx, provider, options)?
 			}
+		}
+		// tuples written like arrays with an explicit type
+		Some(Token::Symbol('(', _)) if is_tuple_type_prefix(tokens, *ind - 1) => {
 			*ind -= 1;
-			parse_arr(tokens, ind, typeid, ctx, provider, options)?
+			let explicit_type = parse_typeid(tokens, ind, ctx, options)?;
+			let typeid = check_explicit_type(typeid, &explicit_type, start_pos, provider, file)?;
+			parse_tuple(tokens, ind, typeid, ('[', ']'), ctx, provider, options)?
+		}
+		// tuples
+		Some(Token::Symbol('(', _)) => {
+			if typeid.ns != 0 || !matches!(typeid.id, 1 | TUPLE_TYPEID) {
+				return mismatch_types(&typeid.name(provider), "tuple", start_pos, file);
+			}
+			*ind -= 1;
+			parse_tuple(tokens, ind, typeid, ('(', ')'), ctx, provider, options)?
 		}
 		// anonymous maps and structs
 		Some(Token::Symbol('{', _)) => {
 			*ind -= 1;
 			if typeid.ns == 0 {
 				if !matches!(typeid.id, 1 | 0x23) {
-					return mismatch_types(&typeid.name(provider), "map", *ind);
+					return mismatch_types(&typeid.name(provider), "map", start_pos, file);
 				}
 				parse_map(tokens, ind, typeid, ctx, provider, options)?
+			} else if let DeclItem::Flags { .. } = resolve_typeid(typeid, provider) {
+				return mismatch_types(&typeid.name(provider), "struct", start_pos, file);
 			} else {
-				parse_item(tokens, ind, typeid, None, start_ind, ctx, provider, options)?
+				parse_item(tokens, ind, typeid, None, start_pos, ctx, provider, options)?
 			}
 		}
 		// numbers
 		Some(Token::Uint(nb, _)) => {
 			if typeid.ns != 0 {
-				return mismatch_types(&typeid.name(provider), "uint", *ind);
+				return mismatch_types(&typeid.name(provider), "uint", start_pos, file);
 			}
 			match typeid.id {
-				0x10..=0x12 | 0x14..=0x16 => parse_small_ints(*nb as i64, typeid, *ind)?,
+				0x10..=0x12 | 0x14..=0x16 => parse_small_ints(*nb as i64, typeid, start_pos, file)?,
 				0x13 | 0x1c | 1 => Value::Uint(*nb),
 				// signed int types with unsigned nb literial
 				0x17 | 0x1d => {
 					if *nb > 1 << 63 {
-						return Err(ParseError::TypeError(format!(
-							"number ({nb}) is out of range for i64 nb at {ind}",
-						)));
+						let msg = format!("number ({nb}) is out of range for i64 nb");
+						return err!(msg, start_pos, file);
 					}
 					Value::Int(*nb as i64)
 				}
 				0x18..=0x19 => Value::Float(*nb as f64),
-				_ => return mismatch_types(&typeid.name(provider), "uint", *ind),
+				DEC_TYPEID => {
+					let Ok(nb) = i64::try_from(*nb) else {
+						let msg = format!("number ({nb}) is out of range for dec");
+						return err!(msg, start_pos, file);
+					};
+					Value::Decimal(Decimal::new(nb, 0))
+				}
+				_ => return mismatch_types(&typeid.name(provider), "uint", start_pos, file),
 			}
 		}
 		Some(Token::Int(nb, _)) => {
 			if typeid.ns != 0 {
-				return mismatch_types(&typeid.name(provider), "int", *ind);
+				return mismatch_types(&typeid.name(provider), "int", start_pos, file);
 			}
 			match typeid.id {
-				0x10..=0x12 | 0x14..=0x16 => parse_small_ints(*nb as i64, typeid, *ind)?,
+				0x10..=0x12 | 0x14..=0x16 => parse_small_ints(*nb as i64, typeid, start_pos, file)?,
 				0x13 | 0x1c => {
 					// unsigned int types with signed nb literial
 					if *nb < 0 {
-						return Err(ParseError::TypeError(format!(
-							"number ({nb}) is out of range for u64 nb at {ind}",
-						)));
+						let msg = format!("number ({nb}) is out of range for u64 nb");
+						return err!(msg, start_pos, file);
 					}
 					Value::Uint(*nb as u64)
 				}
 				0x17 | 0x1d | 1 => Value::Int(*nb),
 				0x18..=0x19 => Value::Float(*nb as f64),
-				_ => return mismatch_types(&typeid.name(provider), "int", *ind),
+				DEC_TYPEID => Value::Decimal(Decimal::new(*nb, 0)),
+				_ => return mismatch_types(&typeid.name(provider), "int", start_pos, file),
 			}
 		}
 		// +inf / -inf
 		Some(Token::Symbol(symbol, _)) if matches!(symbol, '+' | '-') => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x18..=0x1a) {
-				mismatch_types(&typeid.name(provider), "f64", *ind)?;
+				mismatch_types(&typeid.name(provider), "f64", start_pos, file)?;
 			}
 
-			let ident = consume_ident(tokens, ind)?;
+			let ident = consume_ident(tokens, ind, file)?;
 			if ident != "inf" {
-				return Err(unexpected_token(symbol, start_ind));
+				return unexpected_token(symbol, start_pos, file);
 			}
 
 			Value::Float(if *symbol == '+' { f64::INFINITY } else { f64::NEG_INFINITY })
 		}
 		Some(Token::Float(nb, _)) => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x18..=0x19) {
-				return mismatch_types(&typeid.name(provider), "f64", *ind);
+				return mismatch_types(&typeid.name(provider), "f64", start_pos, file);
 			}
 			Value::Float(*nb)
 		}
+		Some(Token::Decimal(nb, _)) => {
+			if typeid.ns != 0 || !matches!(typeid.id, 1 | DEC_TYPEID) {
+				return mismatch_types(&typeid.name(provider), "dec", start_pos, file);
+			}
+			Value::Decimal(*nb)
+		}
 		Some(Token::BigInt(_, _)) => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x1e) {
-				return mismatch_types(&typeid.name(provider), "bint", *ind);
+				return mismatch_types(&typeid.name(provider), "bint", start_pos, file);
 			}
 			Value::BigInt(vec![])
 		}
 		// strings
 		Some(Token::Str(str, _)) => {
 			if typeid.ns != 0 || !matches!(typeid.id, 1 | 0x20) {
-				return mismatch_types(&typeid.name(provider), "str", *ind);
+				return mismatch_types(&typeid.name(provider), "str", start_pos, file);
 			}
 			Value::Str(str.clone())
 		}
-		_ => return Err(end_of_input(tokens.len())),
+		Some(Token::EOF(_)) | None => return end_of_input(file),
+		Some(token) => return unexpected_token(token, start_pos, file),
 	};
 
 	// add metadata wrapper around the value
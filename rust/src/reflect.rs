@@ -0,0 +1,287 @@
+//! read only view over declaration files, for tools generating code, documentation or schemas from them.
+//!
+//! items are listed in definition order, fields, variants and flags in tag order, and types are resolved across imports into [`TypeRef`].
+//!
+//! ## example
+//! ```
+//! let file = FileRef::new(provider.load("user.stomd").unwrap(), &provider);
+//! for item in file.items() {
+//! 	for field in item.fields() {
+//! 		let optional = if field.is_optional() { "?" } else { "" };
+//! 		match field.ty() {
+//! 			TypeRef::Builtin(ty) => println!("{}.{}{optional}: {ty}", item.name(), field.name()),
+//! 			TypeRef::Item(ty, _) => println!("{}.{}{optional}: {}", item.name(), field.name(), ty.name()),
+//! 			_ => (),
+//! 		}
+//! 	}
+//! }
+//! ```
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::{
+	DeclFile, DeclProvider, Value,
+	builtins::{
+		ANY_TYPEID, ARR_TYPEID, BUILT_INS_NAMES, FIXED_ARR_TYPEID, MAP_TYPEID, PARAM_TYPEID,
+		SET_TYPEID, TUPLE_TYPEID,
+	},
+	declaration::{DeclItem, EnumVariant, Field, TypeId},
+};
+
+pub use crate::declaration::Annotations;
+
+/// a declaration file, with the provider resolving its imports.
+#[derive(Clone, Copy)]
+pub struct FileRef<'a> {
+	file: &'a DeclFile,
+	provider: &'a dyn DeclProvider,
+}
+
+/// a struct, enum or flags declared in a file.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemRef<'a> {
+	item: &'a DeclItem,
+	file: FileRef<'a>,
+}
+
+/// kind of an [`ItemRef`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+	Struct,
+	Enum,
+	Flags,
+}
+
+/// a field of a struct or an enum variant.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldRef<'a> {
+	field: &'a Field,
+	/// the item declaring the field, holding its generic parameters
+	item: ItemRef<'a>,
+}
+
+/// a variant of an enum.
+#[derive(Debug, Clone, Copy)]
+pub struct VariantRef<'a> {
+	variant: &'a EnumVariant,
+	item: ItemRef<'a>,
+}
+
+/// a resolved type.
+#[derive(Debug, Clone)]
+pub enum TypeRef<'a> {
+	Any,
+	/// primitive builtin by name, like `u32` or `str`
+	Builtin(&'static str),
+	Arr(Box<TypeRef<'a>>),
+	/// array of fixed length
+	FixedArr(Box<TypeRef<'a>>, u16),
+	Set(Box<TypeRef<'a>>),
+	/// map with a primitive key by name
+	Map(&'static str, Box<TypeRef<'a>>),
+	Tuple(Vec<TypeRef<'a>>),
+	/// generic parameter of the containing item by name
+	Param(&'a str),
+	/// declared item with its generic arguments
+	Item(ItemRef<'a>, Vec<TypeRef<'a>>),
+}
+
+// the provider is not shown
+impl Debug for FileRef<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("FileRef")
+			.field("name", &self.file.name)
+			.field("id", &self.file.id)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<'a> FileRef<'a> {
+	/// creates a `FileRef` over a file, resolving its imports through a given provider.
+	pub fn new(file: &'a DeclFile, provider: &'a dyn DeclProvider) -> Self {
+		Self { file, provider }
+	}
+
+	pub fn name(&self) -> &'a str {
+		&self.file.name
+	}
+	/// the underlying file
+	pub fn file(&self) -> &'a DeclFile {
+		self.file
+	}
+
+	/// declared items in definition order, anonymous items are reached through the types using them
+	pub fn items(&self) -> Vec<ItemRef<'a>> {
+		let items = self.file.order.iter().map(|id| &self.file.items[id]);
+		let items = items.filter(|item| !item.is_anonymous());
+		items.map(|item| ItemRef { item, file: *self }).collect()
+	}
+	/// get an item by its qualified name
+	pub fn get(&self, name: &str) -> Option<ItemRef<'a>> {
+		self.file.get_by_name(name).map(|item| ItemRef { item, file: *self })
+	}
+
+	/// type aliases ordered by name, with their resolved type
+	pub fn aliases(&self) -> Vec<(&'a str, TypeRef<'a>)> {
+		let mut aliases: Vec<_> = self.file.aliases.iter().collect();
+		aliases.sort_by_key(|(name, _)| *name);
+		aliases
+			.into_iter()
+			.map(|(name, typeid)| (name.as_str(), self.resolve(typeid)))
+			.collect()
+	}
+	/// declared type of the root value
+	pub fn root(&self) -> Option<TypeRef<'a>> {
+		self.file.root.as_ref().map(|typeid| self.resolve(typeid))
+	}
+	/// imported files in import order
+	pub fn imports(&self) -> Vec<FileRef<'a>> {
+		let files = self.file.imports.iter().map(|id| self.provider.get(*id));
+		files.map(|file| FileRef { file, provider: self.provider }).collect()
+	}
+
+	/// resolve a type used in the file, outside of generic items
+	pub fn resolve(&self, typeid: &'a TypeId) -> TypeRef<'a> {
+		resolve(typeid, &[], *self)
+	}
+}
+
+impl<'a> ItemRef<'a> {
+	/// qualified name of the item, including its modules
+	pub fn name(&self) -> &'a str {
+		self.item.name()
+	}
+	pub fn kind(&self) -> ItemKind {
+		match self.item {
+			DeclItem::Struct { .. } => ItemKind::Struct,
+			DeclItem::Enum { .. } => ItemKind::Enum,
+			DeclItem::Flags { .. } => ItemKind::Flags,
+		}
+	}
+	pub fn typeid(&self) -> u16 {
+		self.item.typeid()
+	}
+	/// the file declaring the item
+	pub fn file(&self) -> FileRef<'a> {
+		self.file
+	}
+	/// names of the generic parameters
+	pub fn params(&self) -> &'a [String] {
+		self.item.params()
+	}
+	/// whether the item was declared inline in a type
+	pub fn is_anonymous(&self) -> bool {
+		self.item.is_anonymous()
+	}
+	pub fn annotations(&self) -> &'a Annotations {
+		self.item.annotations()
+	}
+
+	/// fields of a struct in tag order, empty for enums and flags
+	pub fn fields(&self) -> Vec<FieldRef<'a>> {
+		let DeclItem::Struct { def, .. } = self.item else { return Vec::new() };
+		let fields = def.fields.iter().flatten();
+		fields.map(|field| FieldRef { field, item: *self }).collect()
+	}
+	/// variants of an enum in tag order, empty for structs and flags
+	pub fn variants(&self) -> Vec<VariantRef<'a>> {
+		let DeclItem::Enum { variants, .. } = self.item else { return Vec::new() };
+		let variants = variants.iter().flatten();
+		variants.map(|variant| VariantRef { variant, item: *self }).collect()
+	}
+	/// flags by bit in bit order, empty for structs and enums
+	pub fn flags(&self) -> Vec<(u32, &'a str)> {
+		let DeclItem::Flags { flags, .. } = self.item else { return Vec::new() };
+		let flags = flags.iter().enumerate();
+		flags.filter_map(|(bit, flag)| Some((bit as u32, flag.as_deref()?))).collect()
+	}
+}
+
+impl<'a> FieldRef<'a> {
+	pub fn name(&self) -> &'a str {
+		&self.field.name
+	}
+	pub fn tag(&self) -> u32 {
+		self.field.tag
+	}
+	pub fn is_optional(&self) -> bool {
+		self.field.is_optional
+	}
+	/// whether the field must be present, fields with default value can be absent
+	pub fn is_required(&self) -> bool {
+		self.field.is_required()
+	}
+	/// value taken when the field is absent
+	pub fn default(&self) -> Option<&'a Value> {
+		self.field.default.as_ref()
+	}
+	pub fn annotations(&self) -> &'a Annotations {
+		&self.field.annotations
+	}
+	/// metadata declared on the type of the field
+	pub fn type_metadata(&self) -> &'a [(String, String)] {
+		self.field.typeid.metadata.as_deref().unwrap_or_default()
+	}
+
+	/// the unresolved type of the field
+	pub fn typeid(&self) -> &'a TypeId {
+		&self.field.typeid
+	}
+	/// the resolved type of the field, generic parameters are of the declaring item
+	pub fn ty(&self) -> TypeRef<'a> {
+		resolve(&self.field.typeid, self.item.params(), self.item.file)
+	}
+}
+
+impl<'a> VariantRef<'a> {
+	pub fn name(&self) -> &'a str {
+		&self.variant.name
+	}
+	pub fn tag(&self) -> u32 {
+		self.variant.tag
+	}
+	pub fn annotations(&self) -> &'a Annotations {
+		&self.variant.annotations
+	}
+	/// whether the variant has no fields
+	pub fn is_unit(&self) -> bool {
+		self.variant.def.is_none()
+	}
+	/// fields of the variant in tag order, empty for unit variants
+	pub fn fields(&self) -> Vec<FieldRef<'a>> {
+		let Some(def) = &self.variant.def else { return Vec::new() };
+		def.fields
+			.iter()
+			.flatten()
+			.map(|field| FieldRef { field, item: self.item })
+			.collect()
+	}
+}
+
+/// resolve a type used in `file`, `params` are the generic parameters in scope
+fn resolve<'a>(typeid: &'a TypeId, params: &'a [String], file: FileRef<'a>) -> TypeRef<'a> {
+	let item = || Box::new(resolve(typeid.item.as_ref().unwrap(), params, file));
+	let args = || -> Vec<_> { typeid.args.iter().map(|arg| resolve(arg, params, file)).collect() };
+
+	if typeid.ns == 0 {
+		return match typeid.id {
+			ANY_TYPEID => TypeRef::Any,
+			ARR_TYPEID => TypeRef::Arr(item()),
+			FIXED_ARR_TYPEID => TypeRef::FixedArr(item(), typeid.variant),
+			SET_TYPEID => TypeRef::Set(item()),
+			MAP_TYPEID => TypeRef::Map(BUILT_INS_NAMES[&typeid.variant], item()),
+			TUPLE_TYPEID => TypeRef::Tuple(args()),
+			PARAM_TYPEID => TypeRef::Param(&params[typeid.variant as usize]),
+			id => TypeRef::Builtin(BUILT_INS_NAMES[&id]),
+		};
+	}
+
+	// the file can be resolved before being in the provider
+	let decl_file = match typeid.ns {
+		ns if ns == file.file.id => file.file,
+		ns => file.provider.get(ns),
+	};
+	let item = decl_file.get_by_id(typeid.id).unwrap();
+	let file = FileRef { file: decl_file, provider: file.provider };
+	TypeRef::Item(ItemRef { item, file }, args())
+}
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use crate::{
+	DeclFile, DeclProvider, ParseOptions, errors::ImportError, fs_decl_provider::ProviderCache,
+	parse_declaration_file,
+};
+
+/// provider that loads declerations from in memory sources.
+///
+/// this provider holds the sources of decleration files by name, parses them lazily when loaded, and caches them for future use.
+///
+/// names are paths separated by `/`, relative imports are resolved against the name of the importing file.
+///
+/// it is thread safe, see [`DeclProvider`].
+///
+/// ## example
+/// ```
+/// let provider = SourceProvider::from_pairs(&[
+/// 	("commons.stomd", include_str!("decls/commons.stomd")),
+/// 	("users/user.stomd", include_str!("decls/users/user.stomd")),
+/// ]);
+///
+/// // loads users/user.stomd, that can import "../commons.stomd"
+/// parse("import \"users/user.stomd\" ... ", &ParseOptions::default(), &provider).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SourceProvider {
+	sources: HashMap<String, String>,
+	parse_options: ParseOptions,
+	cache: RwLock<ProviderCache<String>>,
+}
+
+impl SourceProvider {
+	/// creates a `SourceProvider` from sources by name with default options.
+	pub fn new(sources: HashMap<String, String>) -> Self {
+		SourceProvider::with_options(sources, ParseOptions::default())
+	}
+	/// creates a `SourceProvider` from sources by name with given options.
+	pub fn with_options(sources: HashMap<String, String>, parse_options: ParseOptions) -> Self {
+		let sources = sources.into_iter().map(|(name, source)| (normalize_name(&name), source));
+		Self { sources: sources.collect(), parse_options, cache: Default::default() }
+	}
+	/// creates a `SourceProvider` from `(name, source)` pairs with default options.
+	pub fn from_pairs(pairs: &[(&str, &str)]) -> Self {
+		let sources = pairs.iter().map(|(name, source)| (name.to_string(), source.to_string()));
+		SourceProvider::new(sources.collect())
+	}
+}
+impl DeclProvider for SourceProvider {
+	fn get(&self, id: u64) -> &DeclFile {
+		self.cache.read().unwrap().get(id)
+	}
+	/// gets a decleration file with a given name.
+	///
+	/// parse it if not loaded before, returns `ImportError` if not found or can not be parsed.
+	fn load<'a>(&'a self, name: &str) -> Result<&'a DeclFile, ImportError> {
+		self.load_with(name, self)
+	}
+	fn load_with<'a>(
+		&'a self, name: &str, imports: &dyn DeclProvider,
+	) -> Result<&'a DeclFile, ImportError> {
+		let name = normalize_name(name);
+		if let Some(file) = self.cache.read().unwrap().get_by_name(name.as_str()) {
+			return Ok(file);
+		}
+
+		let source = self.sources.get(&name).ok_or(ImportError::NotFound)?;
+		let file = parse_declaration_file(source, name.clone(), &self.parse_options, imports)
+			.map_err(ImportError::Parse)?;
+
+		Ok(self.cache.write().unwrap().insert(name, file))
+	}
+	fn find(&self, id: u64) -> Option<&DeclFile> {
+		self.cache.read().unwrap().find(id)
+	}
+	fn files(&self) -> Vec<&DeclFile> {
+		self.cache.read().unwrap().files()
+	}
+}
+
+/// normalize a `/` separated name, resolving "." and ".." segments
+pub(crate) fn normalize_name(name: &str) -> String {
+	let mut segments = Vec::new();
+	for segment in name.split('/') {
+		match segment {
+			"" | "." => (),
+			".." => _ = segments.pop(),
+			segment => segments.push(segment),
+		}
+	}
+	segments.join("/")
+}
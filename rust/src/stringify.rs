@@ -1,9 +1,13 @@
+mod declaration;
+
 use std::{collections::HashMap, fmt::Write};
 
 use chrono::{DateTime, TimeDelta, Timelike, Utc};
 
 use crate::{Key, Value};
 
+pub use declaration::stringify_declarations;
+
 /// options for [`stringify`]
 #[derive(Debug, Clone)]
 pub struct StringifyOptions<'a> {
@@ -51,6 +55,13 @@ macro_rules! str_commons {
 				$result.push('\"');
 			}
 			$ty::Inst(inst) => str_inst(inst, $result),
+			$ty::InstZ(inst) => {
+				$result.push_str("instZ \"");
+				$result.push_str(&inst.to_rfc3339());
+				$result.push('"');
+			}
+			$ty::Date(date) => write!($result, "date \"{date}\"").unwrap(),
+			$ty::Time(time) => write!($result, "time \"{time}\"").unwrap(),
 			$ty::Dur(dur) => str_dur(dur, $result),
 			$ty::UUID(uuid) => str_uuid(uuid, $result),
 			_ => (),
@@ -70,6 +81,7 @@ pub fn str_value(value: &Value, result: &mut String, depth: usize, options: &Str
 				result.push_str(nb.to_string().as_str())
 			}
 		}
+		Value::Decimal(dec) => write!(result, "{dec}dec").unwrap(),
 		Value::Arr(arr) => str_arr(arr, result, depth, options),
 		Value::Map(map) => str_map(map, result, depth, options),
 		Value::UnitVar(var) => result.push_str(var),
@@ -0,0 +1,627 @@
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Write,
+};
+
+use crate::{
+	DeclFile, DeclProvider, StringifyOptions,
+	builtins::{
+		ARR_TYPEID, BUILT_INS_NAMES, FIXED_ARR_TYPEID, MAP_TYPEID, PARAM_TYPEID, SET_TYPEID,
+		TUPLE_TYPEID,
+	},
+	declaration::{Annotations, DeclItem, Field, Reserved, StructDef, TypeId},
+	stringify::str_value,
+};
+
+/// variables used during declarations stringifying
+struct Ctx<'a> {
+	file: &'a DeclFile,
+	provider: &'a dyn DeclProvider,
+	options: &'a StringifyOptions<'a>,
+	/// how the items of each imported file are referenced
+	refs: HashMap<u64, Ref>,
+	/// next implicit item id
+	cur_id: u16,
+	/// anonymous items already written
+	written: HashSet<u16>,
+	/// written aliases holding anonymous items, they reference them afterward
+	aliases: Vec<(&'a TypeId, &'a str)>,
+	/// generic parameters of the current item
+	params: &'a [String],
+	result: String,
+}
+
+/// how the items of an imported file are referenced
+enum Ref {
+	/// qualified name prefixed by the namespace, empty for imports without namespace
+	Namespace(String),
+	/// local names of selected items, by qualified name
+	Selected(HashMap<String, String>),
+}
+
+/// statements of a module, ordered by the item ids they declare
+enum Entry<'a> {
+	Item(&'a DeclItem),
+	Alias(&'a str, &'a TypeId),
+	Module(&'a str),
+	Root(&'a TypeId),
+}
+
+/// stringify a [`DeclFile`] into declarations that parse back into it.
+///
+/// explicit `[tag]` and `[typeid]` are written only when they differ from the implicit ones, anonymous items are written inline, the imported files are written by their name.
+///
+/// re-exports are kept, other imports are written under a namespace only when used, items used from a file re-exported through selected items are added to its selection.
+///
+/// ## example
+/// ```
+/// let options = StringifyOptions { metadata: true, ident: "\t" };
+/// stringify_declarations(&file, &provider, &options) // => "struct A {\n\tv: vint,\n}\n"
+/// ```
+pub fn stringify_declarations(
+	file: &DeclFile, provider: &dyn DeclProvider, options: &StringifyOptions,
+) -> String {
+	let (refs, cur_id, written, aliases) = (HashMap::new(), 0, HashSet::new(), Vec::new());
+	let (params, result) = (&[][..], String::new());
+	#[rustfmt::skip]
+	let mut ctx = Ctx {
+		file, provider, options, refs, cur_id, written, aliases, params, result,
+	};
+
+	str_imports(&mut ctx);
+	str_module("", 0, &mut ctx);
+
+	ctx.result
+}
+
+/// the module of a qualified name, empty at top level
+fn module_of(name: &str) -> &str {
+	name.rsplit_once('.').map_or("", |(module, _)| module)
+}
+fn last_segment(name: &str) -> &str {
+	name.rsplit_once('.').map_or(name, |(_, last)| last)
+}
+fn is_ident(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+		&& chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn str_string(value: &str, result: &mut String) {
+	result.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\t' => result.push_str("\\t"),
+			'\0' => result.push_str("\\0"),
+			c => result.push(c),
+		}
+	}
+	result.push('"');
+}
+
+fn add_indent(depth: usize, ctx: &mut Ctx) {
+	for _ in 0..depth {
+		ctx.result.push_str(ctx.options.ident);
+	}
+}
+
+/// call `f` on a type and the types it contains
+fn walk_type(typeid: &TypeId, f: &mut impl FnMut(&TypeId)) {
+	f(typeid);
+	if let Some(item) = &typeid.item {
+		walk_type(item, f);
+	}
+	for arg in &typeid.args {
+		walk_type(arg, f);
+	}
+}
+
+/// the smallest id of the anonymous items of the file used in a type
+fn first_anonymous(typeid: &TypeId, file: &DeclFile) -> Option<u16> {
+	let mut first: Option<u16> = None;
+	walk_type(typeid, &mut |typeid| {
+		if typeid.ns == file.id && file.get_by_id(typeid.id).unwrap().is_anonymous() {
+			first = Some(first.map_or(typeid.id, |first| first.min(typeid.id)));
+		}
+	});
+	first
+}
+
+/// fields declared by the struct itself, without the ones of spread structs, ordered by tag
+fn own_fields<'a>(
+	def: &'a StructDef, file: &DeclFile, provider: &dyn DeclProvider,
+) -> Vec<&'a Field> {
+	let inherited: Vec<u32> =
+		def.bases.iter().flat_map(|base| base_tags(base, file, provider)).collect();
+	let fields = def.fields.iter().filter_map(|field| field.as_ref());
+	fields.filter(|field| !inherited.contains(&field.tag)).collect()
+}
+/// tags of the fields of a spread struct
+fn base_tags(base: &TypeId, file: &DeclFile, provider: &dyn DeclProvider) -> Vec<u32> {
+	let item = match base.ns {
+		ns if ns == file.id => file.get_by_id(base.id),
+		ns => provider.get(ns).get_by_id(base.id),
+	};
+	let Some(DeclItem::Struct { def, .. }) = item else { return Vec::new() };
+	def.fields
+		.iter()
+		.filter_map(|field| field.as_ref().map(|field| field.tag))
+		.collect()
+}
+
+/// types written in the file, including the ones of anonymous items
+fn written_types<'a>(file: &'a DeclFile, provider: &dyn DeclProvider) -> Vec<&'a TypeId> {
+	let mut types = Vec::new();
+	let add_def = |def: &'a StructDef, types: &mut Vec<&'a TypeId>| {
+		types.extend(&def.bases);
+		types.extend(own_fields(def, file, provider).into_iter().map(|field| &field.typeid));
+	};
+
+	let mut ids: Vec<_> = file.items.keys().collect();
+	ids.sort();
+	for id in ids {
+		match &file.items[id] {
+			DeclItem::Struct { def, .. } => add_def(def, &mut types),
+			DeclItem::Enum { variants, .. } => {
+				for def in variants.iter().flatten().filter_map(|variant| variant.def.as_ref()) {
+					add_def(def, &mut types);
+				}
+			}
+			DeclItem::Flags { .. } => (),
+		}
+	}
+	let mut aliases: Vec<_> = file.aliases.iter().collect();
+	aliases.sort_by_key(|(name, _)| *name);
+	types.extend(aliases.into_iter().map(|(_, typeid)| typeid));
+	types.extend(&file.root);
+	types
+}
+
+/// grammer: ["export"] "import" (str ["as" ident] | "{" (path ["as" ident]),* "}" "from" str)
+fn str_imports(ctx: &mut Ctx) {
+	let (file, provider) = (ctx.file, ctx.provider);
+	let exports = &file.exports;
+
+	// items used from each file, in order of use
+	let mut used: Vec<(u64, Vec<String>)> = Vec::new();
+	for typeid in written_types(file, provider) {
+		walk_type(typeid, &mut |typeid| {
+			if typeid.ns == 0 || typeid.ns == file.id {
+				return;
+			}
+			let name = provider.get(typeid.ns).get_by_id(typeid.id).unwrap().name().to_string();
+			match used.iter_mut().find(|(ns, _)| *ns == typeid.ns) {
+				Some((_, names)) if names.contains(&name) => (),
+				Some((_, names)) => names.push(name),
+				None => used.push((typeid.ns, vec![name])),
+			}
+		});
+	}
+
+	// imported files, then files of selected items and used files reached through re-exports
+	let mut selected: Vec<_> = exports.items.iter().collect();
+	selected.sort_by_key(|(local, _)| *local);
+	let mut files = file.imports.clone();
+	let others = selected.iter().map(|(_, (ns, _))| *ns).chain(used.iter().map(|(ns, _)| *ns));
+	for ns in others {
+		if !files.contains(&ns) {
+			files.push(ns);
+		}
+	}
+
+	// names generated namespaces can not take
+	let top_level = file.items_by_name.keys().chain(file.aliases.keys()).chain(&file.modules);
+	let mut taken: HashSet<String> =
+		top_level.filter(|name| !name.contains('.')).cloned().collect();
+	taken.extend(exports.namespaces.keys().cloned());
+	taken.extend(selected.iter().map(|(local, _)| local.to_string()));
+
+	for ns in files {
+		let name = &provider.get(ns).name;
+		let used = used.iter().find(|(file, _)| *file == ns).map(|(_, names)| names);
+
+		if let Some((namespace, _)) = exports.namespaces.iter().find(|(_, file)| **file == ns) {
+			ctx.result.push_str("export import ");
+			str_string(name, &mut ctx.result);
+			writeln!(ctx.result, " as {namespace}").unwrap();
+			ctx.refs.insert(ns, Ref::Namespace(format!("{namespace}.")));
+		} else if exports.files.contains(&ns) {
+			ctx.result.push_str("export import ");
+			str_string(name, &mut ctx.result);
+			ctx.result.push('\n');
+			ctx.refs.insert(ns, Ref::Namespace(String::new()));
+		} else if selected.iter().any(|(_, (file, _))| *file == ns) {
+			let mut items: Vec<(String, String)> = selected
+				.iter()
+				.filter(|(_, (file, _))| *file == ns)
+				.map(|(local, (_, name))| (name.clone(), local.to_string()))
+				.collect();
+			for name in used.into_iter().flatten() {
+				if !items.iter().any(|(item, _)| item == name) {
+					items.push((name.clone(), last_segment(name).to_string()));
+				}
+			}
+
+			ctx.result.push_str("export import { ");
+			for (ind, (name, local)) in items.iter().enumerate() {
+				if ind != 0 {
+					ctx.result.push_str(", ");
+				}
+				ctx.result.push_str(name);
+				if last_segment(name) != local {
+					write!(ctx.result, " as {local}").unwrap();
+				}
+			}
+			ctx.result.push_str(" } from ");
+			str_string(name, &mut ctx.result);
+			ctx.result.push('\n');
+			ctx.refs.insert(ns, Ref::Selected(items.into_iter().collect()));
+		} else if used.is_some() {
+			// namespace named after the file
+			let stem = name.rsplit('/').next().unwrap().split('.').next().unwrap();
+			let mut base: String =
+				stem.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+			if !is_ident(&base) {
+				base.insert(0, '_');
+			}
+			let mut namespace = base.clone();
+			let mut count = 2;
+			while taken.contains(&namespace) {
+				namespace = format!("{base}{count}");
+				count += 1;
+			}
+
+			ctx.result.push_str("import ");
+			str_string(name, &mut ctx.result);
+			writeln!(ctx.result, " as {namespace}").unwrap();
+			ctx.refs.insert(ns, Ref::Namespace(format!("{namespace}.")));
+			taken.insert(namespace);
+		}
+	}
+
+	if !ctx.result.is_empty() {
+		ctx.result.push('\n');
+	}
+}
+
+/// write the statements of a module, ordered by the ids of the items they declare
+fn str_module(module: &str, depth: usize, ctx: &mut Ctx) {
+	let file = ctx.file;
+	let mut entries: Vec<(Option<u16>, Entry)> = Vec::new();
+
+	let items = file.items.values();
+	let items = items.filter(|item| module_of(item.name()) == module && !item.is_anonymous());
+	entries.extend(items.map(|item| (Some(item.typeid()), Entry::Item(item))));
+
+	// modules are placed at their first item
+	for path in file.modules.iter().filter(|path| module_of(path) == module) {
+		let prefix = format!("{path}.");
+		let ids = file.items.values().filter(|item| item.name().starts_with(&prefix));
+		entries.push((ids.map(|item| item.typeid()).min(), Entry::Module(path)));
+	}
+
+	// aliases and root are placed at the anonymous items they declare, else at the end
+	let mut aliases: Vec<_> =
+		file.aliases.iter().filter(|(name, _)| module_of(name) == module).collect();
+	aliases.sort_by_key(|(name, _)| *name);
+	for (name, typeid) in aliases {
+		entries.push((first_anonymous(typeid, file), Entry::Alias(name, typeid)));
+	}
+	if let (true, Some(root)) = (module.is_empty(), &file.root) {
+		entries.push((first_anonymous(root, file), Entry::Root(root)));
+	}
+
+	entries.sort_by_key(|(id, _)| id.map_or(u32::MAX, |id| id as u32));
+
+	for (ind, (_, entry)) in entries.into_iter().enumerate() {
+		if ind != 0 {
+			ctx.result.push('\n');
+		}
+		match entry {
+			Entry::Item(item) => str_item(item, depth, ctx),
+			Entry::Alias(name, typeid) => {
+				add_indent(depth, ctx);
+				write!(ctx.result, "type {} = ", last_segment(name)).unwrap();
+				str_typeid(typeid, depth, ctx);
+				ctx.result.push('\n');
+				if first_anonymous(typeid, file).is_some() {
+					ctx.aliases.push((typeid, name));
+				}
+			}
+			Entry::Module(path) => {
+				add_indent(depth, ctx);
+				writeln!(ctx.result, "mod {} {{", last_segment(path)).unwrap();
+				str_module(path, depth + 1, ctx);
+				add_indent(depth, ctx);
+				ctx.result.push_str("}\n");
+			}
+			Entry::Root(typeid) => {
+				add_indent(depth, ctx);
+				ctx.result.push_str("root ");
+				str_typeid(typeid, depth, ctx);
+				ctx.result.push('\n');
+			}
+		}
+	}
+}
+
+/// grammer: (doc_comment | "@" ident "(" str ")")*
+fn str_annotations(annotations: &Annotations, depth: usize, ctx: &mut Ctx) {
+	for line in annotations.doc.iter().flat_map(|doc| doc.split('\n')) {
+		add_indent(depth, ctx);
+		writeln!(ctx.result, "/// {line}").unwrap();
+	}
+	if let Some(reason) = &annotations.deprecated {
+		add_indent(depth, ctx);
+		ctx.result.push_str("@deprecated(");
+		str_string(reason, &mut ctx.result);
+		ctx.result.push_str(")\n");
+	}
+	if ctx.options.metadata {
+		for (name, value) in annotations.metadata.iter().flatten() {
+			add_indent(depth, ctx);
+			write!(ctx.result, "@{name}(").unwrap();
+			str_string(value, &mut ctx.result);
+			ctx.result.push_str(")\n");
+		}
+	}
+}
+
+/// grammer: ("struct" | "enum" | "flags") ident ["<" ident ("," ident)* ">"] [typeid] body
+fn str_item<'a>(item: &'a DeclItem, depth: usize, ctx: &mut Ctx<'a>) {
+	str_annotations(item.annotations(), depth, ctx);
+	add_indent(depth, ctx);
+
+	let kind = match item {
+		DeclItem::Struct { .. } => "struct",
+		DeclItem::Enum { .. } => "enum",
+		DeclItem::Flags { .. } => "flags",
+	};
+	write!(ctx.result, "{kind} {}", last_segment(item.name())).unwrap();
+	if !item.params().is_empty() {
+		write!(ctx.result, "<{}>", item.params().join(", ")).unwrap();
+	}
+	if item.typeid() != ctx.cur_id {
+		write!(ctx.result, " [{}]", item.typeid()).unwrap();
+	}
+	ctx.cur_id = item.typeid() + 1;
+	ctx.result.push(' ');
+
+	ctx.params = item.params();
+	str_body(item, depth, ctx);
+	ctx.params = &[];
+	ctx.result.push('\n');
+}
+
+/// write the body of a struct, enum or flags
+fn str_body<'a>(item: &'a DeclItem, depth: usize, ctx: &mut Ctx<'a>) {
+	match item {
+		DeclItem::Struct { def, .. } => str_def(def, depth, ctx),
+		DeclItem::Enum { variants, reserved, .. } => {
+			ctx.result.push_str("{\n");
+			str_reserved(reserved, depth + 1, ctx);
+
+			let mut cur_tag = 0;
+			for variant in variants.iter().flatten() {
+				str_annotations(&variant.annotations, depth + 1, ctx);
+				add_indent(depth + 1, ctx);
+				str_tag(variant.tag, &mut cur_tag, Some(reserved), ctx);
+				ctx.result.push_str(&variant.name);
+				if let Some(def) = &variant.def {
+					ctx.result.push(' ');
+					str_def(def, depth + 1, ctx);
+				}
+				ctx.result.push_str(",\n");
+			}
+
+			add_indent(depth, ctx);
+			ctx.result.push('}');
+		}
+		DeclItem::Flags { flags, .. } => {
+			ctx.result.push_str("{ ");
+			let mut cur_bit = 0;
+			for (bit, flag) in flags.iter().enumerate() {
+				let Some(flag) = flag else { continue };
+				if cur_bit != 0 {
+					ctx.result.push_str(", ");
+				}
+				str_tag(bit as u32, &mut cur_bit, None, ctx);
+				ctx.result.push_str(flag);
+			}
+			ctx.result.push_str(" }");
+		}
+	}
+}
+
+/// write `[tag] ` if the tag is not the implicit one, auto tags skip reserved ones
+fn str_tag(tag: u32, cur_tag: &mut u64, reserved: Option<&Reserved>, ctx: &mut Ctx) {
+	let mut implicit = *cur_tag;
+	while reserved.is_some_and(|reserved| reserved.has_tag(implicit as u32)) {
+		implicit += 1;
+	}
+	if tag as u64 != implicit {
+		write!(ctx.result, "[{tag}] ").unwrap();
+	}
+	*cur_tag = tag as u64 + 1;
+}
+
+/// grammer: "reserved" reserve ("," reserve)* ";"
+///
+/// written before the fields, so auto tags skip them.
+fn str_reserved(reserved: &Reserved, depth: usize, ctx: &mut Ctx) {
+	if reserved.tags.is_empty() && reserved.names.is_empty() {
+		return;
+	}
+	add_indent(depth, ctx);
+	ctx.result.push_str("reserved ");
+	for (ind, (start, end)) in reserved.tags.iter().enumerate() {
+		if ind != 0 {
+			ctx.result.push_str(", ");
+		}
+		match start == end {
+			true => write!(ctx.result, "{start}").unwrap(),
+			false => write!(ctx.result, "{start}..{end}").unwrap(),
+		}
+	}
+	for (ind, name) in reserved.names.iter().enumerate() {
+		if ind != 0 || !reserved.tags.is_empty() {
+			ctx.result.push_str(", ");
+		}
+		str_string(name, &mut ctx.result);
+	}
+	ctx.result.push_str(";\n");
+}
+
+/// grammer: "{" ((field_def | spread) "," | reserved)* "}"
+fn str_def<'a>(def: &'a StructDef, depth: usize, ctx: &mut Ctx<'a>) {
+	ctx.result.push_str("{\n");
+	str_reserved(&def.reserved, depth + 1, ctx);
+
+	// spreads are written before the first field with a greater tag, keeping fields tags valid
+	let mut spreads: Vec<_> = def
+		.bases
+		.iter()
+		.map(|base| {
+			let tags = base_tags(base, ctx.file, ctx.provider);
+			(tags.into_iter().max().map_or(0, |tag| tag as u64 + 1), base)
+		})
+		.collect();
+	spreads.sort_by_key(|(end, _)| *end);
+	let mut spreads = spreads.into_iter().peekable();
+
+	let mut cur_tag = 0;
+	for field in own_fields(def, ctx.file, ctx.provider) {
+		while let Some((end, base)) = spreads.next_if(|(end, _)| *end <= field.tag as u64) {
+			str_spread(base, end, &mut cur_tag, depth + 1, ctx);
+		}
+
+		str_annotations(&field.annotations, depth + 1, ctx);
+		add_indent(depth + 1, ctx);
+		str_tag(field.tag, &mut cur_tag, Some(&def.reserved), ctx);
+		match is_ident(&field.name) {
+			true => ctx.result.push_str(&field.name),
+			false => str_string(&field.name, &mut ctx.result),
+		}
+		if field.is_optional {
+			ctx.result.push('?');
+		}
+		ctx.result.push_str(": ");
+		str_typeid(&field.typeid, depth + 1, ctx);
+		if let Some(default) = &field.default {
+			ctx.result.push_str(" = ");
+			str_value(default, &mut ctx.result, depth + 1, ctx.options);
+		}
+		ctx.result.push_str(",\n");
+	}
+	for (end, base) in spreads {
+		str_spread(base, end, &mut cur_tag, depth + 1, ctx);
+	}
+
+	add_indent(depth, ctx);
+	ctx.result.push('}');
+}
+
+/// grammer: ".." type_id
+fn str_spread<'a>(base: &'a TypeId, end: u64, cur_tag: &mut u64, depth: usize, ctx: &mut Ctx<'a>) {
+	add_indent(depth, ctx);
+	ctx.result.push_str("..");
+	str_typeid(base, depth, ctx);
+	ctx.result.push_str(",\n");
+	*cur_tag = (*cur_tag).max(end);
+}
+
+/// grammer: metadata (builtin | path ["<" type_id ("," type_id)* ">"] | "(" type_id,* ")" | anonymous_item)
+fn str_typeid<'a>(typeid: &'a TypeId, depth: usize, ctx: &mut Ctx<'a>) {
+	if ctx.options.metadata {
+		for (name, value) in typeid.metadata.iter().flatten() {
+			write!(ctx.result, "@{name}(").unwrap();
+			str_string(value, &mut ctx.result);
+			ctx.result.push_str(") ");
+		}
+	}
+
+	// builtins
+	if typeid.ns == 0 {
+		let item = typeid.item.as_deref();
+		match typeid.id {
+			ARR_TYPEID | SET_TYPEID | FIXED_ARR_TYPEID => {
+				let name = if typeid.id == SET_TYPEID { "set<" } else { "arr<" };
+				ctx.result.push_str(name);
+				str_typeid(item.unwrap(), depth, ctx);
+				if typeid.id == FIXED_ARR_TYPEID {
+					write!(ctx.result, ", {}", typeid.variant).unwrap();
+				}
+				ctx.result.push('>');
+			}
+			MAP_TYPEID => {
+				write!(ctx.result, "map<{}, ", BUILT_INS_NAMES[&typeid.variant]).unwrap();
+				str_typeid(item.unwrap(), depth, ctx);
+				ctx.result.push('>');
+			}
+			TUPLE_TYPEID => {
+				ctx.result.push('(');
+				str_args(&typeid.args, depth, ctx);
+				ctx.result.push(')');
+			}
+			PARAM_TYPEID => ctx.result.push_str(&ctx.params[typeid.variant as usize]),
+			id => ctx.result.push_str(BUILT_INS_NAMES[&id]),
+		}
+		return;
+	}
+
+	// own items
+	if typeid.ns == ctx.file.id {
+		// types of written aliases holding anonymous items are referenced by the alias
+		if first_anonymous(typeid, ctx.file).is_some_and(|id| ctx.written.contains(&id))
+			&& let Some((_, name)) = ctx.aliases.iter().find(|(alias, _)| *alias == typeid)
+		{
+			ctx.result.push_str(name);
+			return;
+		}
+
+		let item = ctx.file.get_by_id(typeid.id).unwrap();
+		if !item.is_anonymous() {
+			ctx.result.push_str(item.name());
+			str_type_args(typeid, depth, ctx);
+			return;
+		}
+
+		// anonymous items are not generic, and cant use the parent parameters
+		let kind = if let DeclItem::Struct { .. } = item { "struct " } else { "enum " };
+		ctx.result.push_str(kind);
+		ctx.written.insert(typeid.id);
+		ctx.cur_id = typeid.id + 1;
+		let params = std::mem::take(&mut ctx.params);
+		str_body(item, depth, ctx);
+		ctx.params = params;
+		return;
+	}
+
+	// imported items
+	let name = ctx.provider.get(typeid.ns).get_by_id(typeid.id).unwrap().name();
+	match &ctx.refs[&typeid.ns] {
+		Ref::Namespace(prefix) => write!(ctx.result, "{prefix}{name}").unwrap(),
+		Ref::Selected(locals) => ctx.result.push_str(&locals[name]),
+	}
+	str_type_args(typeid, depth, ctx);
+}
+
+fn str_type_args<'a>(typeid: &'a TypeId, depth: usize, ctx: &mut Ctx<'a>) {
+	if !typeid.args.is_empty() {
+		ctx.result.push('<');
+		str_args(&typeid.args, depth, ctx);
+		ctx.result.push('>');
+	}
+}
+fn str_args<'a>(args: &'a [TypeId], depth: usize, ctx: &mut Ctx<'a>) {
+	for (ind, arg) in args.iter().enumerate() {
+		if ind != 0 {
+			ctx.result.push_str(", ");
+		}
+		str_typeid(arg, depth, ctx);
+	}
+}
@@ -0,0 +1,27 @@
+use crate::{
+	VoidProvider,
+	compat::{ChangeKind, Direction, check},
+};
+
+use super::parse_decl;
+
+#[test]
+fn classifies_changes() {
+	let old = parse_decl("struct A { a: u8, b: str }\nenum E { x, y }");
+	let new = parse_decl("struct A { a: u16, [2] c?: str }\nenum E { x }\nstruct B { b: u8 }");
+	let changes = check(&old, &new, &VoidProvider {}, &VoidProvider {});
+	let find = |path: &str| changes.iter().find(|change| change.path == path).unwrap();
+
+	let removed = find("A.b");
+	assert_eq!(removed.kind, ChangeKind::FieldRemoved { tag: 1 });
+	assert!(removed.backward && !removed.forward);
+	assert!(!removed.is_breaking() && removed.breaks(Direction::Forward));
+
+	let added = find("A.c");
+	assert_eq!(added.kind, ChangeKind::FieldAdded { tag: 2 });
+	assert!(!added.breaks(Direction::Full));
+
+	assert!(find("A.a").breaks(Direction::Backward));
+	assert!(find("E.y").is_breaking());
+	assert_eq!(find("B").kind, ChangeKind::ItemAdded);
+}
@@ -0,0 +1,89 @@
+use crate::{
+	BundleProvider, DeclFile, DeclProvider, FixedSetProvider, ParseOptions, StringifyOptions,
+	VoidProvider, bundle, parse_declaration_file, reflect::FileRef, stringify_declarations,
+};
+
+use super::parse_decl;
+
+const USER: &str = "
+struct Audit {
+	[4] created_at: inst,
+	updated_by: str
+}
+/// a registered user
+struct User {
+	[1] id: u64,
+	..Audit,
+	name: str,
+	nick?: str
+}
+";
+
+#[test]
+fn spread_keeps_tags() {
+	let file = parse_decl(USER);
+	let provider = VoidProvider {};
+	let user = FileRef::new(&file, &provider).get("User").unwrap();
+	let tags: Vec<_> = user.fields().iter().map(|field| (field.name(), field.tag())).collect();
+	assert_eq!(tags, [("id", 1), ("created_at", 4), ("updated_by", 5), ("name", 6), ("nick", 7)]);
+}
+
+#[test]
+fn spread_rejects_lower_tags() {
+	let source = "struct Audit { created_at: inst }\nstruct User { [3] id: u64, ..Audit }";
+	let options = ParseOptions::default();
+	let result = parse_declaration_file(source, "file".to_string(), &options, &VoidProvider {});
+	assert!(result.is_err());
+}
+
+#[test]
+fn stray_docs_are_comments() {
+	parse_decl("/// not documenting anything\nstruct A { a: u8 }\n/// trailing");
+}
+
+#[test]
+fn stringify_round_trip() {
+	let file = parse_decl(USER);
+	let options = StringifyOptions::default();
+	let source = stringify_declarations(&file, &VoidProvider {}, &options);
+	let reparsed = parse_decl(&source);
+	assert_eq!(stringify_declarations(&reparsed, &VoidProvider {}, &options), source);
+
+	let provider = VoidProvider {};
+	let (file, reparsed) = (FileRef::new(&file, &provider), FileRef::new(&reparsed, &provider));
+	assert_eq!(tags(file), tags(reparsed));
+}
+
+/// fields of every item with their tags
+fn tags(file: FileRef<'_>) -> Vec<(String, String, u32)> {
+	let mut tags = Vec::new();
+	for item in file.items() {
+		for field in item.fields() {
+			tags.push((item.name().to_string(), field.name().to_string(), field.tag()));
+		}
+	}
+	tags
+}
+
+#[test]
+fn items_in_definition_order() {
+	let file =
+		parse_decl("struct B [5] { a: u8 }\nstruct A { b: struct { c: u8 } }\nenum C [9] { x }");
+	assert_eq!(names(&file), ["B", "A", "C"]);
+
+	// kept through bundles
+	let provider = FixedSetProvider::new(vec![file]);
+	let file = provider.load("file").unwrap();
+	let bundle = BundleProvider::new(&bundle::write(&[file], "", &provider)).unwrap();
+	assert_eq!(names(bundle.load("file").unwrap()), ["B", "A", "C"]);
+}
+
+/// names of the listed items
+fn names(file: &DeclFile) -> Vec<String> {
+	let provider = VoidProvider {};
+	FileRef::new(file, &provider)
+		.items()
+		.iter()
+		.map(|item| item.name().to_string())
+		.collect()
+}
@@ -0,0 +1,79 @@
+use crate::{
+	BundleProvider, DeclProvider, ParseOptions, SourceProvider, Value, bundle, decode, encode,
+	encode_root, parse, reflect::FileRef,
+};
+
+const CONFIG: &str = "
+enum Mode { fast, safe }
+flags Perms { read, write }
+struct Config {
+	port?: u16 = 8080,
+	host: str,
+	mode: Mode,
+	fallback: Mode = Mode.fast,
+	perms: Perms = Perms.read | Perms.write
+}
+root Config
+";
+
+/// config value typed by the root declaration of the imported file
+fn config(provider: &dyn DeclProvider) -> Value {
+	let source = "import \"config.stomd\"\n{ host: \"localhost\", mode: Mode.safe }";
+	parse(source, &ParseOptions::default(), provider).unwrap()
+}
+
+#[test]
+fn any_round_trip() {
+	let value = Value::from(vec![1, 2, 3]);
+	assert_eq!(decode(&encode(&value), &SourceProvider::from_pairs(&[])), Some(value));
+}
+
+#[test]
+fn root_round_trip() {
+	let provider = SourceProvider::from_pairs(&[("config.stomd", CONFIG)]);
+	let file = provider.load("config.stomd").unwrap();
+	let value = config(&provider);
+	for fingerprint in [false, true] {
+		let data = encode_root(&value, file, &provider, fingerprint).unwrap();
+		assert_eq!(decode(&data, &provider), Some(value.clone()));
+	}
+}
+
+#[test]
+fn moved_root_round_trip() {
+	let provider = SourceProvider::from_pairs(&[("config.stomd", CONFIG)]);
+	let file = provider.load("config.stomd").unwrap();
+	let value = config(&provider);
+	let data = encode_root(&value, file, &provider, true).unwrap();
+
+	// the type is found by its fingerprint in the loaded files
+	let moved = SourceProvider::from_pairs(&[("moved.stomd", CONFIG)]);
+	assert_eq!(decode(&data, &moved), None);
+	moved.load("moved.stomd").unwrap();
+	assert_eq!(decode(&data, &moved), Some(value.clone()));
+
+	// without a fingerprint the path is required
+	let data = encode_root(&value, file, &provider, false).unwrap();
+	assert_eq!(decode(&data, &moved), None);
+}
+
+#[test]
+fn bundle_round_trip() {
+	let provider = SourceProvider::from_pairs(&[("config.stomd", CONFIG)]);
+	let file = provider.load("config.stomd").unwrap();
+	let bundle = BundleProvider::new(&bundle::write(&[file], "", &provider)).unwrap();
+	let bundled = bundle.load("config.stomd").unwrap();
+	assert_eq!(bundled.name, file.name);
+
+	// defaults of every kind are kept
+	let defaults = |file| {
+		let config = FileRef::new(file, &provider).get("Config").unwrap();
+		config.fields().iter().map(|field| field.default().cloned()).collect::<Vec<_>>()
+	};
+	assert_eq!(defaults(bundled), defaults(file));
+
+	// data encoded against the sources decodes against the bundle
+	let value = config(&provider);
+	let data = encode_root(&value, file, &provider, true).unwrap();
+	assert_eq!(decode(&data, &bundle), Some(value));
+}
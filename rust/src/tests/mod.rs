@@ -0,0 +1,12 @@
+mod compat;
+mod declarations;
+mod encoding;
+mod values;
+
+use crate::{DeclFile, ParseOptions, VoidProvider, parse_declaration_file};
+
+/// parse a declaration file that imports nothing
+fn parse_decl(source: &str) -> DeclFile {
+	parse_declaration_file(source, "file".to_string(), &ParseOptions::default(), &VoidProvider {})
+		.unwrap()
+}
@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{ParseOptions, SourceProvider, Value, VoidProvider, parse, parse_as};
+
+use super::parse_decl;
+
+fn parse_value(source: &str) -> Option<Value> {
+	parse(source, &ParseOptions::default(), &VoidProvider {}).ok()
+}
+
+#[test]
+fn fixed_arr_prefix_checks_length() {
+	let value = parse_value("arr<u8, 3> [255, 0, 128]").unwrap();
+	assert_eq!(value, Value::from(vec![255u64, 0, 128]));
+	assert!(parse_value("arr<u8, 3> [255, 0]").is_none());
+}
+
+#[test]
+fn tuple_prefix() {
+	let value = parse_value("(u8, str) [1, \"a\"]").unwrap();
+	assert_eq!(value, Value::Arr(vec![Value::Uint(1), Value::from("a")]));
+	assert!(parse_value("(u8, str) [1]").is_none());
+	assert!(parse_value("(u8, str) [\"a\", 1]").is_none());
+
+	// plain tuples are not prefixes
+	assert!(parse_value("(1, \"a\")").is_some());
+}
+
+#[test]
+fn set_compares_instz_by_instant() {
+	let source = "set<instZ> [instZ \"2024-01-01T02:00:00+02:00\", instZ \"2024-01-01T00:00:00Z\"]";
+	assert!(parse_value(source).is_none());
+	assert!(parse_value("set<instZ> [instZ \"2024-01-01T00:00:00Z\"]").is_some());
+}
+
+#[test]
+fn parse_as_rejects_unbound_generics() {
+	let file = parse_decl("struct Page<T> { items: arr<T> }");
+	let result =
+		parse_as("{ items: [] }", (&file, "Page"), &ParseOptions::default(), &VoidProvider {});
+	let msg = result.unwrap_err().to_string();
+	assert!(msg.starts_with("generic type \"Page\" requires 1 type arguments"));
+}
+
+#[test]
+fn warnings_are_collected() {
+	let decl = "struct User { name: str, @deprecated(\"use name\") nick?: str }\nroot User";
+	let provider = SourceProvider::from_pairs(&[("user.stomd", decl)]);
+	let warnings = Arc::new(Mutex::new(Vec::new()));
+	let collected = warnings.clone();
+	let on_warning = move |msg: &str| collected.lock().unwrap().push(msg.to_string());
+	let options = ParseOptions { on_warning: Some(Arc::new(on_warning)), ..Default::default() };
+
+	let source = "import \"user.stomd\"\n{ name: \"a\", nick: \"b\" }";
+	parse(source, &options, &provider).unwrap();
+	let warnings = warnings.lock().unwrap();
+	assert_eq!(warnings.len(), 1);
+	assert!(warnings[0].starts_with("field \"nick\" is deprecated: use name"));
+}
@@ -1,5 +1,5 @@
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	fmt::{self, Display, Formatter, Write},
 	hash::{self, Hash},
 	ops::{Index, IndexMut},
@@ -7,7 +7,7 @@ use std::{
 	sync::LazyLock,
 };
 
-use chrono::{DateTime, TimeDelta, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeDelta, Utc};
 
 use crate::stringify::{str_dur_val, str_uuid_val};
 
@@ -82,10 +82,18 @@ pub enum Value {
 	BigInt(Vec<u8>),
 	/// floating point value, types: `f32`, `f64`
 	Float(f64),
+	/// fixed point decimal value, types: `dec`.
+	Decimal(Decimal),
 	/// string value, types: `str`, unit enums.
 	Str(String),
 	/// instance value, types: `inst`, `instN`.
 	Inst(DateTime<Utc>),
+	/// instance value with offset, types: `instZ`.
+	InstZ(DateTime<FixedOffset>),
+	/// calendar date value, types: `date`.
+	Date(NaiveDate),
+	/// time of day value, types: `time`.
+	Time(NaiveTime),
 	/// duration value, types: `dur`.
 	Dur(TimeDelta),
 	/// uuid value, types: `uuid`.
@@ -123,12 +131,59 @@ pub enum Key {
 	Str(String),
 	/// instance value, types: `inst`, `instN`.
 	Inst(DateTime<Utc>),
+	/// instance value with offset, types: `instZ`.
+	InstZ(DateTime<FixedOffset>),
+	/// calendar date value, types: `date`.
+	Date(NaiveDate),
+	/// time of day value, types: `time`.
+	Time(NaiveTime),
 	/// duration value, types: `dur`.
 	Dur(TimeDelta),
 	/// uuid value, types: `uuid`.
 	UUID([u8; 16]),
 }
 
+/// fixed point decimal number, equal to `value * 10^-scale`.
+///
+/// decimals are exact, usefull for monetary values, they are compared by their representation, so `1.0dec != 1.00dec`.
+///
+/// ## example
+/// ```
+/// let price = Decimal::new(1234, 2); // => 12.34
+/// price.to_string(); // => "12.34"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Decimal {
+	/// the unscaled integer value
+	pub value: i64,
+	/// count of digits after the decimal point
+	pub scale: u8,
+}
+
+impl Decimal {
+	pub fn new(value: i64, scale: u8) -> Self {
+		Self { value, scale }
+	}
+	/// convert into the nearest `f64`, lossy.
+	pub fn to_f64(&self) -> f64 {
+		self.value as f64 / 10f64.powi(self.scale as i32)
+	}
+}
+
+impl Display for Decimal {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let sign = if self.value < 0 { "-" } else { "" };
+		let scale = self.scale as usize;
+		if scale == 0 {
+			return write!(f, "{sign}{}", self.value.unsigned_abs());
+		}
+		// pad with zeros to have at least 1 integer digit
+		let digits = format!("{:0>1$}", self.value.unsigned_abs(), scale + 1);
+		let (int, frac) = digits.split_at(digits.len() - scale);
+		write!(f, "{sign}{int}.{frac}")
+	}
+}
+
 impl Default for Value {
 	fn default() -> Self {
 		Value::Uint(0)
@@ -175,6 +230,7 @@ impl Value {
 	is_impl!(Value, (Bool, is_bool), (Uint, is_uint), (Int, is_int), (Str, is_str));
 	is_impl!(Value, (BigInt, is_bigint), (Float, is_float), (Inst, is_inst), (Dur, is_dur));
 	is_impl!(Value, (UUID, is_uuid), (Arr, is_array), (Map, is_map), (UnitVar, is_unit_variant));
+	is_impl!(Value, (Decimal, is_decimal), (InstZ, is_instz), (Date, is_date), (Time, is_time));
 
 	/// whether the inner value is an enum
 	pub fn is_enum(&self) -> bool {
@@ -197,6 +253,7 @@ impl Value {
 impl Key {
 	is_impl!(Key, (Bool, is_bool), (Uint, is_uint), (Int, is_int), (Str, is_str));
 	is_impl!(Key, (BigInt, is_bigint), (Inst, is_inst), (Dur, is_dur), (UUID, is_uuid));
+	is_impl!(Key, (InstZ, is_instz), (Date, is_date), (Time, is_time));
 }
 
 impl Value {
@@ -250,6 +307,9 @@ impl TryFrom<Value> for Key {
 			Value::BigInt(i) => Ok(Key::BigInt(i)),
 			Value::Str(s) => Ok(Key::Str(s)),
 			Value::Inst(i) => Ok(Key::Inst(i)),
+			Value::InstZ(i) => Ok(Key::InstZ(i)),
+			Value::Date(d) => Ok(Key::Date(d)),
+			Value::Time(t) => Ok(Key::Time(t)),
 			Value::Dur(d) => Ok(Key::Dur(d)),
 			Value::UUID(u) => Ok(Key::UUID(u)),
 			_ => Err(()),
@@ -265,6 +325,9 @@ impl From<Key> for Value {
 			Key::BigInt(i) => Value::BigInt(i),
 			Key::Str(s) => Value::Str(s),
 			Key::Inst(i) => Value::Inst(i),
+			Key::InstZ(i) => Value::InstZ(i),
+			Key::Date(d) => Value::Date(d),
+			Key::Time(t) => Value::Time(t),
 			Key::Dur(d) => Value::Dur(d),
 			Key::UUID(u) => Value::UUID(u),
 		}
@@ -290,7 +353,8 @@ macro_rules! from_impl {
 
 from_impl!(Value, (bool, Bool), (i64, Int), (u64, Uint), (f64, Float));
 from_impl!(Value, (String, Str), (DateTime<Utc>, Inst), (TimeDelta, Dur));
-from_impl!(Value, ([u8; 16], UUID));
+from_impl!(Value, ([u8; 16], UUID), (Decimal, Decimal));
+from_impl!(Value, (DateTime<FixedOffset>, InstZ), (NaiveDate, Date), (NaiveTime, Time));
 
 from_impl!(Value, Uint, u64, [u8, u16, u32, usize]);
 from_impl!(Value, Int, i64, [i8, i16, i32, isize]);
@@ -298,6 +362,7 @@ from_impl!(Value, Float, f64, [f32]);
 
 from_impl!(Key, (bool, Bool), (i64, Int), (u64, Uint), (String, Str));
 from_impl!(Key, (DateTime<Utc>, Inst), (TimeDelta, Dur), ([u8; 16], UUID));
+from_impl!(Key, (DateTime<FixedOffset>, InstZ), (NaiveDate, Date), (NaiveTime, Time));
 
 from_impl!(Key, Uint, u64, [u8, u16, u32, usize]);
 from_impl!(Key, Int, i64, [i8, i16, i32, isize]);
@@ -323,6 +388,12 @@ impl<K: Into<Key>, V: Into<Value>> From<HashMap<K, V>> for Value {
 		Value::Map(Box::new(m.into_iter().map(|(k, v)| (k.into(), v.into())).collect()))
 	}
 }
+// sets are stored as arrays
+impl<T: Into<Value>> From<HashSet<T>> for Value {
+	fn from(s: HashSet<T>) -> Self {
+		Value::Arr(s.into_iter().map(|v| v.into()).collect())
+	}
+}
 
 macro_rules! try_into_impl {
 	($enum:ident, $(($ty:ty, $var:ident)),+) => {
@@ -353,12 +424,14 @@ macro_rules! try_into_int_impl {
 }
 try_into_impl!(Value, (bool, Bool), (u64, Uint), (i64, Int), (f64, Float), (f32, Float));
 try_into_impl!(Value, (String, Str), (DateTime<Utc>, Inst), (TimeDelta, Dur));
-try_into_impl!(Value, ([u8; 16], UUID));
+try_into_impl!(Value, ([u8; 16], UUID), (Decimal, Decimal));
+try_into_impl!(Value, (DateTime<FixedOffset>, InstZ), (NaiveDate, Date), (NaiveTime, Time));
 
 try_into_int_impl!(Value, [u8, u16, u32, usize, i8, i16, i32, isize]);
 
 try_into_impl!(Key, (bool, Bool), (u64, Uint), (i64, Int), (String, Str));
 try_into_impl!(Key, (DateTime<Utc>, Inst), (TimeDelta, Dur), ([u8; 16], UUID));
+try_into_impl!(Key, (DateTime<FixedOffset>, InstZ), (NaiveDate, Date), (NaiveTime, Time));
 try_into_int_impl!(Key, [u8, u16, u32, usize, i8, i16, i32, isize]);
 
 impl<T> TryInto<Vec<T>> for Value
@@ -399,6 +472,57 @@ where
 		}
 	}
 }
+impl<T> TryInto<HashSet<T>> for Value
+where
+	Value: TryInto<T>,
+	T: Eq + hash::Hash,
+{
+	type Error = ();
+	fn try_into(self) -> Result<HashSet<T>, Self::Error> {
+		match self {
+			Value::Arr(v) => {
+				let mut set = HashSet::<T>::with_capacity(v.len());
+				for item in v {
+					if !set.insert(item.try_into().map_err(|_| ())?) {
+						return Err(());
+					}
+				}
+				Ok(set)
+			}
+			_ => Err(()),
+		}
+	}
+}
+
+// tuples are stored as arrays
+macro_rules! tuple_impl {
+	($(($($ty:ident $ind:tt),+)),+) => {
+		$(impl<$($ty: Into<Value>),+> From<($($ty,)+)> for Value {
+			fn from(v: ($($ty,)+)) -> Self {
+				Value::Arr(vec![$(v.$ind.into()),+])
+			}
+		}
+		impl<$($ty),+> TryInto<($($ty,)+)> for Value
+		where
+			$(Value: TryInto<$ty>),+
+		{
+			type Error = ();
+			fn try_into(self) -> Result<($($ty,)+), Self::Error> {
+				let Value::Arr(arr) = self else { return Err(()) };
+				let mut items = arr.into_iter();
+				let tuple =
+					($(TryInto::<$ty>::try_into(items.next().ok_or(())?).map_err(|_| ())?,)+);
+				if items.next().is_some() {
+					return Err(());
+				}
+				Ok(tuple)
+			}
+		})+
+	};
+}
+tuple_impl!((A 0), (A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+tuple_impl!((A 0, B 1, C 2, D 3, E 4), (A 0, B 1, C 2, D 3, E 4, F 5));
+tuple_impl!((A 0, B 1, C 2, D 3, E 4, F 5, G 6), (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7));
 
 impl Value {
 	/// cast value into `T`
@@ -459,6 +583,8 @@ impl Value {
 	as_impl!(Value, (bool, as_bool, Bool), (i64, as_int, Int), (u64, as_uint, Uint));
 	as_impl!(Value, (f64, as_float, Float), (DateTime<Utc>, as_inst, Inst));
 	as_impl!(Value, (TimeDelta, as_dur, Dur), ([u8; 16], as_uuid, UUID));
+	as_impl!(Value, (Decimal, as_decimal, Decimal), (DateTime<FixedOffset>, as_instz, InstZ));
+	as_impl!(Value, (NaiveDate, as_date, Date), (NaiveTime, as_time, Time));
 	as_ref_impl!(Value, (str, as_str, Str), ([Value], as_slice, Arr));
 	as_ref_impl!(Value, ([u8], as_bigint, BigInt), (HashMap<Key, Value>, as_map, Map));
 	as_mut_impl!(Value, (Vec<Value>, as_vec_mut, Arr), (HashMap<Key, Value>, as_map_mut, Map));
@@ -470,6 +596,8 @@ impl Value {
 impl Key {
 	as_impl!(Key, (bool, as_bool, Bool), (i64, as_int, Int), (DateTime<Utc>, as_inst, Inst));
 	as_impl!(Key, (TimeDelta, as_dur, Dur), (u64, as_uint, Uint), ([u8; 16], as_uuid, UUID));
+	as_impl!(Key, (DateTime<FixedOffset>, as_instz, InstZ), (NaiveDate, as_date, Date));
+	as_impl!(Key, (NaiveTime, as_time, Time));
 	as_ref_impl!(Key, (str, as_str, Str), ([u8], as_bigint, BigInt));
 }
 
@@ -482,6 +610,9 @@ impl PartialEq<Key> for Value {
 			(Value::BigInt(a), Key::BigInt(b)) => a == b,
 			(Value::Str(a), Key::Str(b)) => a == b,
 			(Value::Inst(a), Key::Inst(b)) => a == b,
+			(Value::InstZ(a), Key::InstZ(b)) => a == b,
+			(Value::Date(a), Key::Date(b)) => a == b,
+			(Value::Time(a), Key::Time(b)) => a == b,
 			(Value::Dur(a), Key::Dur(b)) => a == b,
 			(Value::UUID(a), Key::UUID(b)) => a == b,
 			_ => false,
@@ -525,12 +656,14 @@ macro_rules! eq_int_impl {
 }
 
 eq_impl!(Value, (bool, Bool), (DateTime<Utc>, Inst), (TimeDelta, Dur), (f64, Float));
-eq_impl!(Value, (&str, Str), (String, Str));
+eq_impl!(Value, (&str, Str), (String, Str), (Decimal, Decimal));
+eq_impl!(Value, (DateTime<FixedOffset>, InstZ), (NaiveDate, Date), (NaiveTime, Time));
 
 eq_int_impl!(Value, [u8, u16, u32, u64, usize]);
 eq_int_impl!(Value, [i8, i16, i32, i64, isize]);
 
 eq_impl!(Key, (bool, Bool), (DateTime<Utc>, Inst), (TimeDelta, Dur), (String, Str), (&str, Str));
+eq_impl!(Key, (DateTime<FixedOffset>, InstZ), (NaiveDate, Date), (NaiveTime, Time));
 
 eq_int_impl!(Key, [u8, u16, u32, u64, usize]);
 eq_int_impl!(Key, [i8, i16, i32, i64, isize]);
@@ -628,6 +761,9 @@ macro_rules! impl_display_commons {
 			$enum::Uint(nb) => return write!($f, "{nb}"),
 			$enum::Str(str) => return write!($f, "\"{}\"", str.replace('"', "\\\"")),
 			$enum::Inst(inst) => return write!($f, "{}", inst.to_rfc3339()),
+			$enum::InstZ(inst) => return write!($f, "{}", inst.to_rfc3339()),
+			$enum::Date(date) => return write!($f, "{date}"),
+			$enum::Time(time) => return write!($f, "{time}"),
 			$enum::Dur(dur) => {
 				str_dur_val(dur, &mut res);
 				return write!($f, "{res}");
@@ -647,6 +783,7 @@ impl Display for Value {
 		impl_display_commons!(self, Value, f);
 		match self {
 			Value::Float(nb) => write!(f, "{nb}"),
+			Value::Decimal(dec) => write!(f, "{dec}dec"),
 			Value::UnitVar(var) => write!(f, "{var}"),
 			Value::Arr(arr) => {
 				f.write_char('[')?;